@@ -0,0 +1,265 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, Ident, LitInt, LitStr, Token, Visibility};
+
+use crate::map::{self, MapArgs};
+use crate::rle;
+
+pub struct Assets {
+    attrs: Vec<syn::Attribute>,
+    vis: Visibility,
+    name: Ident,
+    assets: Vec<Asset>,
+}
+
+enum Asset {
+    Sprite {
+        name: Ident,
+        args: SpriteArgs,
+    },
+    RleSprite {
+        name: Ident,
+        args: SpriteArgs,
+    },
+    Map {
+        name: Ident,
+        args: MapArgs,
+    },
+    Sound {
+        name: Ident,
+        expr: syn::Expr,
+    },
+    Bytes {
+        name: Ident,
+        path: LitStr,
+    },
+}
+
+impl Parse for Assets {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![mod]>()?;
+        let name = input.parse()?;
+        let content;
+        braced!(content in input);
+        let mut assets = Vec::new();
+        while !content.is_empty() {
+            assets.push(content.parse()?);
+        }
+        Ok(Assets { attrs, vis, name, assets })
+    }
+}
+
+impl Parse for Asset {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let kind: Ident = input.parse()?;
+        let name: Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let asset = if kind == "sprite" {
+            Asset::Sprite { name, args: input.parse()? }
+        } else if kind == "rle_sprite" {
+            Asset::RleSprite { name, args: input.parse()? }
+        } else if kind == "map" {
+            Asset::Map { name, args: input.parse()? }
+        } else if kind == "sound" {
+            Asset::Sound { name, expr: input.parse()? }
+        } else if kind == "bytes" {
+            Asset::Bytes { name, path: input.parse()? }
+        } else {
+            return Err(syn::Error::new(
+                kind.span(),
+                "Expected `sprite`, `rle_sprite`, `map`, `sound`, or `bytes`",
+            ));
+        };
+        input.parse::<Token![;]>()?;
+        Ok(asset)
+    }
+}
+
+/// The path, size, and flags of a file of raw sprite data
+pub struct SpriteArgs {
+    path: LitStr,
+    width: LitInt,
+    height: LitInt,
+    flags: Vec<Ident>,
+}
+
+impl Parse for SpriteArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let width = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let height = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let mut flags = vec![input.parse()?];
+        while input.peek(Token![|]) {
+            input.parse::<Token![|]>()?;
+            flags.push(input.parse()?);
+        }
+        Ok(SpriteArgs { path, width, height, flags })
+    }
+}
+
+struct LoadedSprite {
+    bytes: Vec<u8>,
+    // The bytes the pixels take up, which may be fewer than the file has
+    needed: usize,
+    full: LitStr,
+    width: u32,
+    height: u32,
+    flags: TokenStream,
+}
+
+impl SpriteArgs {
+    fn load(&self) -> syn::Result<LoadedSprite> {
+        let (bytes, full) = read(&self.path)?;
+        let width: u32 = self.width.base10_parse()?;
+        let height: u32 = self.height.base10_parse()?;
+        let bpp = if self.flags.iter().any(|f| f == "TWO_BPP") { 2 } else { 1 };
+        let needed = ((width * height * bpp) as usize).div_ceil(8);
+        if bytes.len() < needed {
+            return Err(syn::Error::new(
+                self.path.span(),
+                format!("A {}x{} sprite needs {} bytes, but the file has {}", width, height, needed, bytes.len()),
+            ));
+        }
+        // With `union` rather than `|`, so it works in constants
+        let (first, rest) = (&self.flags[0], &self.flags[1..]);
+        let flags = quote!(::sw4::SpriteFlags::#first #(.union(::sw4::SpriteFlags::#rest))*);
+        Ok(LoadedSprite { bytes, needed, full, width, height, flags })
+    }
+}
+
+/// An `RleSprite` from a file of raw sprite data, compressed at compile time
+pub fn include_rle_sprite(args: SpriteArgs) -> syn::Result<TokenStream> {
+    let LoadedSprite { bytes, needed, full, width, height, flags } = args.load()?;
+    let packed = rle::encode(&bytes[..needed]);
+    Ok(quote! {
+        {
+            // So the sprite is rebuilt when the file changes
+            const _: &[u8] = include_bytes!(#full);
+            ::sw4::rle::RleSprite::new(&[#(#packed),*], #width, #height, #flags)
+        }
+    })
+}
+
+fn read(path: &LitStr) -> syn::Result<(Vec<u8>, LitStr)> {
+    let full = map::crate_path(path);
+    let bytes = std::fs::read(&full).map_err(|e| {
+        syn::Error::new(path.span(), format!("Couldn't read {}: {}", full.display(), e))
+    })?;
+    Ok((bytes, map::path_lit(&full)))
+}
+
+pub fn assets(input: Assets) -> syn::Result<TokenStream> {
+    let Assets { attrs, vis, name, assets } = input;
+    let mut size = 0usize;
+    let mut puts = Vec::new();
+    let mut includes = Vec::new();
+    let mut accessors = Vec::new();
+
+    for asset in &assets {
+        let start = size;
+        match asset {
+            Asset::Sprite { name, args } => {
+                let LoadedSprite { bytes, full, width, height, flags, .. } = args.load()?;
+                size += bytes.len();
+                let end = size;
+                puts.push(quote!(let blob = ::sw4::assets::__put(blob, #start, &[#(#bytes),*]);));
+                includes.push(full);
+                accessors.push(quote! {
+                    pub fn #name() -> ::sw4::Sprite<'static> {
+                        ::sw4::Sprite::new(&BLOB[#start..#end], #width, #height, #flags)
+                    }
+                });
+            }
+            Asset::RleSprite { name, args } => {
+                let LoadedSprite { bytes, needed, full, width, height, flags } = args.load()?;
+                let packed = rle::encode(&bytes[..needed]);
+                size += packed.len();
+                let end = size;
+                puts.push(quote!(let blob = ::sw4::assets::__put(blob, #start, &[#(#packed),*]);));
+                includes.push(full);
+                accessors.push(quote! {
+                    pub fn #name() -> ::sw4::rle::RleSprite<'static> {
+                        ::sw4::rle::RleSprite::new(&BLOB[#start..#end], #width, #height, #flags)
+                    }
+                });
+            }
+            Asset::Map { name, args } => {
+                let loaded = map::load(args)?;
+                let tiles = &loaded.tiles;
+                size += tiles.len();
+                let end = size;
+                let width = loaded.width as u32;
+                let height = loaded.height as u32;
+                let compressed = loaded.compressed;
+                let spawns = map::spawns_tokens(&loaded.spawns);
+                puts.push(quote!(let blob = ::sw4::assets::__put(blob, #start, &[#(#tiles),*]);));
+                includes.push(map::path_lit(&map::crate_path(&args.path)));
+                accessors.push(quote! {
+                    pub fn #name() -> ::sw4::tilemap::MapData {
+                        ::sw4::tilemap::MapData {
+                            width: #width,
+                            height: #height,
+                            tiles: &BLOB[#start..#end],
+                            compressed: #compressed,
+                            spawns: #spawns,
+                        }
+                    }
+                });
+            }
+            Asset::Sound { name, expr } => {
+                size += 11;
+                puts.push(quote! {
+                    let blob = ::sw4::assets::__put(blob, #start, &::sw4::Sound::to_bytes(&(#expr)));
+                });
+                accessors.push(quote! {
+                    pub fn #name() -> ::sw4::Sound {
+                        let mut bytes = [0; ::sw4::Sound::BYTES];
+                        bytes.copy_from_slice(&BLOB[#start..#start + ::sw4::Sound::BYTES]);
+                        ::sw4::Sound::from_bytes(&bytes)
+                    }
+                });
+            }
+            Asset::Bytes { name, path } => {
+                let (bytes, full) = read(path)?;
+                size += bytes.len();
+                let end = size;
+                puts.push(quote!(let blob = ::sw4::assets::__put(blob, #start, &[#(#bytes),*]);));
+                includes.push(full);
+                accessors.push(quote! {
+                    pub fn #name() -> &'static [u8] {
+                        &BLOB[#start..#end]
+                    }
+                });
+            }
+        }
+    }
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis mod #name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// The total size of every asset, in bytes
+            pub const SIZE: usize = #size;
+
+            /// Every asset, packed together
+            pub static BLOB: [u8; SIZE] = {
+                let blob = [0; SIZE];
+                #(#puts)*
+                blob
+            };
+
+            // So the assets are rebuilt when their files change
+            #(const _: &[u8] = include_bytes!(#includes);)*
+
+            #(#accessors)*
+        }
+    })
+}
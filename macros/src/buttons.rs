@@ -0,0 +1,180 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{parenthesized, Expr, Ident, Token};
+
+pub struct ButtonMatch {
+    source: Expr,
+    // `None` for `_`
+    arms: Vec<(Option<Pattern>, Expr)>,
+}
+
+enum Pattern {
+    // Held down, or just pressed
+    Button(Ident, bool),
+    All(Vec<Pattern>),
+    Any(Vec<Pattern>),
+}
+
+const BUTTONS: [&str; 6] = ["X", "Z", "Left", "Right", "Up", "Down"];
+
+impl Pattern {
+    fn parse_any(input: ParseStream, just: bool) -> syn::Result<Self> {
+        let mut any = vec![Self::parse_all(input, just)?];
+        while input.peek(Token![|]) && !input.peek(Token![||]) {
+            input.parse::<Token![|]>()?;
+            any.push(Self::parse_all(input, just)?);
+        }
+        Ok(if any.len() == 1 { any.remove(0) } else { Self::Any(any) })
+    }
+
+    fn parse_all(input: ParseStream, just: bool) -> syn::Result<Self> {
+        let mut all = vec![Self::parse_one(input, just)?];
+        while input.peek(Token![+]) {
+            input.parse::<Token![+]>()?;
+            all.push(Self::parse_one(input, just)?);
+        }
+        Ok(if all.len() == 1 { all.remove(0) } else { Self::All(all) })
+    }
+
+    fn parse_one(input: ParseStream, just: bool) -> syn::Result<Self> {
+        if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            return Self::parse_any(&content, just);
+        }
+        let name: Ident = input.parse()?;
+        if name == "just" && input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            return Self::parse_any(&content, true);
+        }
+        if !BUTTONS.iter().any(|b| name == b) {
+            return Err(syn::Error::new(
+                name.span(),
+                "Expected a button (`X`, `Z`, `Left`, `Right`, `Up`, or `Down`), or `just(...)`",
+            ));
+        }
+        Ok(Self::Button(name, just))
+    }
+
+    // Whether any button is checked as just pressed, or as held
+    fn uses(&self, just: bool) -> bool {
+        match self {
+            Self::Button(_, j) => *j == just,
+            Self::All(patterns) | Self::Any(patterns) => patterns.iter().any(|p| p.uses(just)),
+        }
+    }
+
+    // Buttons combined with `+` are checked together with a mask of their bits
+    fn condition(&self) -> TokenStream {
+        let mask = |buttons: &[&Ident], bits: TokenStream| {
+            quote!((#bits & (0 #(| ::sw4::Button::#buttons.bit())*)) == (0 #(| ::sw4::Button::#buttons.bit())*))
+        };
+        match self {
+            Self::Button(name, just) => mask(&[name], bits(*just)),
+            Self::All(patterns) => {
+                let mut held = Vec::new();
+                let mut pressed = Vec::new();
+                let mut rest = Vec::new();
+                for pattern in patterns {
+                    match pattern {
+                        Self::Button(name, false) => held.push(name),
+                        Self::Button(name, true) => pressed.push(name),
+                        _ => rest.push(pattern.condition()),
+                    }
+                }
+                if !held.is_empty() {
+                    rest.insert(0, mask(&held, bits(false)));
+                }
+                if !pressed.is_empty() {
+                    rest.insert(0, mask(&pressed, bits(true)));
+                }
+                quote!((#(#rest)&&*))
+            }
+            Self::Any(patterns) => {
+                let any = patterns.iter().map(Self::condition);
+                quote!((#(#any)||*))
+            }
+        }
+    }
+}
+
+// The names are odd so they can't clash with anything in the arms
+fn bits(just: bool) -> TokenStream {
+    if just {
+        quote!(__sw4_just_pressed)
+    } else {
+        quote!(__sw4_held)
+    }
+}
+
+impl Parse for ButtonMatch {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let source = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let content;
+        syn::braced!(content in input);
+        let mut arms = Vec::new();
+        while !content.is_empty() {
+            let pattern = if content.peek(Token![_]) {
+                content.parse::<Token![_]>()?;
+                None
+            } else {
+                Some(Pattern::parse_any(&content, false)?)
+            };
+            content.parse::<Token![=>]>()?;
+            let body: Expr = content.parse()?;
+            // Like `match`, blocks don't need a comma after them
+            if content.peek(Token![,]) || (!matches!(body, Expr::Block(_)) && !content.is_empty()) {
+                content.parse::<Token![,]>()?;
+            }
+            arms.push((pattern, body));
+        }
+        Ok(Self { source, arms })
+    }
+}
+
+pub fn buttons(input: ButtonMatch) -> syn::Result<TokenStream> {
+    let source = &input.source;
+    let mut branches = Vec::new();
+    let mut fallback = None;
+    for (pattern, body) in &input.arms {
+        if fallback.is_some() {
+            return Err(syn::Error::new_spanned(body, "Nothing after `_` can match"));
+        }
+        match pattern {
+            Some(pattern) => {
+                let condition = pattern.condition();
+                let body = block(body);
+                branches.push(quote!(if #condition #body));
+            }
+            None => fallback = Some(body),
+        }
+    }
+    let fallback = fallback.map_or_else(|| quote!({}), block);
+    let patterns = input.arms.iter().filter_map(|(p, _)| p.as_ref());
+    let get = |just: bool, method: TokenStream| {
+        let bits = bits(just);
+        patterns.clone().any(|p| p.uses(just)).then(|| quote!(let #bits = __sw4_source.#method().bits();))
+    };
+    let held = get(false, quote!(held_buttons));
+    let just_pressed = get(true, quote!(just_pressed_buttons));
+    Ok(quote! {
+        {
+            #[allow(unused_imports)]
+            use ::sw4::input::ButtonState as _;
+            let __sw4_source = &(#source);
+            #held
+            #just_pressed
+            #(#branches else)* #fallback
+        }
+    })
+}
+
+fn block(body: &Expr) -> TokenStream {
+    match body {
+        Expr::Block(_) => quote!(#body),
+        _ => quote!({ #body }),
+    }
+}
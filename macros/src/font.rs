@@ -0,0 +1,276 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitInt, LitStr, Token};
+
+use crate::{map, png};
+
+pub struct FontArgs {
+    path: LitStr,
+    chars: Option<LitStr>,
+    width: Option<LitInt>,
+    height: Option<LitInt>,
+    spacing: Option<LitInt>,
+    line_spacing: Option<LitInt>,
+    proportional: bool,
+}
+
+impl Parse for FontArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = FontArgs {
+            path: input.parse()?,
+            chars: None,
+            width: None,
+            height: None,
+            spacing: None,
+            line_spacing: None,
+            proportional: false,
+        };
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            if key == "proportional" {
+                args.proportional = true;
+                continue;
+            }
+            input.parse::<Token![=]>()?;
+            if key == "chars" {
+                args.chars = Some(input.parse()?);
+                continue;
+            }
+            let value = Some(input.parse()?);
+            if key == "width" {
+                args.width = value;
+            } else if key == "height" {
+                args.height = value;
+            } else if key == "spacing" {
+                args.spacing = value;
+            } else if key == "line_spacing" {
+                args.line_spacing = value;
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "Expected `chars`, `width`, `height`, `spacing`, `line_spacing`, or `proportional`",
+                ));
+            }
+        }
+        Ok(args)
+    }
+}
+
+struct Glyph {
+    c: char,
+    width: usize,
+    // Row by row, true for the text
+    ink: Vec<bool>,
+}
+
+fn default_chars() -> Vec<char> {
+    (' '..='~').collect()
+}
+
+fn parse_int<T: std::str::FromStr>(lit: &Option<LitInt>) -> syn::Result<Option<T>>
+where
+    T::Err: std::fmt::Display,
+{
+    lit.as_ref().map(|lit| lit.base10_parse()).transpose()
+}
+
+pub fn include_font(args: FontArgs) -> syn::Result<TokenStream> {
+    let span = args.path.span();
+    let full = map::crate_path(&args.path);
+    let bytes = std::fs::read(&full)
+        .map_err(|e| syn::Error::new(span, format!("Couldn't read {}: {}", full.display(), e)))?;
+    let chars = args.chars.as_ref().map(|lit| lit.value().chars().collect::<Vec<_>>());
+
+    let extension = full.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let (glyphs, height) = match extension {
+        "bdf" => {
+            let source = String::from_utf8(bytes).map_err(|_| syn::Error::new(span, "BDF files must be text"))?;
+            load_bdf(&source, chars).map_err(|msg| syn::Error::new(span, msg))?
+        }
+        "png" => {
+            let image = png::decode(&bytes).map_err(|msg| syn::Error::new(span, format!("Couldn't read PNG: {}", msg)))?;
+            let Some(width) = parse_int(&args.width)? else {
+                return Err(syn::Error::new(span, "A PNG font needs the `width` of each glyph"));
+            };
+            let height = parse_int(&args.height)?.unwrap_or(image.height);
+            let chars = chars.unwrap_or_else(default_chars);
+            let glyphs = load_png(&image, &chars, width, height, args.proportional).map_err(|msg| syn::Error::new(span, msg))?;
+            (glyphs, height)
+        }
+        _ => return Err(syn::Error::new(span, "Expected a `.bdf` or `.png` file")),
+    };
+    if glyphs.is_empty() {
+        return Err(syn::Error::new(span, "The font has none of the characters"));
+    }
+
+    let mut offsets = Vec::new();
+    let mut total = 0usize;
+    for glyph in &glyphs {
+        offsets.push(total as u16);
+        total += glyph.width;
+    }
+    if total > u16::MAX as usize || glyphs.iter().any(|g| g.width > 255) {
+        return Err(syn::Error::new(span, "The font's glyphs are too wide"));
+    }
+    // Text is 0 bits, like wasm4's own font
+    let mut bitmap = vec![0xFFu8; (total * height).div_ceil(8)];
+    for y in 0..height {
+        for (glyph, &offset) in glyphs.iter().zip(&offsets) {
+            for x in 0..glyph.width {
+                if glyph.ink[y * glyph.width + x] {
+                    let bit = y * total + offset as usize + x;
+                    bitmap[bit / 8] &= !(0x80 >> (bit % 8));
+                }
+            }
+        }
+    }
+
+    let chars = LitStr::new(&glyphs.iter().map(|g| g.c).collect::<String>(), Span::call_site());
+    let widths = glyphs.iter().map(|g| g.width as u8);
+    let (total, height) = (total as u32, height as u32);
+    let spacing: u8 = parse_int(&args.spacing)?.unwrap_or(0);
+    let line_spacing: u8 = parse_int(&args.line_spacing)?.unwrap_or(0);
+    let full = map::path_lit(&full);
+    Ok(quote! {
+        {
+            // So the font is rebuilt when the file changes
+            const _: &[u8] = include_bytes!(#full);
+            ::sw4::font::Font {
+                chars: #chars,
+                offsets: &[#(#offsets),*],
+                widths: &[#(#widths),*],
+                bitmap: ::sw4::Sprite::new(&[#(#bitmap),*], #total, #height, ::sw4::SpriteFlags::ONE_BPP),
+                spacing: #spacing,
+                line_spacing: #line_spacing,
+            }
+        }
+    })
+}
+
+// Cells of `width` by `height` pixels, left to right then top to bottom. Text
+// is the opaque pixels if any are transparent, and the dark pixels otherwise
+fn load_png(image: &png::Image, chars: &[char], width: usize, height: usize, proportional: bool) -> Result<Vec<Glyph>, String> {
+    if width == 0 || height == 0 {
+        return Err("Glyphs must be at least 1 pixel across".into());
+    }
+    let columns = image.width / width;
+    let cells = columns * (image.height / height);
+    if chars.len() > cells {
+        return Err(format!("The image has room for {} glyphs, but there are {} characters", cells, chars.len()));
+    }
+    let transparent = image.pixels.iter().any(|p| p[3] < 255);
+    let is_ink = |p: [u8; 4]| {
+        if transparent {
+            p[3] >= 128
+        } else {
+            (p[0] as u32 * 299 + p[1] as u32 * 587 + p[2] as u32 * 114) / 1000 < 128
+        }
+    };
+    let mut glyphs = Vec::new();
+    for (i, &c) in chars.iter().enumerate() {
+        let (left, top) = ((i % columns) * width, (i / columns) * height);
+        let pixel = |x: usize, y: usize| is_ink(image.pixels[(top + y) * image.width + left + x]);
+        // Trimmed to the last column with any text, leaving blank glyphs whole
+        let used = (0..width).rev().find(|&x| (0..height).any(|y| pixel(x, y))).map_or(width, |x| x + 1);
+        let glyph_width = if proportional { used } else { width };
+        let ink = (0..height).flat_map(|y| (0..glyph_width).map(move |x| (x, y))).map(|(x, y)| pixel(x, y)).collect();
+        glyphs.push(Glyph { c, width: glyph_width, ink });
+    }
+    Ok(glyphs)
+}
+
+fn load_bdf(source: &str, chars: Option<Vec<char>>) -> Result<(Vec<Glyph>, usize), String> {
+    let numbers = |rest: &str| -> Result<Vec<i32>, String> {
+        rest.split_whitespace().map(|n| n.parse().map_err(|_| format!("`{}` isn't a number", n))).collect()
+    };
+    let mut ascent = None;
+    let mut descent = None;
+    let mut bounds = None;
+    // (encoding, advance, bounding box, bitmap rows)
+    let mut found: Vec<(i32, i32, [i32; 4], Vec<u32>)> = Vec::new();
+    let mut lines = source.lines();
+    while let Some(line) = lines.next() {
+        let (key, rest) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+        match key {
+            "FONT_ASCENT" => ascent = numbers(rest)?.first().copied(),
+            "FONT_DESCENT" => descent = numbers(rest)?.first().copied(),
+            "FONTBOUNDINGBOX" => bounds = Some(numbers(rest)?),
+            "STARTCHAR" => {
+                let (mut encoding, mut advance, mut bbx) = (-1, None, [0; 4]);
+                let mut rows = Vec::new();
+                for line in lines.by_ref() {
+                    let (key, rest) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+                    match key {
+                        "ENCODING" => encoding = numbers(rest)?.first().copied().unwrap_or(-1),
+                        "DWIDTH" => advance = numbers(rest)?.first().copied(),
+                        "BBX" => {
+                            let n = numbers(rest)?;
+                            if n.len() < 4 {
+                                return Err("BBX needs 4 numbers".into());
+                            }
+                            if n[0] > 32 {
+                                return Err("glyphs wider than 32 pixels aren't supported".into());
+                            }
+                            bbx = [n[0], n[1], n[2], n[3]];
+                        }
+                        "BITMAP" => {
+                            for line in lines.by_ref() {
+                                let line = line.trim();
+                                if line == "ENDCHAR" {
+                                    break;
+                                }
+                                if line.len() > 8 {
+                                    return Err("glyphs wider than 32 pixels aren't supported".into());
+                                }
+                                let bits = u32::from_str_radix(line, 16).map_err(|_| format!("`{}` isn't hex", line))?;
+                                // Left aligned in the row's whole bytes
+                                rows.push(bits << (32 - line.len() * 4));
+                            }
+                            break;
+                        }
+                        _ => (),
+                    }
+                }
+                found.push((encoding, advance.unwrap_or(bbx[0] + bbx[2]), bbx, rows));
+            }
+            _ => (),
+        }
+    }
+    let (ascent, height) = match (ascent, descent, &bounds) {
+        (Some(a), Some(d), _) => (a, a + d),
+        (_, _, Some(b)) if b.len() >= 4 => (b[1] + b[3], b[1]),
+        _ => return Err("The font needs FONT_ASCENT and FONT_DESCENT, or FONTBOUNDINGBOX".into()),
+    };
+    if height <= 0 {
+        return Err("The font has no height".into());
+    }
+
+    let explicit = chars.is_some();
+    let mut glyphs = Vec::new();
+    for c in chars.unwrap_or_else(default_chars) {
+        let Some((_, advance, [w, h, x_off, y_off], rows)) = found.iter().find(|g| g.0 == c as i32) else {
+            if explicit {
+                return Err(format!("The font has no glyph for {:?}", c));
+            }
+            continue;
+        };
+        let width = (*advance).max(0) as usize;
+        let mut ink = vec![false; width * height as usize];
+        for (r, bits) in rows.iter().enumerate().take(*h as usize) {
+            let y = ascent - (y_off + h) + r as i32;
+            for col in 0..*w {
+                let x = x_off + col;
+                if bits & (1 << (31 - col)) != 0 && (0..width as i32).contains(&x) && (0..height).contains(&y) {
+                    ink[y as usize * width + x as usize] = true;
+                }
+            }
+        }
+        glyphs.push(Glyph { c, width, ink });
+    }
+    Ok((glyphs, height as usize))
+}
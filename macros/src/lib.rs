@@ -1,7 +1,19 @@
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, AttributeArgs, ItemFn, Type};
+use syn::{parse_macro_input, AttributeArgs, Data, DeriveInput, Fields, ItemFn, Type};
 use quote::quote;
 
+mod assets;
+mod buttons;
+mod font;
+mod map;
+mod music;
+mod palette;
+mod png;
+mod rle;
+mod save;
+mod sound;
+mod sprite;
+
 macro_rules! error {
     ($($x:tt)*) => {
         return quote! {
@@ -10,48 +22,197 @@ macro_rules! error {
     };
 }
 
+// How `#[update]` gets its user state
+enum UpdateArgs {
+    // Made by `#[start]`
+    Start,
+    // Made on the first frame with `Default::default()`
+    Default,
+    // Made on the first frame with the given expression
+    Init(Box<syn::Expr>),
+}
+
+impl syn::parse::Parse for UpdateArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Ok(UpdateArgs::Start);
+        }
+        let ident: syn::Ident = input.parse()?;
+        let args = if ident == "default" {
+            UpdateArgs::Default
+        } else if ident == "init" {
+            input.parse::<syn::Token![=]>()?;
+            UpdateArgs::Init(input.parse()?)
+        } else {
+            return Err(syn::Error::new(ident.span(), "Expected `default` or `init = ...`"));
+        };
+        if !input.is_empty() {
+            return Err(input.error("Unexpected macro args"));
+        }
+        Ok(args)
+    }
+}
+
+fn is_wasm4_type(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(ty) => match &*ty.elem {
+            Type::Path(path) => path.path.segments.last().is_some_and(|s| s.ident == "Wasm4"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// The `T` in a `Result<T, E>`
+fn result_ok_type(ty: &Type) -> Option<Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let last = path.path.segments.last().filter(|s| s.ident == "Result")?;
+    match &last.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(syn::GenericArgument::Type(ok)) => Some(ok.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_frame_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.last().is_some_and(|s| s.ident == "Frame"),
+        _ => false,
+    }
+}
+
+/// Marks the function called every frame
+/// 
+/// It can take `(&mut Wasm4, &mut UserState)`, or just one of them, or 
+/// nothing. `sw4::Frame` can be taken instead of `&mut Wasm4`.
+/// 
+/// The user state comes from the `#[start]` function by default. Without one,
+/// it can be made on the first frame instead, with `#[update(default)]` to use
+/// its `Default` impl or `#[update(init = expr)]` to use an expression.
 #[proc_macro_attribute]
 pub fn update(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as AttributeArgs);
-    if !args.is_empty() {
-        error!("Unexpected macro args");
-    }
+    let args = parse_macro_input!(args as UpdateArgs);
     let func = parse_macro_input!(input as ItemFn);
     let func_name = &func.sig.ident;
 
+    let mut params = Vec::new();
+    for arg in &func.sig.inputs {
+        match arg {
+            syn::FnArg::Typed(arg) => params.push(&*arg.ty),
+            syn::FnArg::Receiver(_) => error!("`update` can't take `self`"),
+        }
+    }
+    // Parameters are `(&mut Wasm4, &mut UserState)`, with either of them optional
+    let is_wasm4 = |ty: &Type| is_wasm4_type(ty) || is_frame_type(ty);
+    let (wasm4_param, user_state_param) = match params.as_slice() {
+        [] => (None, None),
+        [ty] if is_wasm4(ty) => (Some(*ty), None),
+        [ty] => (None, Some(*ty)),
+        [wasm4, ty] if is_wasm4(wasm4) => (Some(*wasm4), Some(*ty)),
+        _ => error!("`update` must take `(&mut Wasm4, &mut UserState)`, one of them, or nothing"),
+    };
+    let takes_wasm4 = wasm4_param.is_some();
+    let user_data_type = match user_state_param {
+        None => None,
+        Some(Type::Reference(ty)) if ty.mutability.is_some() => Some((*ty.elem).clone()),
+        Some(_) => error!("The user state parameter must be a `&mut` reference"),
+    };
+
+    let init = match args {
+        UpdateArgs::Start => None,
+        UpdateArgs::Default => Some(quote!(::core::default::Default::default())),
+        UpdateArgs::Init(expr) => Some(quote!(#expr)),
+    };
+    let (state_cell, lazy_init) = match (init, &user_data_type) {
+        (None, _) => (quote!(), quote!()),
+        (Some(_), None) => error!("Lazily initialized state needs a user state parameter"),
+        (Some(init), Some(user_data_type)) => {
+            let state_cell = quote! {
+                #[allow(deprecated)]
+                static SW4_USER_STATE: sw4::SyncUnsafeCell<core::mem::MaybeUninit<#user_data_type>> = 
+                    sw4::SyncUnsafeCell::new(core::mem::MaybeUninit::uninit());
+            };
+            let lazy_init = quote! {
+                // Without a `start`, the state is made on the first frame
+                if *(1 as *mut u8) != 1 {
+                    SW4_USER_STATE.get().cast::<#user_data_type>().write(#init);
+                    (1 as *mut u8).write(1)
+                }
+            };
+            (state_cell, lazy_init)
+        }
+    };
+
+    let wasm4_arg = match wasm4_param {
+        Some(ty) if is_frame_type(ty) => quote! {
+            let state_v = &mut *(4 as *mut ::sw4::Wasm4 );
+            let mut state = ();
+            let state = ::sw4::Frame::new(shorten(state_v, &mut state));
+        },
+        Some(_) => quote! {
+            let state_v = &mut *(4 as *mut ::sw4::Wasm4 );
+            let mut state = ();
+            let state = shorten(state_v, &mut state);
+        },
+        None => quote!(),
+    };
+    let user_state_arg = if user_data_type.is_some() {
+        quote! {
+            // there's a initialization flag at memory addres 0x0001
+            // so that in the very unlikely case `start` isn't run first,
+            // there won't be UB reading the user state
+            if *(1 as *mut u8) != 1 {
+                return;
+            }
+            let user_state_v = (&mut *(SW4_USER_STATE.get())).assume_init_mut();
+            let mut user_state = ();
+            let user_state = shorten(user_state_v, &mut user_state);
+        }
+    } else {
+        quote!()
+    };
+    let call_args = match (takes_wasm4, user_data_type.is_some()) {
+        (true, true) => quote!(state, user_state),
+        (true, false) => quote!(state),
+        (false, true) => quote!(user_state),
+        (false, false) => quote!(),
+    };
+
     let out = quote! {
+        #state_cell
         #[no_mangle]
         pub extern "C" fn update() {
             // The user-implemented update function
             #func
+            #[allow(dead_code)]
             fn shorten<'a, T: 'static, U>(x: &'static mut T, _y: &'a mut U) -> &'a mut T {
                 x
             }
             unsafe {
-                // there's a initialization flag at memory addres 0x0001
-                // so that in the very unlikely case `start` isn't run first,
-                // there won't be UB reading the user state
-                if *(1 as *mut u8) != 1 {
-                    return;
-                }
-                let state_v = &mut *(4 as *mut ::sw4::Wasm4 );
-                let user_state_v = (&mut *(SW4_USER_STATE.get())).assume_init_mut();
-
+                #lazy_init
                 // The lifetimes have to be shortened, as giving the user a
                 // 'static lifetime would allow them to store it between frames
                 // which could break aliasing    
-                let mut state = ();
-                let state = shorten(state_v, &mut state);
-                let mut user_state = ();
-                let user_state = shorten(user_state_v, &mut user_state);
-                (#func_name)(state, user_state)
+                #user_state_arg
+                #wasm4_arg
+                (#func_name)(#call_args);
             }
+            ::sw4::__end_frame();
         }
     };
 
     out.into()
 }
 
+/// Marks the function called once before the first frame
+/// 
+/// It takes `&mut Wasm4`, and returns the user state given to `#[update]`.
+/// It can also return a `Result` whose error is `Display`, which is given to
+/// `sw4::panic` if `start` fails.
 #[proc_macro_attribute]
 pub fn start(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as AttributeArgs);
@@ -64,6 +225,22 @@ pub fn start(args: TokenStream, input: TokenStream) -> TokenStream {
         syn::ReturnType::Default => Type::Verbatim(quote!(())),
         syn::ReturnType::Type(_, ty) => (**ty).clone(),
     };
+    // A `Result` is unwrapped, panicking with the error if there is one
+    let (user_data_type, init) = match result_ok_type(&user_data_type) {
+        Some(ok) => (
+            ok,
+            quote! {
+                match (#func_name)(state) {
+                    ::core::result::Result::Ok(user_state) => user_state,
+                    ::core::result::Result::Err(e) => {
+                        let mut buf = ::sw4::StrBuf::<256>::new();
+                        ::sw4::panic(::sw4::format_buf!(buf, "`start` failed: {}", e))
+                    }
+                }
+            },
+        ),
+        None => (user_data_type, quote!((#func_name)(state))),
+    };
 
     let out = quote! {
         #[allow(deprecated)]
@@ -80,7 +257,7 @@ pub fn start(args: TokenStream, input: TokenStream) -> TokenStream {
                 let state_v = &mut *(4 as *mut ::sw4::Wasm4 );
                 let mut state = ();
                 let state = shorten(state_v, &mut state);
-                SW4_USER_STATE.get().cast::<#user_data_type>().write((#func_name)(state));
+                SW4_USER_STATE.get().cast::<#user_data_type>().write(#init);
                 // Set an initialization flag at memory addres 0x0001
                 (1 as *mut u8).write(1)
             }
@@ -88,4 +265,565 @@ pub fn start(args: TokenStream, input: TokenStream) -> TokenStream {
     };
 
     out.into()
-}
\ No newline at end of file
+}
+
+/// Marks an `impl` block holding the game's `start` and `update` methods
+/// 
+/// `start` makes the game, and can take `&mut Wasm4` or nothing. `update` is
+/// called every frame with `&mut self`, and can also take `&mut Wasm4`. This
+/// implements `sw4::Game` with them and uses `sw4::run_game!`, so neither
+/// needs both parameters.
+/// 
+/// ```ignore
+/// struct MyGame {
+///     x: i32,
+/// }
+/// 
+/// #[sw4::game]
+/// impl MyGame {
+///     fn start() -> Self {
+///         MyGame { x: 0 }
+///     }
+/// 
+///     fn update(&mut self, w: &mut Wasm4) {
+///         self.x += 1;
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn game(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
+    if !args.is_empty() {
+        error!("Unexpected macro args");
+    }
+    let item = parse_macro_input!(input as syn::ItemImpl);
+    if item.trait_.is_some() {
+        error!("`game` goes on an inherent `impl` block, not a trait impl");
+    }
+    if !item.generics.params.is_empty() {
+        error!("`game` can't be used on a generic `impl` block");
+    }
+    let game_type = &item.self_ty;
+    let method = |name: &str| {
+        item.items.iter().find_map(|item| match item {
+            syn::ImplItem::Method(method) if method.sig.ident == name => Some(&method.sig),
+            _ => None,
+        })
+    };
+
+    let Some(start_sig) = method("start") else {
+        error!("`game` needs a `fn start() -> Self`");
+    };
+    let (start_param, start_args) = match start_sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [] => (quote!(_w), quote!()),
+        [syn::FnArg::Typed(arg)] if is_wasm4_type(&arg.ty) => (quote!(w), quote!(w)),
+        _ => error!("`start` must take `&mut Wasm4` or nothing"),
+    };
+
+    let Some(update_sig) = method("update") else {
+        error!("`game` needs a `fn update(&mut self)`");
+    };
+    let (update_param, update_args) = match update_sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [syn::FnArg::Receiver(r)] if r.reference.is_some() && r.mutability.is_some() => (quote!(_w), quote!()),
+        [syn::FnArg::Receiver(r), syn::FnArg::Typed(arg)]
+            if r.reference.is_some() && r.mutability.is_some() && is_wasm4_type(&arg.ty) =>
+        {
+            (quote!(w), quote!(w))
+        }
+        _ => error!("`update` must take `(&mut self, &mut Wasm4)` or `&mut self`"),
+    };
+
+    // Going through `sw4::Game`, whose elided lifetimes turn away `'static`
+    // borrows that could be kept and alias the next frame's
+    let out = quote! {
+        #item
+
+        impl ::sw4::Game for #game_type {
+            fn start(#start_param: &mut ::sw4::Wasm4) -> Self {
+                <#game_type>::start(#start_args)
+            }
+
+            fn update(&mut self, #update_param: &mut ::sw4::Wasm4) {
+                <#game_type>::update(self, #update_args)
+            }
+        }
+
+        ::sw4::run_game!(#game_type);
+    };
+
+    out.into()
+}
+
+#[proc_macro_derive(Checksum)]
+pub fn derive_checksum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::sw4::checksum::Checksum));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Produces a pattern binding every field, and the code hashing them in order
+    fn fields_pattern(fields: &Fields) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+        let names = (0..fields.len())
+            .map(|i| quote::format_ident!("field_{}", i))
+            .collect::<Vec<_>>();
+        let pattern = match fields {
+            Fields::Named(fields) => {
+                let idents = fields.named.iter().map(|f| &f.ident);
+                quote!({ #(#idents: #names),* })
+            }
+            Fields::Unnamed(_) => quote!(( #(#names),* )),
+            Fields::Unit => quote!(),
+        };
+        let body = quote! {
+            #(::sw4::checksum::Checksum::checksum(#names, hasher);)*
+        };
+        (pattern, body)
+    }
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, body) = fields_pattern(&data.fields);
+            quote! {
+                let #name #pattern = self;
+                #body
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().enumerate().map(|(i, variant)| {
+                let variant_name = &variant.ident;
+                let i = i as u32;
+                let (pattern, body) = fields_pattern(&variant.fields);
+                quote! {
+                    #name::#variant_name #pattern => {
+                        hasher.write_u32(#i);
+                        #body
+                    }
+                }
+            });
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => error!("`Checksum` can't be derived for unions"),
+    };
+
+    let out = quote! {
+        impl #impl_generics ::sw4::checksum::Checksum for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn checksum(&self, hasher: &mut ::sw4::checksum::Hasher) {
+                #body
+            }
+        }
+    };
+
+    out.into()
+}
+
+/// Give a struct or enum a fixed binary layout with `sw4::disk::Save`, so it
+/// can be stored on disk
+/// 
+/// Every field must be `Save` too, like integers, `bool`, arrays, and other
+/// derived types. Fields are stored in order, and enums store a byte for the
+/// variant before the largest variant's fields. Fails to compile if it's
+/// larger than the disk.
+/// 
+/// An unknown variant byte, from a damaged disk or another version of the
+/// game, makes `Save::try_load` and `Disk::load` give `None`.
+/// 
+/// ```ignore
+/// #[derive(Save)]
+/// struct Progress {
+///     level: u8,
+///     high_scores: [u32; 5],
+///     unlocked: Option<Character>,
+/// }
+/// 
+/// w.disk.save(&progress);
+/// ```
+#[proc_macro_derive(Save)]
+pub fn derive_save(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match save::derive_save(input) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Turn a level file into a `sw4::tilemap::MapData` at compile time
+/// 
+/// The path is relative to the crate root. The format is picked by the file
+/// extension:
+/// - `.csv`: rows of comma-separated tile numbers, with `-1` for no tile.
+/// - `.tmx`: a Tiled map, using the first tile layer (which must be CSV
+///   encoded), with every object becoming a spawn point.
+/// - Anything else: plain text, one row per line. `tiles = "..."` gives the
+///   characters for each tile number in order, and `spawns = "..."` gives
+///   characters which become spawn points on top of tile 0. Spaces are no tile.
+/// 
+/// Add `compress` to store the tiles compressed.
+/// 
+/// ```ignore
+/// const LEVEL_1: MapData = include_map!("levels/1.txt", tiles = ".#=", spawns = "PE", compress);
+/// ```
+#[proc_macro]
+pub fn include_map(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as map::MapArgs);
+    match map::include_map(args) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Compress a file of raw sprite data into an `sw4::rle::RleSprite` at
+/// compile time
+/// 
+/// Takes the path relative to the crate root, then the width, height, and
+/// flags of the sprite.
+/// 
+/// ```ignore
+/// const SKY: RleSprite = include_rle_sprite!("art/sky.bin", 160, 96, TWO_BPP);
+/// ```
+#[proc_macro]
+pub fn include_rle_sprite(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as assets::SpriteArgs);
+    match assets::include_rle_sprite(args) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// A sprite drawn as ASCII art, one string per row
+/// 
+/// By default `.`, `#`, `x`, and `o` are the pixel values 0 to 3, which are
+/// drawn in draw colors 1 to 4. Give `chars = "..."` first to pick other
+/// characters, in order of value. The sprite is 1bpp if it only uses the
+/// first two, and 2bpp otherwise. One string can hold several rows on
+/// separate lines, with the whitespace around them ignored, unless `chars`
+/// has whitespace in it.
+/// 
+/// ```ignore
+/// const HEART: Sprite = sprite!(
+///     ".#.#.",
+///     "#####",
+///     ".###.",
+///     "..#..",
+/// );
+/// const COIN: Sprite = sprite!(chars = " .oO", " oo ", "oOOo", "oOOo", " oo ");
+/// ```
+#[proc_macro]
+pub fn sprite(input: TokenStream) -> TokenStream {
+    let art = parse_macro_input!(input as sprite::SpriteArt);
+    match sprite::sprite(art) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// An `sw4::music::Song` written as tracker patterns, checked at compile time
+/// 
+/// Takes these, each ending in `;`:
+/// 
+/// - `speed = N`: how many frames each row lasts
+/// - `instruments = [...]`: up to four `Sound`s, one for each column of the
+///   patterns, like those from [`macro@sound`]
+/// - `pattern NAME = "..."`: rows of steps, one row per line, with the steps
+///   separated by spaces. A step is a note like `C4`, `C#4`, `Db4`, or `C-4`,
+///   `..` to carry on, or `--` to end the note.
+/// - `order = [NAME, ...]`: the order the patterns play in, which is the order
+///   they're written in if left out
+/// - `loop_to = N`: the place in the order to loop back to at the end, or
+///   leave it out to stop
+/// 
+/// ```ignore
+/// static SONG: Song = music! {
+///     speed = 8;
+///     instruments = [sound!(pulse1 @ 25%, r:4, vol 60), sound!(triangle)];
+///     pattern intro = "
+///         C4  C2
+///         ..  ..
+///         E4  G2
+///         --  ..
+///     ";
+///     pattern verse = "
+///         G4  C3
+///         F4  ..
+///         E4  G2
+///         D4  --
+///     ";
+///     order = [intro, verse, verse];
+///     loop_to = 1;
+/// };
+/// ```
+#[proc_macro]
+pub fn music(input: TokenStream) -> TokenStream {
+    let music = parse_macro_input!(input as music::Music);
+    match music::music(music) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// An `sw4::Sound` described in a short notation, usable in constants
+/// 
+/// Parts are separated by commas and can come in any order. Anything left out
+/// is the same as `Sound::builder()`.
+/// 
+/// - The channel: `pulse1` or `pulse2`, with an optional duty cycle like
+///   `@ 25%`, `triangle`, or `noise`
+/// - The frequency: `440 Hz`, or `440 -> 880 Hz` to slide between two
+/// - The envelope, in frames: any of `a:N`, `d:N`, `s:N`, and `r:N` for the
+///   attack, decay, sustain, and release
+/// - The volume from 0 to 100: `vol 80`, or `vol 80/60` for different peak
+///   and sustain volumes
+/// - `pan left`, `pan right`, or `pan center`
+/// 
+/// ```ignore
+/// const JUMP: Sound = sound!(pulse1 @ 50%, 440 -> 880 Hz, a:2 d:4 s:20 r:6, vol 80/60);
+/// const HIT: Sound = sound!(noise, 200 Hz, s:4 r:8, pan left);
+/// ```
+#[proc_macro]
+pub fn sound(input: TokenStream) -> TokenStream {
+    let sound = parse_macro_input!(input as sound::SoundDsl);
+    sound::sound(sound).into()
+}
+
+/// Convert a font file into an `sw4::font::Font` at compile time
+/// 
+/// The path is relative to the crate root, and the format comes from its
+/// extension:
+/// 
+/// - `.bdf`: a bitmap font, with each glyph as wide as its `DWIDTH`
+/// - `.png`: glyphs in cells `width` pixels wide and `height` pixels tall
+///   (the whole image by default), left to right then top to bottom. The
+///   text is the opaque pixels if the image has any transparency, and the
+///   dark pixels otherwise. Add `proportional` to trim each glyph to the
+///   last column with anything in it.
+/// 
+/// `chars = "..."` gives the characters the font has, in the order of the
+/// glyphs in a PNG, and defaults to the printable ASCII characters.
+/// `spacing` and `line_spacing` give the gaps in pixels between glyphs and
+/// lines.
+/// 
+/// ```ignore
+/// const PIXEL: Font = include_font!("fonts/pixel.bdf");
+/// const THIN: Font = include_font!("fonts/thin.png", chars = "0123456789", width = 4, spacing = 1);
+/// ```
+#[proc_macro]
+pub fn include_font(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as font::FontArgs);
+    match font::include_font(args) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// An `sw4::Palette` from four hex colors, checked at compile time
+/// 
+/// Colors are written like `"#180d2f"` or `"#fff"`, from palette index 0 to 3.
+/// 
+/// ```ignore
+/// const PALETTE: Palette = palette!("#180d2f", "#353658", "#686f99", "#8c98d6");
+/// ```
+#[proc_macro]
+pub fn palette(input: TokenStream) -> TokenStream {
+    let parser = syn::punctuated::Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated;
+    let colors = parse_macro_input!(input with parser);
+    match palette::palette(colors) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// An `sw4::Palette` from a file of four colors, in one of the formats lospec
+/// exports
+/// 
+/// The path is relative to the crate root. Files ending in `.gpl` are read as
+/// GIMP palettes, and anything else as one hex color per line, like `.hex`
+/// and Paint.NET `.txt` files.
+/// 
+/// ```ignore
+/// const PALETTE: Palette = include_palette!("art/palette.hex");
+/// ```
+#[proc_macro]
+pub fn include_palette(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as syn::LitStr);
+    match palette::include_palette(path) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Pack sprites, maps, sounds, and raw data into one blob, with a function to
+/// get each
+/// 
+/// Generates a module with a `BLOB` static holding everything, its `SIZE`, and
+/// a function named after each asset. Paths are relative to the crate root.
+/// Attributes before `mod`, such as [`macro@size_budget`], are kept.
+/// 
+/// ```ignore
+/// sw4::assets! {
+///     pub mod assets {
+///         // Width, height, and flags of raw sprite data
+///         sprite player = "art/player.bin", 16, 16, TWO_BPP;
+///         // The same, compressed into an `sw4::rle::RleSprite`
+///         rle_sprite sky = "art/sky.bin", 160, 96, TWO_BPP;
+///         // Takes the same arguments as `include_map!`
+///         map level_1 = "levels/1.txt", tiles = ".#", spawns = "P", compress;
+///         // Any constant `Sound`
+///         sound jump = Sound::builder().freq_slide(300, 600).build();
+///         bytes font = "art/font.bin";
+///     }
+/// }
+/// 
+/// w.frame_buffer.sprite_at(&assets::player(), pos);
+/// ```
+#[proc_macro]
+pub fn assets(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as assets::Assets);
+    match assets::assets(input) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Run the first arm whose buttons are down, like a `match` on a gamepad
+/// 
+/// Takes anything implementing `sw4::input::ButtonState`, then arms of
+/// buttons. `+` needs every button, `|` needs any of them, and `+` goes first,
+/// so `X + Left | Right` is `(X + Left) | Right`. Buttons are checked as held
+/// down, or as pressed this frame inside `just(...)`, which needs an
+/// `sw4::input::GamepadTracker`. `_` matches anything.
+/// 
+/// ```ignore
+/// buttons!(tracker => {
+///     just(X) + Left => jump_left(),
+///     just(X) | just(Z) + Up => jump(),
+///     Left | Right => walk(),
+///     _ => stand(),
+/// });
+/// ```
+#[proc_macro]
+pub fn buttons(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as buttons::ButtonMatch);
+    match buttons::buttons(input) {
+        Ok(out) => out.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Fail to compile if the `const`s and `static`s in a module add up to more
+/// than a number of bytes
+/// 
+/// Constants that are references, like `&[u8]`, count the size of what they
+/// point to. Only items directly in the module are counted.
+/// 
+/// ```ignore
+/// #[sw4::size_budget(60_000)]
+/// mod assets {
+///     pub const PLAYER: &[u8] = include_bytes!("player.bin");
+///     pub static LEVELS: [u8; 4096] = ...;
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn size_budget(args: TokenStream, input: TokenStream) -> TokenStream {
+    let budget = parse_macro_input!(args as syn::LitInt);
+    let mut module = parse_macro_input!(input as syn::ItemMod);
+    let budget_bytes: usize = match budget.base10_parse() {
+        Ok(b) => b,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let Some((_, items)) = &mut module.content else {
+        error!("`size_budget` needs a module with a body");
+    };
+
+    let sizes = items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Const(item) => Some((&item.ident, &*item.ty)),
+            syn::Item::Static(item) => Some((&item.ident, &*item.ty)),
+            _ => None,
+        })
+        .filter(|(ident, _)| *ident != "_")
+        .map(|(ident, ty)| match ty {
+            Type::Reference(_) => quote!(::core::mem::size_of_val(#ident)),
+            ty => quote!(::core::mem::size_of::<#ty>()),
+        })
+        .collect::<Vec<_>>();
+    let message = format!("`{}` is over its size budget of {} bytes", module.ident, budget_bytes);
+    items.push(syn::parse_quote! {
+        const _: () = {
+            const TOTAL: usize = 0 #(+ #sizes)*;
+            ::core::assert!(TOTAL <= #budget_bytes, #message);
+        };
+    });
+
+    quote!(#module).into()
+}
+
+/// A string literal with `{NAME}` replaced by the gamepad glyphs in wasm4's
+/// font
+/// 
+/// The names are `X`, `Z`, `LEFT`, `RIGHT`, `UP`, and `DOWN`, matching
+/// `sw4::glyphs`. Use `{{` and `}}` for literal braces.
+/// 
+/// ```ignore
+/// w.frame_buffer.text(glyph_str!("Press {X} to start"), 20, 80);
+/// ```
+#[proc_macro]
+pub fn glyph_str(input: TokenStream) -> TokenStream {
+    let lit = parse_macro_input!(input as syn::LitStr);
+    let source = lit.value();
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('{') if name.is_empty() => {
+                            out.push('{');
+                            break;
+                        }
+                        Some('}') => {
+                            let glyph = match name.as_str() {
+                                "X" => '\u{80}',
+                                "Z" => '\u{81}',
+                                "LEFT" => '\u{84}',
+                                "RIGHT" => '\u{85}',
+                                "UP" => '\u{86}',
+                                "DOWN" => '\u{87}',
+                                _ => {
+                                    let msg = format!("Unknown glyph `{}`", name);
+                                    return syn::Error::new(lit.span(), msg).to_compile_error().into();
+                                }
+                            };
+                            out.push(glyph);
+                            break;
+                        }
+                        Some(c) => name.push(c),
+                        None => {
+                            return syn::Error::new(lit.span(), "Unclosed `{`").to_compile_error().into();
+                        }
+                    }
+                }
+            }
+            '}' => {
+                if chars.next() != Some('}') {
+                    return syn::Error::new(lit.span(), "Unmatched `}`, use `}}` for a literal brace")
+                        .to_compile_error()
+                        .into();
+                }
+                out.push('}');
+            }
+            c => out.push(c),
+        }
+    }
+    let out = syn::LitStr::new(&out, lit.span());
+    quote!(#out).into()
+}
@@ -0,0 +1,305 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitStr, Token};
+
+// Matches `sw4::tilemap::TileMap::EMPTY`
+const EMPTY: u8 = 0xFF;
+
+pub struct MapArgs {
+    pub path: LitStr,
+    pub tiles: Option<LitStr>,
+    pub spawns: Option<LitStr>,
+    pub compress: bool,
+}
+
+impl Parse for MapArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = MapArgs {
+            path: input.parse()?,
+            tiles: None,
+            spawns: None,
+            compress: false,
+        };
+        // Stopping at `;`, for when the args are inside `assets!`
+        while !input.is_empty() && !input.peek(Token![;]) {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() || input.peek(Token![;]) {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            if key == "compress" {
+                args.compress = true;
+                continue;
+            }
+            input.parse::<Token![=]>()?;
+            let value: LitStr = input.parse()?;
+            if key == "tiles" {
+                args.tiles = Some(value);
+            } else if key == "spawns" {
+                args.spawns = Some(value);
+            } else {
+                return Err(syn::Error::new(key.span(), "Expected `tiles`, `spawns`, or `compress`"));
+            }
+        }
+        Ok(args)
+    }
+}
+
+pub struct Map {
+    pub width: usize,
+    pub height: usize,
+    // Compressed if the args asked for it
+    pub tiles: Vec<u8>,
+    pub compressed: bool,
+    pub spawns: Vec<(String, i32, i32)>,
+}
+
+/// Resolve a path relative to the crate being compiled
+pub fn crate_path(path: &LitStr) -> std::path::PathBuf {
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into());
+    std::path::Path::new(&root).join(path.value())
+}
+
+/// A literal for the absolute path, to `include_bytes!` so changes to the file
+/// cause a rebuild
+pub fn path_lit(path: &std::path::Path) -> LitStr {
+    LitStr::new(&path.to_string_lossy(), Span::call_site())
+}
+
+pub fn include_map(args: MapArgs) -> syn::Result<TokenStream> {
+    let path = path_lit(&crate_path(&args.path));
+    let map = load(&args)?;
+    let width = map.width as u32;
+    let height = map.height as u32;
+    let tiles = map.tiles;
+    let compressed = map.compressed;
+    let spawns = spawns_tokens(&map.spawns);
+    Ok(quote! {
+        {
+            // So the map is rebuilt when the file changes
+            const _: &[u8] = include_bytes!(#path);
+            ::sw4::tilemap::MapData {
+                width: #width,
+                height: #height,
+                tiles: &[#(#tiles),*],
+                compressed: #compressed,
+                spawns: #spawns,
+            }
+        }
+    })
+}
+
+pub fn spawns_tokens(spawns: &[(String, i32, i32)]) -> TokenStream {
+    let spawns = spawns.iter().map(|(name, x, y)| {
+        quote!(::sw4::tilemap::Spawn { name: #name, x: #x, y: #y })
+    });
+    quote!(&[#(#spawns),*])
+}
+
+pub fn load(args: &MapArgs) -> syn::Result<Map> {
+    let span = args.path.span();
+    let path = crate_path(&args.path);
+    let source = std::fs::read_to_string(&path)
+        .map_err(|e| syn::Error::new(span, format!("Couldn't read {}: {}", path.display(), e)))?;
+    let err = |msg: String| syn::Error::new(span, msg);
+
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let map = match extension {
+        "csv" => parse_csv(&source).map_err(err)?,
+        "tmx" => parse_tmx(&source).map_err(err)?,
+        _ => {
+            let tiles = args
+                .tiles
+                .as_ref()
+                .ok_or_else(|| syn::Error::new(span, "Text maps need `tiles = \"...\"`"))?
+                .value();
+            let spawns = args.spawns.as_ref().map(|s| s.value()).unwrap_or_default();
+            parse_text(&source, &tiles, &spawns).map_err(err)?
+        }
+    };
+
+    Ok(if args.compress {
+        Map {
+            tiles: crate::rle::encode(&map.tiles),
+            compressed: true,
+            ..map
+        }
+    } else {
+        map
+    })
+}
+
+// Rows of comma-separated tile numbers, with -1 for no tile
+fn parse_csv(source: &str) -> Result<Map, String> {
+    let mut tiles = Vec::new();
+    let mut width = None;
+    let mut height = 0;
+    for line in source.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let row = parse_csv_row(line)?;
+        match width {
+            None => width = Some(row.len()),
+            Some(w) if w != row.len() => {
+                return Err(format!("Row {} has {} tiles, expected {}", height + 1, row.len(), w))
+            }
+            _ => {}
+        }
+        tiles.extend(row);
+        height += 1;
+    }
+    Ok(Map {
+        width: width.unwrap_or(0),
+        height,
+        tiles,
+        compressed: false,
+        spawns: Vec::new(),
+    })
+}
+
+fn parse_csv_row(line: &str) -> Result<Vec<u8>, String> {
+    line.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| match s.parse::<i32>() {
+            Ok(-1) => Ok(EMPTY),
+            Ok(n) if (0..EMPTY as i32).contains(&n) => Ok(n as u8),
+            _ => Err(format!("Invalid tile `{}`, tiles must be -1 or 0 to 254", s)),
+        })
+        .collect()
+}
+
+// One row per line, with each character's tile being its position in `tiles`.
+// Characters in `spawns` become spawn points on top of the first tile
+fn parse_text(source: &str, tiles: &str, spawns: &str) -> Result<Map, String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let mut map = Map {
+        width,
+        height: lines.len(),
+        tiles: Vec::with_capacity(width * lines.len()),
+        compressed: false,
+        spawns: Vec::new(),
+    };
+    for (y, line) in lines.iter().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            let tile = if let Some(i) = tiles.chars().position(|t| t == c) {
+                if i >= EMPTY as usize {
+                    return Err("Too many tile characters".into());
+                }
+                i as u8
+            } else if spawns.contains(c) {
+                map.spawns.push((c.to_string(), x as i32, y as i32));
+                0
+            } else if c == ' ' {
+                EMPTY
+            } else {
+                return Err(format!("Unknown tile `{}` at line {}, column {}", c, y + 1, x + 1));
+            };
+            map.tiles.push(tile);
+        }
+        // Short lines are padded with empty tiles
+        map.tiles.resize((y + 1) * width, EMPTY);
+    }
+    Ok(map)
+}
+
+// Tiled maps, using the first CSV-encoded tile layer, and every object as a
+// spawn point
+fn parse_tmx(source: &str) -> Result<Map, String> {
+    let map_tag = find_tag(source, "map").ok_or("No `<map>` in Tiled map")?;
+    let tile_width: i32 = attr_num(map_tag, "tilewidth")?;
+    let tile_height: i32 = attr_num(map_tag, "tileheight")?;
+    let first_gid: u32 = match find_tag(source, "tileset") {
+        Some(tag) => attr_num(tag, "firstgid")?,
+        None => 1,
+    };
+
+    let layer_start = source.find("<layer").ok_or("No tile layer in Tiled map")?;
+    let layer = &source[layer_start..];
+    let layer_tag = find_tag(layer, "layer").ok_or("No tile layer in Tiled map")?;
+    let width: usize = attr_num(layer_tag, "width")?;
+    let height: usize = attr_num(layer_tag, "height")?;
+    let data_tag = find_tag(layer, "data").ok_or("No `<data>` in tile layer")?;
+    if attr(data_tag, "encoding") != Some("csv") {
+        return Err("Only CSV tile layer encoding is supported".into());
+    }
+    let data_start = layer.find(data_tag).unwrap() + data_tag.len() + 1;
+    let data_end = layer[data_start..].find("</data>").ok_or("Unclosed `<data>`")? + data_start;
+
+    let mut tiles = Vec::with_capacity(width * height);
+    for s in layer[data_start..data_end].split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let gid: u32 = s.parse().map_err(|_| format!("Invalid tile `{}`", s))?;
+        // The top bits are flip flags
+        let gid = gid & 0x0FFF_FFFF;
+        let tile = match gid {
+            0 => EMPTY,
+            gid if gid >= first_gid && gid - first_gid < EMPTY as u32 => (gid - first_gid) as u8,
+            _ => return Err(format!("Tile {} is out of range", gid)),
+        };
+        tiles.push(tile);
+    }
+    if tiles.len() != width * height {
+        return Err(format!("Tile layer has {} tiles, expected {}", tiles.len(), width * height));
+    }
+
+    let mut spawns = Vec::new();
+    let mut rest = source;
+    while let Some(tag) = find_tag(rest, "object") {
+        let name = attr(tag, "name").or_else(|| attr(tag, "type")).unwrap_or("").to_string();
+        let x: f64 = attr_num(tag, "x")?;
+        let y: f64 = attr_num(tag, "y")?;
+        spawns.push((
+            name,
+            (x / tile_width as f64).floor() as i32,
+            (y / tile_height as f64).floor() as i32,
+        ));
+        rest = &rest[rest.find(tag).unwrap() + tag.len()..];
+    }
+
+    Ok(Map {
+        width,
+        height,
+        tiles,
+        compressed: false,
+        spawns,
+    })
+}
+
+// The inside of the first `<name ...>` tag
+fn find_tag<'a>(source: &'a str, name: &str) -> Option<&'a str> {
+    let open = format!("<{}", name);
+    let mut offset = 0;
+    while let Some(i) = source[offset..].find(&open) {
+        let start = offset + i + open.len();
+        // Make sure it's not just a tag starting with the same name
+        if source[start..].starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+            let end = source[start..].find('>')? + start;
+            return Some(source[start..end].trim_end_matches('/'));
+        }
+        offset = start;
+    }
+    None
+}
+
+fn attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!("{}=\"", name);
+    let mut offset = 0;
+    while let Some(i) = tag[offset..].find(&key) {
+        let start = offset + i;
+        // Make sure it's not the end of a longer attribute name
+        if start == 0 || tag[..start].ends_with(char::is_whitespace) {
+            let value_start = start + key.len();
+            let value_end = tag[value_start..].find('"')? + value_start;
+            return Some(&tag[value_start..value_end]);
+        }
+        offset = start + key.len();
+    }
+    None
+}
+
+fn attr_num<T: std::str::FromStr>(tag: &str, name: &str) -> Result<T, String> {
+    attr(tag, name)
+        .ok_or_else(|| format!("Missing `{}` attribute", name))?
+        .parse()
+        .map_err(|_| format!("Invalid `{}` attribute", name))
+}
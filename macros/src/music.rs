@@ -0,0 +1,176 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, Expr, Ident, LitInt, LitStr, Token};
+
+pub struct Music {
+    speed: Option<LitInt>,
+    instruments: Vec<Expr>,
+    patterns: Vec<(Ident, LitStr)>,
+    order: Option<Vec<Ident>>,
+    loop_to: Option<LitInt>,
+    span: proc_macro2::Span,
+}
+
+impl Parse for Music {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut music = Music {
+            speed: None,
+            instruments: Vec::new(),
+            patterns: Vec::new(),
+            order: None,
+            loop_to: None,
+            span: input.span(),
+        };
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            if key == "pattern" {
+                let name = input.parse()?;
+                input.parse::<Token![=]>()?;
+                music.patterns.push((name, input.parse()?));
+            } else {
+                input.parse::<Token![=]>()?;
+                if key == "speed" {
+                    music.speed = Some(input.parse()?);
+                } else if key == "loop_to" {
+                    music.loop_to = Some(input.parse()?);
+                } else if key == "instruments" {
+                    let content;
+                    bracketed!(content in input);
+                    let list = Punctuated::<Expr, Token![,]>::parse_terminated(&content)?;
+                    music.instruments = list.into_iter().collect();
+                } else if key == "order" {
+                    let content;
+                    bracketed!(content in input);
+                    let list = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+                    music.order = Some(list.into_iter().collect());
+                } else {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "Expected `speed`, `instruments`, `pattern`, `order`, or `loop_to`",
+                    ));
+                }
+            }
+            input.parse::<Token![;]>()?;
+        }
+        Ok(music)
+    }
+}
+
+enum Step {
+    Empty,
+    Note(u8),
+    Off,
+}
+
+// A cell like `C4`, `C#4`, `Db4`, or `C-4`, an empty `..`, or an `--` off
+fn parse_step(cell: &str) -> Result<Step, String> {
+    if cell.chars().all(|c| c == '.') {
+        return Ok(Step::Empty);
+    }
+    if cell.chars().all(|c| c == '-') {
+        return Ok(Step::Off);
+    }
+    let bad = || format!("`{}` isn't a note, like `C4`, `C#4`, or `Db4`", cell);
+    let mut chars = cell.chars().peekable();
+    let note: i32 = match chars.next().map(|c| c.to_ascii_uppercase()) {
+        Some('C') => 0,
+        Some('D') => 2,
+        Some('E') => 4,
+        Some('F') => 5,
+        Some('G') => 7,
+        Some('A') => 9,
+        Some('B') => 11,
+        _ => return Err(bad()),
+    };
+    let accidental = match chars.peek() {
+        Some('#') => 1,
+        Some('b') => -1,
+        _ => 0,
+    };
+    // `-` fills in for no accidental, as in trackers
+    if matches!(chars.peek(), Some('#' | 'b' | '-')) {
+        chars.next();
+    }
+    let octave: String = chars.collect();
+    let octave: i32 = octave.parse().map_err(|_| bad())?;
+    let midi = (octave + 1) * 12 + note + accidental;
+    if !(0..=127).contains(&midi) {
+        return Err(format!("`{}` is outside of the MIDI range", cell));
+    }
+    Ok(Step::Note(midi as u8))
+}
+
+pub fn music(music: Music) -> syn::Result<TokenStream> {
+    let columns = music.instruments.len();
+    if !(1..=4).contains(&columns) {
+        return Err(syn::Error::new(music.span, "A song needs between 1 and 4 `instruments`"));
+    }
+    if music.patterns.is_empty() || music.patterns.len() > 256 {
+        return Err(syn::Error::new(music.span, "A song needs between 1 and 256 `pattern`s"));
+    }
+    let speed: u8 = match &music.speed {
+        Some(lit) => match lit.base10_parse() {
+            Ok(speed) if speed > 0 => speed,
+            _ => return Err(syn::Error::new(lit.span(), "`speed` is from 1 to 255 frames per row")),
+        },
+        None => return Err(syn::Error::new(music.span, "A song needs a `speed`, in frames per row")),
+    };
+
+    let mut patterns = Vec::new();
+    for (name, lit) in &music.patterns {
+        let mut steps = Vec::new();
+        for line in lit.value().lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let cells: Vec<&str> = line.split_whitespace().collect();
+            if cells.len() != columns {
+                let msg = format!("Every row needs {} steps, one for each instrument, but `{}` has {}", columns, line, cells.len());
+                return Err(syn::Error::new(lit.span(), msg));
+            }
+            for cell in cells {
+                steps.push(match parse_step(cell).map_err(|msg| syn::Error::new(lit.span(), msg))? {
+                    Step::Empty => quote!(::sw4::music::Step::Empty),
+                    Step::Note(midi) => quote!(::sw4::music::Step::Note(::sw4::notes::Pitch::from_midi(#midi))),
+                    Step::Off => quote!(::sw4::music::Step::Off),
+                });
+            }
+        }
+        if steps.is_empty() {
+            return Err(syn::Error::new(name.span(), format!("Pattern `{}` has no rows", name)));
+        }
+        patterns.push(quote!(::sw4::music::Pattern { steps: &[#(#steps),*] }));
+    }
+
+    // Every pattern in turn if there's no order
+    let order: Vec<u8> = match &music.order {
+        Some(order) => order
+            .iter()
+            .map(|name| {
+                let index = music.patterns.iter().position(|(p, _)| p == name);
+                index.map(|i| i as u8).ok_or_else(|| syn::Error::new(name.span(), format!("No pattern named `{}`", name)))
+            })
+            .collect::<syn::Result<_>>()?,
+        None => (0..music.patterns.len()).map(|i| i as u8).collect(),
+    };
+    let loop_to = match &music.loop_to {
+        Some(lit) => {
+            let index: u8 = lit.base10_parse()?;
+            if index as usize >= order.len() {
+                return Err(syn::Error::new(lit.span(), "`loop_to` is past the end of the `order`"));
+            }
+            quote!(::core::option::Option::Some(#index))
+        }
+        None => quote!(::core::option::Option::None),
+    };
+
+    let instruments = &music.instruments;
+    Ok(quote! {
+        ::sw4::music::Song {
+            speed: #speed,
+            instruments: &[#(#instruments),*],
+            patterns: &[#(#patterns),*],
+            order: &[#(#order),*],
+            loop_to: #loop_to,
+        }
+    })
+}
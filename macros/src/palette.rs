@@ -0,0 +1,96 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{LitStr, Token};
+
+use crate::map;
+
+// `#RRGGBB` or `#RGB`, with the `#` optional
+fn parse_hex(hex: &str) -> Option<u32> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(digits, 16).ok()?;
+    match digits.len() {
+        6 => Some(value),
+        // Each digit doubled
+        3 => Some((0..3).rev().fold(0, |rgb, i| (rgb << 8) | (((value >> (i * 4)) & 0xF) * 0x11))),
+        _ => None,
+    }
+}
+
+fn palette_tokens(colors: &[u32]) -> TokenStream {
+    let [a, b, c, d] = [colors[0], colors[1], colors[2], colors[3]];
+    quote! {
+        ::sw4::Palette {
+            a: ::sw4::Color::from_u32(#a),
+            b: ::sw4::Color::from_u32(#b),
+            c: ::sw4::Color::from_u32(#c),
+            d: ::sw4::Color::from_u32(#d),
+        }
+    }
+}
+
+pub fn palette(colors: Punctuated<LitStr, Token![,]>) -> syn::Result<TokenStream> {
+    if colors.len() != 4 {
+        return Err(syn::Error::new(Span::call_site(), "A palette needs exactly 4 colors"));
+    }
+    let values = colors
+        .iter()
+        .map(|lit| {
+            parse_hex(&lit.value())
+                .ok_or_else(|| syn::Error::new(lit.span(), "Expected a color like \"#180d2f\" or \"#fff\""))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(palette_tokens(&values))
+}
+
+// Colors in the formats lospec exports: one hex color per line (`.hex`),
+// Paint.NET's `AARRGGBB` lines with `;` comments (`.txt`), or GIMP's `R G B
+// name` lines after a header (`.gpl`)
+fn parse_file(source: &str, gimp: bool) -> Result<Vec<u32>, String> {
+    let mut colors = Vec::new();
+    for line in source.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        if gimp {
+            if line.starts_with('#') || line == "GIMP Palette" || line.contains(':') {
+                continue;
+            }
+            let rgb: Vec<u32> = line.split_whitespace().take(3).map_while(|n| n.parse().ok()).collect();
+            match rgb[..] {
+                [r, g, b] if r < 256 && g < 256 && b < 256 => colors.push((r << 16) | (g << 8) | b),
+                _ => return Err(format!("`{}` isn't an `R G B` color", line)),
+            }
+        } else {
+            let digits = line.strip_prefix('#').unwrap_or(line);
+            // Paint.NET puts the alpha first
+            let digits = if digits.len() == 8 { &digits[2..] } else { digits };
+            colors.push(parse_hex(digits).ok_or_else(|| format!("`{}` isn't a hex color", line))?);
+        }
+    }
+    Ok(colors)
+}
+
+pub fn include_palette(path: LitStr) -> syn::Result<TokenStream> {
+    let full = map::crate_path(&path);
+    let source = std::fs::read_to_string(&full)
+        .map_err(|e| syn::Error::new(path.span(), format!("Couldn't read {}: {}", full.display(), e)))?;
+    let gimp = full.extension().is_some_and(|e| e == "gpl");
+    let colors = parse_file(&source, gimp).map_err(|msg| syn::Error::new(path.span(), msg))?;
+    if colors.len() != 4 {
+        let msg = format!("A palette needs exactly 4 colors, but the file has {}", colors.len());
+        return Err(syn::Error::new(path.span(), msg));
+    }
+    let palette = palette_tokens(&colors);
+    let full = map::path_lit(&full);
+    Ok(quote! {
+        {
+            // So the palette is rebuilt when the file changes
+            const _: &[u8] = include_bytes!(#full);
+            #palette
+        }
+    })
+}
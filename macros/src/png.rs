@@ -0,0 +1,283 @@
+// Just enough of PNG to read images at compile time: every color type and bit
+// depth, without interlacing
+
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    // Row by row
+    pub pixels: Vec<[u8; 4]>,
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Image, String> {
+    const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+    if !bytes.starts_with(&SIGNATURE) {
+        return Err("not a PNG file".into());
+    }
+    let mut header = None;
+    let mut palette: &[u8] = &[];
+    let mut transparency: &[u8] = &[];
+    let mut compressed = Vec::new();
+    let mut rest = &bytes[SIGNATURE.len()..];
+    while rest.len() >= 12 {
+        let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+        let kind = &rest[4..8];
+        let data = rest.get(8..8 + len).ok_or("truncated chunk")?;
+        match kind {
+            b"IHDR" => header = Some(data),
+            b"PLTE" => palette = data,
+            b"tRNS" => transparency = data,
+            b"IDAT" => compressed.extend_from_slice(data),
+            b"IEND" => break,
+            _ => (),
+        }
+        rest = rest.get(12 + len..).ok_or("truncated chunk")?;
+    }
+    let header = header.filter(|h| h.len() >= 13).ok_or("missing IHDR chunk")?;
+    let width = u32::from_be_bytes(header[0..4].try_into().unwrap()) as usize;
+    let height = u32::from_be_bytes(header[4..8].try_into().unwrap()) as usize;
+    let depth = header[8] as usize;
+    let color_type = header[9];
+    if header[12] != 0 {
+        return Err("interlaced PNGs aren't supported".into());
+    }
+    let channels = match color_type {
+        0 | 3 => 1,
+        2 => 3,
+        4 => 2,
+        6 => 4,
+        _ => return Err(format!("unknown color type {}", color_type)),
+    };
+    if ![1, 2, 4, 8, 16].contains(&depth) {
+        return Err(format!("unknown bit depth {}", depth));
+    }
+
+    // Skip the zlib header, and ignore its checksum at the end
+    let raw = inflate(compressed.get(2..).ok_or("missing image data")?)?;
+    let bits_per_pixel = channels * depth;
+    let stride = width.checked_mul(bits_per_pixel).ok_or("image is too big")?.div_ceil(8);
+    let filter_bpp = bits_per_pixel.div_ceil(8);
+    let size = (stride + 1).checked_mul(height).ok_or("image is too big")?;
+    if raw.len() < size {
+        return Err("not enough image data".into());
+    }
+    let mut rows = vec![0u8; stride * height];
+    for y in 0..height {
+        let filter = raw[y * (stride + 1)];
+        let src = &raw[y * (stride + 1) + 1..(y + 1) * (stride + 1)];
+        let (done, row) = rows.split_at_mut(y * stride);
+        let prior = if y == 0 { None } else { Some(&done[(y - 1) * stride..]) };
+        let row = &mut row[..stride];
+        for i in 0..stride {
+            let a = if i >= filter_bpp { row[i - filter_bpp] } else { 0 };
+            let b = prior.map_or(0, |p| p[i]);
+            let c = if i >= filter_bpp { prior.map_or(0, |p| p[i - filter_bpp]) } else { 0 };
+            let predicted = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return Err(format!("unknown filter type {}", filter)),
+            };
+            row[i] = src[i].wrapping_add(predicted);
+        }
+    }
+
+    // The raw value of sample `n` in a row, as every bit depth packs them
+    let sample = |row: &[u8], n: usize| -> u16 {
+        match depth {
+            16 => u16::from_be_bytes([row[n * 2], row[n * 2 + 1]]),
+            8 => row[n] as u16,
+            _ => {
+                let bit = n * depth;
+                ((row[bit / 8] >> (8 - depth - bit % 8)) & ((1 << depth) - 1) as u8) as u16
+            }
+        }
+    };
+    let max = (1u32 << depth) - 1;
+    let scale = |v: u16| (v as u32 * 255 / max) as u8;
+    let key = |i: usize| transparency.get(i * 2..i * 2 + 2).map(|k| u16::from_be_bytes([k[0], k[1]]));
+    let mut pixels = Vec::with_capacity(width * height);
+    for row in rows.chunks(stride.max(1)).take(height) {
+        for x in 0..width {
+            let s = |c: usize| sample(row, x * channels + c);
+            let pixel = match color_type {
+                0 => {
+                    let v = s(0);
+                    let alpha = if key(0) == Some(v) { 0 } else { 255 };
+                    [scale(v), scale(v), scale(v), alpha]
+                }
+                2 => {
+                    let (r, g, b) = (s(0), s(1), s(2));
+                    let transparent = key(0) == Some(r) && key(1) == Some(g) && key(2) == Some(b);
+                    [scale(r), scale(g), scale(b), if transparent { 0 } else { 255 }]
+                }
+                3 => {
+                    let i = s(0) as usize;
+                    let rgb = palette.get(i * 3..i * 3 + 3).ok_or("palette index out of range")?;
+                    [rgb[0], rgb[1], rgb[2], transparency.get(i).copied().unwrap_or(255)]
+                }
+                4 => [scale(s(0)), scale(s(0)), scale(s(0)), scale(s(1))],
+                _ => [scale(s(0)), scale(s(1)), scale(s(2)), scale(s(3))],
+            };
+            pixels.push(pixel);
+        }
+    }
+    Ok(Image { width, height, pixels })
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+struct Bits<'a> {
+    data: &'a [u8],
+    // In bits
+    pos: usize,
+}
+
+impl Bits<'_> {
+    fn bits(&mut self, count: usize) -> Result<usize, String> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = self.data.get(self.pos / 8).ok_or("image data ended early")?;
+            value |= (((byte >> (self.pos % 8)) & 1) as usize) << i;
+            self.pos += 1;
+        }
+        Ok(value)
+    }
+
+    fn decode(&mut self, code: &Huffman) -> Result<usize, String> {
+        // Canonical codes of each length follow on from the shorter ones
+        let (mut value, mut first, mut index) = (0, 0, 0);
+        for &count in &code.counts[1..] {
+            value |= self.bits(1)?;
+            // Only a malformed code can leave `value` behind `first`
+            let offset = value.checked_sub(first).ok_or("bad Huffman code in image data")?;
+            if offset < count {
+                return code.symbols.get(index + offset).copied().ok_or_else(|| "bad Huffman code in image data".into());
+            }
+            index += count;
+            first = (first + count) << 1;
+            value <<= 1;
+        }
+        Err("bad Huffman code in image data".into())
+    }
+}
+
+struct Huffman {
+    // How many codes there are of each length
+    counts: [usize; 16],
+    // Ordered by code
+    symbols: Vec<usize>,
+}
+
+impl Huffman {
+    fn new(lengths: &[usize]) -> Self {
+        let mut counts = [0; 16];
+        for &len in lengths {
+            counts[len] += 1;
+        }
+        counts[0] = 0;
+        let mut symbols = Vec::with_capacity(lengths.len());
+        for len in 1..16 {
+            symbols.extend(lengths.iter().enumerate().filter(|(_, &l)| l == len).map(|(s, _)| s));
+        }
+        Self { counts, symbols }
+    }
+}
+
+const LEN_BASE: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LEN_EXTRA: [usize; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DIST_BASE: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097,
+    6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [usize; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+// Decompress raw DEFLATE data
+fn inflate(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut bits = Bits { data, pos: 0 };
+    let mut out = Vec::new();
+    loop {
+        let last = bits.bits(1)? == 1;
+        match bits.bits(2)? {
+            0 => {
+                let start = bits.pos.div_ceil(8);
+                let header = data.get(start..start + 4).ok_or("image data ended early")?;
+                let len = u16::from_le_bytes([header[0], header[1]]) as usize;
+                let stored = data.get(start + 4..start + 4 + len).ok_or("image data ended early")?;
+                out.extend_from_slice(stored);
+                bits.pos = (start + 4 + len) * 8;
+            }
+            1 => {
+                let mut lengths = [8; 288];
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                let literals = Huffman::new(&lengths);
+                let distances = Huffman::new(&[5; 30]);
+                inflate_block(&mut bits, &mut out, &literals, &distances)?;
+            }
+            2 => {
+                const ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+                let literal_count = bits.bits(5)? + 257;
+                let distance_count = bits.bits(5)? + 1;
+                let code_count = bits.bits(4)? + 4;
+                let mut code_lengths = [0; 19];
+                for &i in &ORDER[..code_count] {
+                    code_lengths[i] = bits.bits(3)?;
+                }
+                let code = Huffman::new(&code_lengths);
+                let mut lengths = Vec::with_capacity(literal_count + distance_count);
+                while lengths.len() < literal_count + distance_count {
+                    let (value, repeat) = match bits.decode(&code)? {
+                        len @ 0..=15 => (len, 1),
+                        16 => (*lengths.last().ok_or("bad code lengths in image data")?, 3 + bits.bits(2)?),
+                        17 => (0, 3 + bits.bits(3)?),
+                        _ => (0, 11 + bits.bits(7)?),
+                    };
+                    lengths.extend(std::iter::repeat_n(value, repeat));
+                }
+                let literals = Huffman::new(&lengths[..literal_count]);
+                let distances = Huffman::new(&lengths[literal_count..literal_count + distance_count]);
+                inflate_block(&mut bits, &mut out, &literals, &distances)?;
+            }
+            _ => return Err("bad block type in image data".into()),
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+fn inflate_block(bits: &mut Bits, out: &mut Vec<u8>, literals: &Huffman, distances: &Huffman) -> Result<(), String> {
+    loop {
+        let symbol = bits.decode(literals)?;
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let i = symbol - 257;
+                let len = LEN_BASE.get(i).ok_or("bad length in image data")? + bits.bits(LEN_EXTRA[i])?;
+                let d = bits.decode(distances)?;
+                let dist = DIST_BASE.get(d).ok_or("bad distance in image data")? + bits.bits(DIST_EXTRA[d])?;
+                let start = out.len().checked_sub(dist).ok_or("bad distance in image data")?;
+                for i in 0..len {
+                    out.push(out[start + i]);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,25 @@
+// The same PackBits format as `sw4::disk::rle_encode`, for compressing data at
+// compile time
+pub fn encode(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < input.len() {
+        let run = input[i..].iter().take(128).take_while(|&&b| b == input[i]).count();
+        if run >= 2 {
+            out.push((257 - run) as u8);
+            out.push(input[i]);
+            i += run;
+            continue;
+        }
+        let start = i;
+        while i < input.len() && i - start < 128 {
+            if input[i..].starts_with(&[input[i]; 3]) {
+                break;
+            }
+            i += 1;
+        }
+        out.push((i - start - 1) as u8);
+        out.extend_from_slice(&input[start..i]);
+    }
+    out
+}
@@ -0,0 +1,134 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields};
+
+// The size of some fields, and the code saving and loading them in order from
+// `offset`
+struct Layout {
+    size: TokenStream,
+    save: TokenStream,
+    load: TokenStream,
+}
+
+fn layout(fields: &Fields, constructor: TokenStream) -> (TokenStream, Layout) {
+    let names = (0..fields.len()).map(|i| format_ident!("field_{}", i)).collect::<Vec<_>>();
+    let types = fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+    let sizes = types.iter().map(|ty| quote!(<#ty as ::sw4::disk::Save>::SIZE)).collect::<Vec<_>>();
+    let loads = types.iter().zip(&sizes).map(|(ty, size)| {
+        quote! {{
+            let value = <#ty as ::sw4::disk::Save>::try_load(&bytes[offset..])?;
+            offset += #size;
+            value
+        }}
+    });
+    let (pattern, load) = match fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
+            (quote!({ #(#idents: #names),* }), quote!(#constructor { #(#idents: #loads),* }))
+        }
+        Fields::Unnamed(_) => (quote!(( #(#names),* )), quote!(#constructor ( #(#loads),* ))),
+        Fields::Unit => (quote!(), constructor),
+    };
+    let save = quote! {
+        #(
+            ::sw4::disk::Save::save(#names, &mut out[offset..]);
+            offset += #sizes;
+        )*
+    };
+    (pattern, Layout { size: quote!(0 #(+ #sizes)*), save, load })
+}
+
+pub fn derive_save(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::sw4::disk::Save));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (size, save, load) = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, Layout { size, save, load }) = layout(&data.fields, quote!(Self));
+            (size, quote!(let Self #pattern = self; #save), quote!(::core::option::Option::Some(#load)))
+        }
+        Data::Enum(data) => {
+            if data.variants.len() > 256 {
+                return Err(syn::Error::new(name.span(), "`Save` enums can have at most 256 variants"));
+            }
+            // A byte for the variant, then the largest variant's fields
+            let mut sizes = Vec::new();
+            let mut save_arms = Vec::new();
+            let mut load_arms = Vec::new();
+            for (i, variant) in data.variants.iter().enumerate() {
+                let variant_name = &variant.ident;
+                let tag = i as u8;
+                let (pattern, Layout { size, save, load }) = layout(&variant.fields, quote!(Self::#variant_name));
+                sizes.push(size);
+                save_arms.push(quote! {
+                    Self::#variant_name #pattern => {
+                        out[0] = #tag;
+                        #save
+                    }
+                });
+                load_arms.push(quote!(#tag => ::core::option::Option::Some(#load),));
+            }
+            let size = quote! {{
+                let mut largest = 0;
+                #(
+                    if #sizes > largest {
+                        largest = #sizes;
+                    }
+                )*
+                1 + largest
+            }};
+            let save = quote! {
+                out[..<Self as ::sw4::disk::Save>::SIZE].fill(0);
+                offset += 1;
+                match self {
+                    #(#save_arms)*
+                }
+            };
+            // An unknown variant is from a damaged disk or a different version
+            let load = quote! {
+                offset += 1;
+                match bytes[0] {
+                    #(#load_arms)*
+                    _ => ::core::option::Option::None,
+                }
+            };
+            (size, save, load)
+        }
+        Data::Union(_) => return Err(syn::Error::new(name.span(), "`Save` can't be derived for unions")),
+    };
+
+    let message = format!("`{}` is too large to fit on the disk", name);
+    let invalid = format!("invalid `{}` in saved data", name);
+    Ok(quote! {
+        impl #impl_generics ::sw4::disk::Save for #name #ty_generics #where_clause {
+            const SIZE: usize = {
+                let size = #size;
+                ::core::assert!(size <= ::sw4::Disk::CAPACITY, #message);
+                size
+            };
+
+            #[allow(unused_variables, unused_mut, unused_assignments)]
+            fn save(&self, out: &mut [u8]) {
+                let mut offset = 0;
+                #save
+            }
+
+            fn load(bytes: &[u8]) -> Self {
+                match <Self as ::sw4::disk::Save>::try_load(bytes) {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => ::sw4::panic(#invalid),
+                }
+            }
+
+            #[allow(unused_variables, unused_mut, unused_assignments)]
+            fn try_load(bytes: &[u8]) -> ::core::option::Option<Self> {
+                let mut offset = 0;
+                #load
+            }
+        }
+    })
+}
@@ -0,0 +1,168 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitFloat, LitInt, Token};
+
+// Starts out the same as `sw4::Sound::builder()`
+pub struct SoundDsl {
+    start_freq: u16,
+    end_freq: u16,
+    attack: u8,
+    decay: u8,
+    sustain: u8,
+    release: u8,
+    peak_vol: u8,
+    sustain_vol: u8,
+    channel: TokenStream,
+    pan: Ident,
+}
+
+fn int<T: std::str::FromStr>(input: ParseStream, message: &str) -> syn::Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    let lit: LitInt = input.parse()?;
+    lit.base10_parse().map_err(|_| syn::Error::new(lit.span(), message))
+}
+
+fn volume(input: ParseStream) -> syn::Result<u8> {
+    let lit: LitInt = input.parse()?;
+    match lit.base10_parse::<u8>() {
+        Ok(vol) if vol <= 100 => Ok(vol),
+        _ => Err(syn::Error::new(lit.span(), "Volumes go from 0 to 100")),
+    }
+}
+
+fn duty_cycle(input: ParseStream) -> syn::Result<Ident> {
+    let span = input.span();
+    let percent = if input.peek(LitFloat) {
+        input.parse::<LitFloat>()?.base10_digits().to_string()
+    } else {
+        input.parse::<LitInt>()?.base10_digits().to_string()
+    };
+    input.parse::<Token![%]>()?;
+    let name = match percent.as_str() {
+        "12.5" => "Eighth",
+        "25" | "25.0" => "Quarter",
+        "50" | "50.0" => "Half",
+        "75" | "75.0" => "ThreeQuarters",
+        _ => return Err(syn::Error::new(span, "Expected a duty cycle of 12.5%, 25%, 50%, or 75%")),
+    };
+    Ok(Ident::new(name, Span::call_site()))
+}
+
+impl SoundDsl {
+    fn section(&mut self, input: ParseStream) -> syn::Result<()> {
+        if input.peek(LitInt) {
+            self.start_freq = int(input, "Frequencies go up to 65535 Hz")?;
+            self.end_freq = self.start_freq;
+            if input.peek(Token![->]) {
+                input.parse::<Token![->]>()?;
+                self.end_freq = int(input, "Frequencies go up to 65535 Hz")?;
+            }
+            if input.peek(Ident) {
+                let unit: Ident = input.parse()?;
+                if unit != "Hz" {
+                    return Err(syn::Error::new(unit.span(), "Expected `Hz`"));
+                }
+            }
+            return Ok(());
+        }
+        let key: Ident = input.parse()?;
+        if key == "pulse1" || key == "pulse2" {
+            let duty = if input.peek(Token![@]) {
+                input.parse::<Token![@]>()?;
+                duty_cycle(input)?
+            } else {
+                Ident::new("Half", Span::call_site())
+            };
+            let channel = Ident::new(if key == "pulse1" { "Pulse1" } else { "Pulse2" }, key.span());
+            self.channel = quote!(::sw4::Channel::#channel(::sw4::DutyCycle::#duty));
+        } else if key == "triangle" {
+            self.channel = quote!(::sw4::Channel::Triangle);
+        } else if key == "noise" {
+            self.channel = quote!(::sw4::Channel::Noise);
+        } else if key == "vol" {
+            self.peak_vol = volume(input)?;
+            self.sustain_vol = self.peak_vol;
+            if input.peek(Token![/]) {
+                input.parse::<Token![/]>()?;
+                self.sustain_vol = volume(input)?;
+            }
+        } else if key == "pan" {
+            let side: Ident = input.parse()?;
+            self.pan = match side.to_string().as_str() {
+                "left" => Ident::new("Left", side.span()),
+                "right" => Ident::new("Right", side.span()),
+                "center" => Ident::new("Center", side.span()),
+                _ => return Err(syn::Error::new(side.span(), "Expected `left`, `right`, or `center`")),
+            };
+        } else if ["a", "d", "s", "r"].iter().any(|k| key == k) {
+            // The envelope, with each stage in frames
+            let mut stage = Some(key);
+            while let Some(key) = stage.take() {
+                input.parse::<Token![:]>()?;
+                let frames = int(input, "Envelope stages are at most 255 frames")?;
+                match key.to_string().as_str() {
+                    "a" => self.attack = frames,
+                    "d" => self.decay = frames,
+                    "s" => self.sustain = frames,
+                    "r" => self.release = frames,
+                    _ => return Err(syn::Error::new(key.span(), "Expected `a`, `d`, `s`, or `r`")),
+                }
+                if input.peek(Ident) {
+                    stage = Some(input.parse()?);
+                }
+            }
+        } else {
+            return Err(syn::Error::new(
+                key.span(),
+                "Expected a channel, a frequency, an envelope, `vol`, or `pan`",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Parse for SoundDsl {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut sound = SoundDsl {
+            start_freq: 440,
+            end_freq: 440,
+            attack: 0,
+            decay: 0,
+            sustain: 10,
+            release: 0,
+            peak_vol: 100,
+            sustain_vol: 100,
+            channel: quote!(::sw4::Channel::Pulse1(::sw4::DutyCycle::Half)),
+            pan: Ident::new("Center", Span::call_site()),
+        };
+        while !input.is_empty() {
+            sound.section(input)?;
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(sound)
+    }
+}
+
+pub fn sound(sound: SoundDsl) -> TokenStream {
+    let SoundDsl { start_freq, end_freq, attack, decay, sustain, release, peak_vol, sustain_vol, channel, pan } =
+        sound;
+    quote! {
+        ::sw4::Sound {
+            start_freq: #start_freq,
+            end_freq: #end_freq,
+            attack: #attack,
+            decay: #decay,
+            sustain: #sustain,
+            release: #release,
+            peak_vol: #peak_vol,
+            sustain_vol: #sustain_vol,
+            channel: #channel,
+            pan: ::sw4::Pan::#pan,
+        }
+    }
+}
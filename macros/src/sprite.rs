@@ -0,0 +1,81 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitStr, Token};
+
+const DEFAULT_CHARS: &str = ".#xo";
+
+pub struct SpriteArt {
+    chars: Option<LitStr>,
+    rows: Vec<LitStr>,
+}
+
+impl Parse for SpriteArt {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut chars = None;
+        if input.peek(Ident) {
+            let key: Ident = input.parse()?;
+            if key != "chars" {
+                return Err(syn::Error::new(key.span(), "Expected `chars` or a string"));
+            }
+            input.parse::<Token![=]>()?;
+            chars = Some(input.parse()?);
+            input.parse::<Token![,]>()?;
+        }
+        let rows = input.parse_terminated::<LitStr, Token![,]>(Parse::parse)?;
+        Ok(SpriteArt { chars, rows: rows.into_iter().collect() })
+    }
+}
+
+pub fn sprite(art: SpriteArt) -> syn::Result<TokenStream> {
+    let chars: Vec<char> = match &art.chars {
+        Some(lit) => lit.value().chars().collect(),
+        None => DEFAULT_CHARS.chars().collect(),
+    };
+    if chars.is_empty() || chars.len() > 4 {
+        let span = art.chars.as_ref().map_or_else(Span::call_site, |lit| lit.span());
+        return Err(syn::Error::new(span, "`chars` needs between 1 and 4 characters"));
+    }
+
+    // A literal can hold several rows on separate lines, unless whitespace is
+    // a pixel, in which case each literal is exactly one row
+    let literal_rows = chars.iter().any(|c| c.is_whitespace());
+    let mut pixels = Vec::new();
+    let mut width = None;
+    let mut height = 0u32;
+    for lit in &art.rows {
+        let value = lit.value();
+        let lines: Vec<&str> = if literal_rows {
+            vec![&value]
+        } else {
+            value.lines().map(str::trim).filter(|l| !l.is_empty()).collect()
+        };
+        for line in lines {
+            let mut row_width = 0u32;
+            for c in line.chars() {
+                let Some(value) = chars.iter().position(|&p| p == c) else {
+                    return Err(syn::Error::new(lit.span(), format!("`{}` isn't one of {:?}", c, chars)));
+                };
+                pixels.push(value as u8);
+                row_width += 1;
+            }
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(syn::Error::new(lit.span(), "Every row must be the same width"));
+            }
+            height += 1;
+        }
+    }
+    let width = width.unwrap_or(0);
+
+    let two_bpp = pixels.iter().any(|&p| p > 1);
+    let bpp = if two_bpp { 2 } else { 1 };
+    let mut bytes = vec![0u8; (pixels.len() * bpp).div_ceil(8)];
+    for (i, &p) in pixels.iter().enumerate() {
+        let bit = i * bpp;
+        bytes[bit / 8] |= p << (8 - bpp - bit % 8);
+    }
+    let flags = if two_bpp { quote!(TWO_BPP) } else { quote!(ONE_BPP) };
+    Ok(quote! {
+        ::sw4::Sprite::new(&[#(#bytes),*], #width, #height, ::sw4::SpriteFlags::#flags)
+    })
+}
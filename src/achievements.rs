@@ -0,0 +1,153 @@
+//! Achievements kept on the disk, apart from any saves
+//! 
+//! Each achievement is one bit at the end of the disk (or anywhere else), so
+//! saves go in the rest of it, like
+//! `SaveSlots::with_region(0, ACHIEVEMENTS.start())`.
+//! 
+//! Only [`SaveSlots`](crate::disk::SaveSlots) and
+//! [`Settings::save`](crate::settings::Settings::save) keep the rest of the
+//! disk when they write. [`Disk::save`], [`Disk::save_versioned`],
+//! [`Disk::write_compressed`], and [`Disk::write_all`] replace the whole disk,
+//! erasing the achievements.
+//! 
+//! ```ignore
+//! const ACHIEVEMENTS: Achievements<16> = Achievements::at_end();
+//! const SLOTS: SaveSlots<3> = SaveSlots::with_region(0, ACHIEVEMENTS.start());
+//! 
+//! if ACHIEVEMENTS.unlock(&w.disk, Achievement::FirstJump as usize) {
+//!     state.toast.show("First jump");
+//! }
+//! state.toast.tick();
+//! state.toast.draw(&mut w.frame_buffer);
+//! ```
+
+use crate::disk::read_image;
+use crate::{assert, read_draw_colors, write_draw_colors, Disk, FrameBuffer};
+
+/// Where `N` achievements are stored on the disk
+pub struct Achievements<const N: usize> {
+    start: usize,
+}
+
+impl<const N: usize> Achievements<N> {
+    /// The number of bytes the achievements take up on the disk
+    pub const SIZE: usize = N.div_ceil(8);
+
+    /// Store the achievements at the very end of the disk
+    /// 
+    /// Saves sharing the disk have to go through `SaveSlots` or `Settings`,
+    /// see the [module docs](self).
+    pub const fn at_end() -> Self {
+        Self::at(Disk::CAPACITY - Self::SIZE)
+    }
+
+    /// Store the achievements starting `start` bytes into the disk
+    /// 
+    /// Saves sharing the disk have to go through `SaveSlots` or `Settings`,
+    /// see the [module docs](self).
+    pub const fn at(start: usize) -> Self {
+        core::assert!(N > 0, "there must be at least one achievement");
+        core::assert!(start + Self::SIZE <= Disk::CAPACITY, "achievements go past the end of the disk");
+        Self { start }
+    }
+
+    /// The first byte of the disk the achievements use
+    pub const fn start(&self) -> usize {
+        self.start
+    }
+
+    fn bit(&self, id: usize) -> (usize, u8) {
+        assert(id < N, "achievement out of range");
+        (self.start + id / 8, 1 << (id % 8))
+    }
+
+    /// Unlock an achievement, returning `true` if it wasn't already unlocked
+    pub fn unlock(&self, disk: &Disk, id: usize) -> bool {
+        let (byte, mask) = self.bit(id);
+        let (mut image, len) = read_image(disk);
+        if image[byte] & mask != 0 {
+            return false;
+        }
+        image[byte] |= mask;
+        disk.write_all(&image[..len.max(byte + 1)]);
+        true
+    }
+
+    pub fn is_unlocked(&self, disk: &Disk, id: usize) -> bool {
+        let (byte, mask) = self.bit(id);
+        let (image, _) = read_image(disk);
+        image[byte] & mask != 0
+    }
+
+    /// How many achievements are unlocked
+    pub fn unlocked_count(&self, disk: &Disk) -> usize {
+        let (image, _) = read_image(disk);
+        (0..N).filter(|id| image[self.start + id / 8] & (1 << (id % 8)) != 0).count()
+    }
+
+    /// Lock every achievement again
+    pub fn reset(&self, disk: &Disk) {
+        let (mut image, len) = read_image(disk);
+        let range = self.start..self.start + Self::SIZE;
+        // Nothing to reset if the disk doesn't reach them
+        if range.start < len {
+            image[range].fill(0);
+            disk.write_all(&image[..len]);
+        }
+    }
+}
+
+// How long a toast slides for, and stays on screen for in total
+const SLIDE_FRAMES: u32 = 10;
+const TOAST_FRAMES: u32 = 150;
+const TOAST_HEIGHT: i32 = 21;
+
+/// A box that slides down from the top of the screen for a few seconds when
+/// an achievement is unlocked
+/// 
+/// Call [`Toast::tick`] and [`Toast::draw`] every frame. Like
+/// [`DialogBox`](crate::dialog::DialogBox), the box is drawn with the current
+/// draw colors and its text with draw color 2.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Toast<'a> {
+    name: &'a str,
+    remaining: u32,
+}
+
+impl<'a> Toast<'a> {
+    pub const fn new() -> Self {
+        Self { name: "", remaining: 0 }
+    }
+
+    /// Show an achievement, replacing any that's already showing
+    /// 
+    /// Names longer than 18 characters run off the side of the box.
+    pub fn show(&mut self, name: &'a str) {
+        self.name = name;
+        self.remaining = TOAST_FRAMES;
+    }
+
+    /// Advance a frame
+    pub fn tick(&mut self) {
+        self.remaining = self.remaining.saturating_sub(1);
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.remaining > 0
+    }
+
+    pub fn draw(&self, fb: &mut FrameBuffer) {
+        if !self.is_visible() {
+            return;
+        }
+        // Sliding in for the first frames, and out for the last
+        let shown = (TOAST_FRAMES - self.remaining).min(self.remaining).min(SLIDE_FRAMES) as i32;
+        let y = -TOAST_HEIGHT + TOAST_HEIGHT * shown / SLIDE_FRAMES as i32;
+        fb.rect(4, y, 152, TOAST_HEIGHT as u32);
+        let colors = read_draw_colors();
+        write_draw_colors((colors >> 4) & 0xF);
+        fb.text("Achievement!", 8, y + 2);
+        fb.text(self.name, 8, y + 11);
+        write_draw_colors(colors);
+    }
+}
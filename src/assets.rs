@@ -0,0 +1,14 @@
+//! Support for [`assets!`](crate::assets!), which packs sprites, maps, 
+//! sounds, and other data into one blob
+//! 
+//! See the macro for how to use it.
+
+#[doc(hidden)]
+pub const fn __put<const N: usize>(mut blob: [u8; N], offset: usize, bytes: &[u8]) -> [u8; N] {
+    let mut i = 0;
+    while i < bytes.len() {
+        blob[offset + i] = bytes[i];
+        i += 1;
+    }
+    blob
+}
@@ -0,0 +1,228 @@
+//! Playing sounds over time
+
+use core::marker::PhantomData;
+
+use crate::{Channel, DutyCycle, Sound, SoundSystem};
+
+/// Plays sounds some number of frames in the future
+/// 
+/// Call [`AudioScheduler::tick`] once every frame. Holds up to `N` sounds at
+/// once.
+pub struct AudioScheduler<const N: usize> {
+    // (frames until it plays, sound)
+    queue: [Option<(u32, Sound)>; N],
+}
+
+impl<const N: usize> AudioScheduler<N> {
+    pub const fn new() -> Self {
+        Self { queue: [None; N] }
+    }
+
+    /// Play a sound after `delay` frames, where 0 is the next tick
+    /// 
+    /// Returns `false` if the queue is full, in which case the sound is
+    /// dropped
+    pub fn schedule(&mut self, delay: u32, sound: Sound) -> bool {
+        match self.queue.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some((delay, sound));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Play each sound in turn, `interval` frames apart, starting after
+    /// `delay` frames
+    /// 
+    /// Returns `false` if the queue filled up or a sound's delay doesn't fit in
+    /// a `u32`, in which case the rest of the sounds are dropped
+    pub fn schedule_sequence(&mut self, delay: u32, interval: u32, sounds: &[Sound]) -> bool {
+        sounds.iter().enumerate().all(|(i, &sound)| {
+            match interval.checked_mul(i as u32).and_then(|d| d.checked_add(delay)) {
+                Some(delay) => self.schedule(delay, sound),
+                None => false,
+            }
+        })
+    }
+
+    /// Play every sound that's due, and move the rest one frame closer
+    pub fn tick(&mut self, sounds: &SoundSystem) {
+        for slot in &mut self.queue {
+            match slot {
+                Some((0, sound)) => {
+                    sounds.play(*sound);
+                    *slot = None;
+                }
+                Some((delay, _)) => *delay -= 1,
+                None => (),
+            }
+        }
+    }
+
+    /// The number of sounds waiting to play
+    pub fn len(&self) -> usize {
+        self.queue.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.iter().all(Option::is_none)
+    }
+
+    /// Cancel every waiting sound
+    pub fn clear(&mut self) {
+        self.queue = [None; N];
+    }
+}
+
+impl<const N: usize> Default for AudioScheduler<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A type for one of the sound channels, see [`ChannelGuard`]
+pub trait ChannelKind: sealed::Sealed {
+    #[doc(hidden)]
+    const INDEX: usize;
+    #[doc(hidden)]
+    fn channel(requested: Channel) -> Channel;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! channel_kinds {
+    ($($name:ident = $index:literal => |$requested:ident| $channel:expr;)*) => {
+        $(
+            /// A channel that can be reserved, see [`ChannelGuard`]
+            pub enum $name {}
+
+            impl sealed::Sealed for $name {}
+
+            impl ChannelKind for $name {
+                const INDEX: usize = $index;
+                fn channel($requested: Channel) -> Channel {
+                    $channel
+                }
+            }
+        )*
+    };
+}
+
+channel_kinds! {
+    Pulse1 = 0 => |requested| match requested {
+        Channel::Pulse1(dc) | Channel::Pulse2(dc) => Channel::Pulse1(dc),
+        _ => Channel::Pulse1(DutyCycle::Half),
+    };
+    Pulse2 = 1 => |requested| match requested {
+        Channel::Pulse1(dc) | Channel::Pulse2(dc) => Channel::Pulse2(dc),
+        _ => Channel::Pulse2(DutyCycle::Half),
+    };
+    Triangle = 2 => |_requested| Channel::Triangle;
+    Noise = 3 => |_requested| Channel::Noise;
+}
+
+// Who has each channel. Owners are identified by a generation number, with 0
+// meaning nobody. Generations only repeat after `u32::MAX` guards, which even
+// a new guard every frame takes over two years to reach
+struct Ownership {
+    owner: [u32; 4],
+    // The owner a channel was stolen from, that gets it back afterwards
+    stolen_from: [u32; 4],
+    next_generation: u32,
+}
+
+#[allow(deprecated)]
+static OWNERSHIP: crate::SyncUnsafeCell<Ownership> = crate::SyncUnsafeCell::new(Ownership {
+    owner: [0; 4],
+    stolen_from: [0; 4],
+    next_generation: 1,
+});
+
+fn ownership() -> &'static mut Ownership {
+    // wasm4 is single-threaded, and this reference never escapes the functions
+    // in this module
+    unsafe { &mut *OWNERSHIP.get() }
+}
+
+/// Exclusive use of a sound channel, so separate parts of a game (like music 
+/// and sound effects) don't cut off each other's sounds
+/// 
+/// Get one from [`SoundSystem::reserve`] or [`SoundSystem::steal`]. The
+/// channel is released when the guard is dropped.
+/// 
+/// A stolen channel goes back to its previous owner once the thief's guard
+/// is dropped. In the meantime, the previous owner's guard is inactive and
+/// its sounds are silently skipped.
+/// 
+/// Guards are told apart by a 32-bit counter, so a guard kept around while
+/// over 4 billion others are made could be mistaken for a newer one.
+pub struct ChannelGuard<C: ChannelKind> {
+    generation: u32,
+    _channel: PhantomData<C>,
+}
+
+impl<C: ChannelKind> ChannelGuard<C> {
+    pub(crate) fn reserve() -> Option<Self> {
+        let state = ownership();
+        if state.owner[C::INDEX] != 0 {
+            return None;
+        }
+        Some(Self::take(state))
+    }
+
+    pub(crate) fn steal() -> Self {
+        let state = ownership();
+        // Only the original owner is remembered, anyone in between loses it
+        if state.stolen_from[C::INDEX] == 0 {
+            state.stolen_from[C::INDEX] = state.owner[C::INDEX];
+        }
+        Self::take(state)
+    }
+
+    fn take(state: &mut Ownership) -> Self {
+        let generation = state.next_generation;
+        state.next_generation = generation.checked_add(1).unwrap_or(1);
+        state.owner[C::INDEX] = generation;
+        Self {
+            generation,
+            _channel: PhantomData,
+        }
+    }
+
+    /// Does this guard currently have the channel?
+    /// 
+    /// This is only false while the channel is stolen
+    pub fn is_active(&self) -> bool {
+        ownership().owner[C::INDEX] == self.generation
+    }
+
+    /// Play a sound on this channel
+    /// 
+    /// The sound's channel is replaced with this one, keeping its duty cycle
+    /// for the pulse channels. Nothing is played if the channel is stolen.
+    /// Returns whether the sound was played.
+    pub fn play(&self, sounds: &SoundSystem, mut sound: Sound) -> bool {
+        if !self.is_active() {
+            return false;
+        }
+        sound.channel = C::channel(sound.channel);
+        sounds.play(sound);
+        true
+    }
+}
+
+impl<C: ChannelKind> Drop for ChannelGuard<C> {
+    fn drop(&mut self) {
+        let state = ownership();
+        if state.owner[C::INDEX] == self.generation {
+            state.owner[C::INDEX] = state.stolen_from[C::INDEX];
+            state.stolen_from[C::INDEX] = 0;
+        } else if state.stolen_from[C::INDEX] == self.generation {
+            // Dropped while stolen, so there's nobody to give it back to
+            state.stolen_from[C::INDEX] = 0;
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Replacing characters wasm4's font can't draw
+//! 
+//! The font has printable ASCII, the Latin-1 supplement, and the
+//! [`glyphs`](crate::glyphs). Anything else draws as garbage, so 
+//! [`FrameBuffer::text_sanitized`](crate::FrameBuffer::text_sanitized) swaps
+//! it for the closest thing the font has, or `?`.
+
+/// What's drawn for characters with no close equivalent
+pub const FALLBACK: &str = "?";
+
+#[allow(deprecated)]
+static STRICT: crate::SyncUnsafeCell<bool> = crate::SyncUnsafeCell::new(false);
+
+/// With strict mode on, sanitizing a character that has to be replaced traps 
+/// in debug builds, to catch missing characters during development
+pub fn set_strict(strict: bool) {
+    unsafe { *STRICT.get() = strict }
+}
+
+/// Can the font draw `c`?
+pub const fn is_supported(c: char) -> bool {
+    matches!(c, '\n' | ' '..='~' | '\u{80}'..='\u{87}' | '\u{A0}'..='\u{FF}')
+}
+
+/// What to draw instead of `c`, or `None` if it's supported already
+pub fn replacement(c: char) -> Option<&'static str> {
+    if is_supported(c) {
+        return None;
+    }
+    Some(match c {
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{2032}' => "'",
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{2033}' => "\"",
+        '\u{2010}'..='\u{2015}' | '\u{2212}' => "-",
+        '\u{2026}' => "...",
+        '\u{2022}' | '\u{2027}' => "\u{B7}",
+        '\u{2039}' => "<",
+        '\u{203A}' => ">",
+        '\u{20AC}' => "EUR",
+        '\u{2122}' => "TM",
+        '\t' => " ",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Ĳ' => "IJ",
+        'ĳ' => "ij",
+        c => latin_extended_a(c).unwrap_or(FALLBACK),
+    })
+}
+
+// The base letters of Latin Extended-A, which mostly go in pairs of
+// uppercase and lowercase
+fn latin_extended_a(c: char) -> Option<&'static str> {
+    const BASES: &str = "AaAaAaCcCcCcCcDdDdEeEeEeEeEeGgGgGgGgHhHhIiIiIiIiIi??JjKkkLlLlLlLlLlNnNnNnnNn\
+OoOoOo??RrRrRrSsSsSsSsTtTtTtUuUuUuUuUuUuWwYyYZzZzZzs";
+    let index = (c as u32).checked_sub(0x100)? as usize;
+    // Every base is ASCII, so any index is a char boundary
+    let base = BASES.get(index..index + 1)?;
+    (base != "?").then_some(base)
+}
+
+/// Copy `s` into `out` with every unsupported character replaced, cutting it 
+/// off if it doesn't fit
+pub fn sanitize<'a>(s: &str, out: &'a mut [u8]) -> &'a str {
+    let strict = cfg!(debug_assertions) && unsafe { *STRICT.get() };
+    let mut len = 0;
+    let mut utf8 = [0; 4];
+    for c in s.chars() {
+        let piece = match replacement(c) {
+            None => c.encode_utf8(&mut utf8),
+            Some(_) if strict => crate::panic("unsupported character in text"),
+            Some(r) => r,
+        };
+        let Some(dest) = out.get_mut(len..len + piece.len()) else {
+            break;
+        };
+        dest.copy_from_slice(piece.as_bytes());
+        len += piece.len();
+    }
+    // Only whole chars were copied in
+    unsafe { core::str::from_utf8_unchecked(&out[..len]) }
+}
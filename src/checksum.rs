@@ -0,0 +1,179 @@
+//! Fast hashing of game state, for finding netplay desyncs
+//! 
+//! Hash the game state at the end of every frame and [`trace`](crate::trace)
+//! it; the first frame where two clients disagree is where they desynced.
+//! 
+//! ```ignore
+//! use sw4::checksum::{self, Checksum};
+//! 
+//! #[derive(Checksum)]
+//! struct State {
+//!     players: [Player; 4],
+//!     frame: u32,
+//! }
+//! 
+//! let hash = checksum::checksum(&state);
+//! ```
+
+pub use sw4_macros::Checksum;
+
+/// An incremental 32-bit FNV-1a hasher
+#[derive(Clone, Copy)]
+pub struct Hasher(u32);
+
+impl Hasher {
+    const OFFSET_BASIS: u32 = 0x811c9dc5;
+    const PRIME: u32 = 0x01000193;
+
+    pub const fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u8(byte);
+        }
+    }
+
+    pub fn write_u8(&mut self, x: u8) {
+        self.0 = (self.0 ^ x as u32).wrapping_mul(Self::PRIME);
+    }
+
+    pub fn write_u16(&mut self, x: u16) {
+        self.write(&x.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, x: u32) {
+        self.write(&x.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, x: u64) {
+        self.write(&x.to_le_bytes());
+    }
+
+    /// The hash of everything written so far
+    pub const fn finish(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A value that can be hashed for comparing between clients
+/// 
+/// Unlike `core::hash::Hash`, the hash must only depend on the value itself,
+/// so it's the same on every client. Can be derived.
+pub trait Checksum {
+    fn checksum(&self, hasher: &mut Hasher);
+}
+
+/// Hash a single value
+pub fn checksum<T: Checksum + ?Sized>(value: &T) -> u32 {
+    let mut hasher = Hasher::new();
+    value.checksum(&mut hasher);
+    hasher.finish()
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {
+        $(
+            impl Checksum for $t {
+                fn checksum(&self, hasher: &mut Hasher) {
+                    hasher.write(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Checksum for bool {
+    fn checksum(&self, hasher: &mut Hasher) {
+        hasher.write_u8(*self as u8);
+    }
+}
+
+impl Checksum for char {
+    fn checksum(&self, hasher: &mut Hasher) {
+        hasher.write_u32(*self as u32);
+    }
+}
+
+impl Checksum for () {
+    fn checksum(&self, _hasher: &mut Hasher) {}
+}
+
+impl<T: Checksum + ?Sized> Checksum for &T {
+    fn checksum(&self, hasher: &mut Hasher) {
+        (**self).checksum(hasher);
+    }
+}
+
+impl<T: Checksum> Checksum for [T] {
+    fn checksum(&self, hasher: &mut Hasher) {
+        hasher.write_u32(self.len() as u32);
+        for x in self {
+            x.checksum(hasher);
+        }
+    }
+}
+
+impl<T: Checksum, const N: usize> Checksum for [T; N] {
+    fn checksum(&self, hasher: &mut Hasher) {
+        for x in self {
+            x.checksum(hasher);
+        }
+    }
+}
+
+impl Checksum for str {
+    fn checksum(&self, hasher: &mut Hasher) {
+        hasher.write_u32(self.len() as u32);
+        hasher.write(self.as_bytes());
+    }
+}
+
+impl<T: Checksum> Checksum for Option<T> {
+    fn checksum(&self, hasher: &mut Hasher) {
+        match self {
+            None => hasher.write_u8(0),
+            Some(x) => {
+                hasher.write_u8(1);
+                x.checksum(hasher);
+            }
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($(($($name:ident),*)),*) => {
+        $(
+            impl<$($name: Checksum),*> Checksum for ($($name,)*) {
+                #[allow(non_snake_case)]
+                fn checksum(&self, hasher: &mut Hasher) {
+                    let ($($name,)*) = self;
+                    $($name.checksum(hasher);)*
+                }
+            }
+        )*
+    };
+}
+
+impl_tuple!((A), (A, B), (A, B, C), (A, B, C, D));
+
+impl Checksum for crate::fixed::Fixed {
+    fn checksum(&self, hasher: &mut Hasher) {
+        self.to_bits().checksum(hasher);
+    }
+}
+
+impl Checksum for crate::Buttons {
+    fn checksum(&self, hasher: &mut Hasher) {
+        hasher.write_u8(self.bits());
+    }
+}
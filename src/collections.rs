@@ -0,0 +1,522 @@
+//! Lists with a fixed capacity, for when the number of things changes but
+//! there's no allocator
+//! 
+//! Each one is stored inline, taking up exactly `N` values' worth of memory
+//! plus a length, with no `Option` per slot.
+
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+
+/// Like a `Vec`, but holding at most `N` values
+/// 
+/// ```ignore
+/// let mut bullets = ArrayVec::<Bullet, 32>::new();
+/// if bullets.push(Bullet::new(x, y)).is_err() {
+///     // Too many bullets already
+/// }
+/// bullets.retain(|b| b.on_screen());
+/// ```
+pub struct ArrayVec<T, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            items: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        // The first `len` items are always initialized
+        unsafe { core::slice::from_raw_parts(self.items.as_ptr().cast(), self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { core::slice::from_raw_parts_mut(self.items.as_mut_ptr().cast(), self.len) }
+    }
+
+    /// Add a value to the end, or give it back if the list is full
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.items[self.len].write(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Remove the last value
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(unsafe { self.items[self.len].assume_init_read() })
+    }
+
+    /// Put a value at `index`, moving everything after it along, or give it
+    /// back if the list is full
+    /// 
+    /// Traps if `index` is past the end.
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), T> {
+        crate::assert(index <= self.len, "ArrayVec::insert index out of range");
+        if self.is_full() {
+            return Err(value);
+        }
+        self.items[index..=self.len].rotate_right(1);
+        self.items[index].write(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Take out the value at `index`, moving everything after it back
+    /// 
+    /// Traps if `index` is out of range.
+    pub fn remove(&mut self, index: usize) -> T {
+        crate::assert(index < self.len, "ArrayVec::remove index out of range");
+        let value = unsafe { self.items[index].assume_init_read() };
+        self.items[index..self.len].rotate_left(1);
+        self.len -= 1;
+        value
+    }
+
+    /// Take out the value at `index`, replacing it with the last one, which
+    /// is faster than [`remove`](Self::remove) but changes the order
+    /// 
+    /// Traps if `index` is out of range.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        crate::assert(index < self.len, "ArrayVec::swap_remove index out of range");
+        self.items.swap(index, self.len - 1);
+        self.len -= 1;
+        unsafe { self.items[self.len].assume_init_read() }
+    }
+
+    /// Remove every value that `f` returns `false` for, keeping the order
+    pub fn retain(&mut self, mut f: impl FnMut(&mut T) -> bool) {
+        let len = self.len;
+        // Nothing is dropped twice if `f` unwinds, just leaked
+        self.len = 0;
+        let mut kept = 0;
+        for i in 0..len {
+            let keep = f(unsafe { self.items[i].assume_init_mut() });
+            if keep {
+                self.items.swap(kept, i);
+                kept += 1;
+            } else {
+                unsafe { self.items[i].assume_init_drop() }
+            }
+        }
+        self.len = kept;
+    }
+
+    /// Remove everything after the first `len` values
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            drop(self.pop());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut clone = Self::new();
+        for value in self.iter() {
+            let _ = clone.push(value.clone());
+        }
+        clone
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for ArrayVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayVec<T, N> {}
+
+impl<T, const N: usize> Deref for ArrayVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArrayVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Like a `String`, but holding at most `N` bytes
+/// 
+/// Writing with `write!` fails if the text doesn't fit, after writing as much
+/// of it as it could.
+#[derive(Clone, Copy)]
+pub struct ArrayString<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> ArrayString<N> {
+    pub const fn new() -> Self {
+        Self { buf: [0; N], len: 0 }
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only whole `str`s and `char`s are ever copied in
+        unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+
+    /// The length in bytes
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Add `s` to the end, or return `false` and add nothing if it doesn't fit
+    pub fn push_str(&mut self, s: &str) -> bool {
+        if s.len() > N - self.len {
+            return false;
+        }
+        self.buf[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        true
+    }
+
+    /// Add `c` to the end, or return `false` if it doesn't fit
+    pub fn push(&mut self, c: char) -> bool {
+        self.push_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Remove the last character
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.as_str().chars().next_back()?;
+        self.len -= c.len_utf8();
+        Some(c)
+    }
+
+    /// Cut the string down to `len` bytes
+    /// 
+    /// Traps if that's in the middle of a character.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            crate::assert(self.as_str().is_char_boundary(len), "ArrayString::truncate not on a char boundary");
+            self.len = len;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for ArrayString<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Deref for ArrayString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Write for ArrayString<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.push_str(s) {
+            return Ok(());
+        }
+        let mut len = N - self.len;
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.push_str(&s[..len]);
+        Err(fmt::Error)
+    }
+}
+
+impl<const N: usize> fmt::Display for ArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for ArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl<const N: usize> PartialEq for ArrayString<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for ArrayString<N> {}
+
+impl<const N: usize> PartialEq<str> for ArrayString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for ArrayString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// The last `N` values pushed, dropping the oldest to make room for new ones
+/// 
+/// Good for histories, like the last few log lines or positions for a trail.
+/// 
+/// ```ignore
+/// let mut trail = RingBuffer::<Vec2, 16>::new();
+/// trail.push(player.pos);
+/// for pos in trail.iter() {
+///     w.frame_buffer.rect(pos.x, pos.y, 1, 1);
+/// }
+/// ```
+pub struct RingBuffer<T, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    // Index of the oldest value
+    start: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            items: [const { MaybeUninit::uninit() }; N],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    // Where the `i`th oldest value goes
+    fn slot(&self, i: usize) -> usize {
+        (self.start + i) % N
+    }
+
+    /// Add a value as the newest, returning the oldest if it had to be
+    /// dropped to make room
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if N == 0 {
+            return Some(value);
+        }
+        if self.is_full() {
+            let slot = self.start;
+            self.start = self.slot(1);
+            return Some(core::mem::replace(unsafe { self.items[slot].assume_init_mut() }, value));
+        }
+        let slot = self.slot(self.len);
+        self.items[slot].write(value);
+        self.len += 1;
+        None
+    }
+
+    /// Remove the oldest value
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let slot = self.start;
+        self.start = self.slot(1);
+        self.len -= 1;
+        Some(unsafe { self.items[slot].assume_init_read() })
+    }
+
+    /// Remove the newest value
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let slot = self.slot(self.len);
+        Some(unsafe { self.items[slot].assume_init_read() })
+    }
+
+    /// The `i`th oldest value
+    pub fn get(&self, i: usize) -> Option<&T> {
+        (i < self.len).then(|| unsafe { self.items[self.slot(i)].assume_init_ref() })
+    }
+
+    /// The `i`th oldest value
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.len {
+            return None;
+        }
+        let slot = self.slot(i);
+        Some(unsafe { self.items[slot].assume_init_mut() })
+    }
+
+    /// The oldest value
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// The newest value
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len.wrapping_sub(1))
+    }
+
+    /// The values from oldest to newest, in the two runs they're stored in
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let first = self.len.min(N - self.start);
+        let ptr = self.items.as_ptr().cast::<T>();
+        // The `len` items from `start`, wrapping around, are initialized
+        unsafe {
+            (
+                core::slice::from_raw_parts(ptr.add(self.start), first),
+                core::slice::from_raw_parts(ptr, self.len - first),
+            )
+        }
+    }
+
+    /// The values from oldest to newest, in the two runs they're stored in
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let first = self.len.min(N - self.start);
+        let ptr = self.items.as_mut_ptr().cast::<T>();
+        // The runs never overlap, since `len <= N`
+        unsafe {
+            (
+                core::slice::from_raw_parts_mut(ptr.add(self.start), first),
+                core::slice::from_raw_parts_mut(ptr, self.len - first),
+            )
+        }
+    }
+
+    /// The values from oldest to newest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        let (a, b) = self.as_slices();
+        a.iter().chain(b)
+    }
+
+    /// The values from oldest to newest
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut T> + '_ {
+        let (a, b) = self.as_mut_slices();
+        a.iter_mut().chain(b)
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_back().is_some() {}
+        self.start = 0;
+    }
+}
+
+impl<T, const N: usize> Drop for RingBuffer<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for RingBuffer<T, N> {
+    fn clone(&self) -> Self {
+        let mut clone = Self::new();
+        for value in self.iter() {
+            clone.push(value.clone());
+        }
+        clone
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for RingBuffer<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
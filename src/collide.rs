@@ -0,0 +1,175 @@
+//! Collision tests between rectangles, circles, points, and line segments
+//! 
+//! Rectangles cover the pixels from their position up to (but not including)
+//! their right and bottom edges, the same as [`FrameBuffer::rect`](crate::FrameBuffer::rect)
+//! fills them.
+
+use crate::fixed::Fixed;
+use crate::geometry::{Rect, Vec2};
+
+/// A circle, covering the pixels no further than `radius` from its center
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Circle {
+    pub center: Vec2,
+    pub radius: u32,
+}
+
+impl Circle {
+    pub const fn new(center: Vec2, radius: u32) -> Self {
+        Self { center, radius }
+    }
+}
+
+/// How two shapes overlap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overlap {
+    /// The direction to move the first shape to separate them, one of the 
+    /// four axis directions
+    pub normal: Vec2,
+    /// How far the first shape must move along `normal` to separate them
+    pub depth: u32,
+}
+
+/// Where a segment first hits a shape
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SegmentHit {
+    /// How far along the segment the hit is, from 0 at the start to 1 at the
+    /// end
+    pub t: Fixed,
+    /// The point that was hit
+    pub point: Vec2,
+    /// The side that was hit, as one of the four axis directions, or zero if
+    /// the segment starts inside the shape
+    pub normal: Vec2,
+    /// How much of the segment is past `point`, in pixels
+    pub depth: Fixed,
+}
+
+pub fn point_in_rect(point: Vec2, rect: &Rect) -> bool {
+    rect.contains(point)
+}
+
+pub fn point_in_circle(point: Vec2, circle: &Circle) -> bool {
+    dist_sq(point, circle.center) <= (circle.radius as i64).pow(2)
+}
+
+/// Do two rectangles overlap?
+pub fn aabb(a: &Rect, b: &Rect) -> bool {
+    a.intersects(b)
+}
+
+/// How two rectangles overlap, if they do
+/// 
+/// The normal is along whichever axis needs the smallest push to separate them
+pub fn aabb_overlap(a: &Rect, b: &Rect) -> Option<Overlap> {
+    if !a.intersects(b) {
+        return None;
+    }
+    // How far `a` must move in each direction to stop overlapping
+    let left = (a.right() - b.x) as u32;
+    let right = (b.right() - a.x) as u32;
+    let up = (a.bottom() - b.y) as u32;
+    let down = (b.bottom() - a.y) as u32;
+    let candidates = [
+        (left, Vec2::new(-1, 0)),
+        (right, Vec2::new(1, 0)),
+        (up, Vec2::new(0, -1)),
+        (down, Vec2::new(0, 1)),
+    ];
+    let (depth, normal) = candidates.into_iter().min_by_key(|(depth, _)| *depth)?;
+    Some(Overlap { normal, depth })
+}
+
+/// Do two circles overlap?
+pub fn circles(a: &Circle, b: &Circle) -> bool {
+    let radii = a.radius as i64 + b.radius as i64;
+    dist_sq(a.center, b.center) <= radii * radii
+}
+
+/// Does a circle overlap a rectangle?
+pub fn circle_rect(circle: &Circle, rect: &Rect) -> bool {
+    !rect.is_empty() && point_in_circle(rect.clamp(circle.center), circle)
+}
+
+/// Where the segment from `start` to `end` first touches a rectangle, if it 
+/// does
+pub fn segment_rect(start: Vec2, end: Vec2, rect: &Rect) -> Option<SegmentHit> {
+    if rect.is_empty() {
+        return None;
+    }
+    let delta = end - start;
+    let mut t_enter = Fixed::MIN;
+    let mut t_exit = Fixed::MAX;
+    let mut normal = Vec2::ZERO;
+
+    let axes = [
+        (start.x, delta.x, rect.x, rect.right() - 1, Vec2::new(1, 0)),
+        (start.y, delta.y, rect.y, rect.bottom() - 1, Vec2::new(0, 1)),
+    ];
+    for (pos, delta, min, max, axis) in axes {
+        if delta == 0 {
+            if pos < min || pos > max {
+                return None;
+            }
+            continue;
+        }
+        let (near, far, axis_normal) = if delta > 0 {
+            (min, max, -axis)
+        } else {
+            (max, min, axis)
+        };
+        let t_near = ratio(near - pos, delta);
+        let t_far = ratio(far - pos, delta);
+        if t_near > t_enter {
+            t_enter = t_near;
+            normal = axis_normal;
+        }
+        t_exit = t_exit.min(t_far);
+    }
+
+    if t_enter > t_exit || t_exit < Fixed::ZERO || t_enter > Fixed::ONE {
+        return None;
+    }
+    // Starting inside
+    if t_enter < Fixed::ZERO {
+        t_enter = Fixed::ZERO;
+        normal = Vec2::ZERO;
+    }
+    let point = Vec2::new(
+        start.x + (Fixed::from_int(delta.x) * t_enter).round(),
+        start.y + (Fixed::from_int(delta.y) * t_enter).round(),
+    );
+    let length = Fixed::from_int(isqrt(dist_sq(start, end) as u64) as i32);
+    Some(SegmentHit {
+        t: t_enter,
+        point,
+        normal,
+        depth: length * (Fixed::ONE - t_enter),
+    })
+}
+
+fn dist_sq(a: Vec2, b: Vec2) -> i64 {
+    let dx = a.x as i64 - b.x as i64;
+    let dy = a.y as i64 - b.y as i64;
+    dx * dx + dy * dy
+}
+
+// `num / den` as a fixed-point number, saturating instead of overflowing
+fn ratio(num: i32, den: i32) -> Fixed {
+    let bits = ((num as i64) << Fixed::FRAC_BITS) / den as i64;
+    Fixed::from_bits(bits.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+}
+
+pub(crate) fn isqrt(x: u64) -> u64 {
+    if x < 2 {
+        return x;
+    }
+    // Newton's method, starting above the root
+    let mut guess = x;
+    let mut next = guess.div_ceil(2);
+    while next < guess {
+        guess = next;
+        next = (guess + x / guess) / 2;
+    }
+    guess
+}
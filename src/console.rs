@@ -0,0 +1,125 @@
+//! A log shown on the screen, for when the native console isn't visible
+
+use crate::{read_draw_colors, write_draw_colors, FrameBuffer, StrBuf};
+
+/// How many characters fit on one line of the screen
+pub const LINE_WIDTH: usize = 20;
+
+#[derive(Clone, Copy)]
+struct Line {
+    buf: [u8; LINE_WIDTH * 4],
+    len: u8,
+}
+
+impl Line {
+    const EMPTY: Self = Self {
+        buf: [0; LINE_WIDTH * 4],
+        len: 0,
+    };
+
+    fn as_str(&self) -> &str {
+        // Only whole chars are ever copied in
+        unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len as usize]) }
+    }
+}
+
+/// Keeps the last `N` lines logged, and draws them at the bottom of the screen
+/// 
+/// Messages are also sent to [`trace`](crate::trace). Lines longer than 
+/// [`LINE_WIDTH`] characters are wrapped.
+pub struct Console<const N: usize> {
+    lines: [Line; N],
+    // Index of the oldest line
+    start: usize,
+    len: usize,
+}
+
+impl<const N: usize> Console<N> {
+    pub const fn new() -> Self {
+        Self {
+            lines: [Line::EMPTY; N],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Log a message, splitting it at newlines
+    pub fn log(&mut self, s: &str) {
+        crate::trace(s);
+        for line in s.split('\n') {
+            self.push_wrapped(line);
+        }
+    }
+
+    /// Log a formatted message, cut off if it's longer than 256 bytes
+    pub fn log_fmt(&mut self, args: core::fmt::Arguments<'_>) {
+        let mut buf = StrBuf::<256>::new();
+        self.log(buf.format(args));
+    }
+
+    fn push_wrapped(&mut self, mut s: &str) {
+        loop {
+            let split = s.char_indices().nth(LINE_WIDTH).map_or(s.len(), |(i, _)| i);
+            let (line, rest) = s.split_at(split);
+            self.push_line(line);
+            if rest.is_empty() {
+                break;
+            }
+            s = rest;
+        }
+    }
+
+    fn push_line(&mut self, s: &str) {
+        if N == 0 {
+            return;
+        }
+        let idx = if self.len < N {
+            self.len += 1;
+            (self.start + self.len - 1) % N
+        } else {
+            let idx = self.start;
+            self.start = (self.start + 1) % N;
+            idx
+        };
+        let line = &mut self.lines[idx];
+        line.buf[..s.len()].copy_from_slice(s.as_bytes());
+        line.len = s.len() as u8;
+    }
+
+    /// The lines currently kept, oldest first
+    pub fn lines(&self) -> impl Iterator<Item = &str> + '_ {
+        (0..self.len).map(move |i| self.lines[(self.start + i) % N].as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.len = 0;
+    }
+
+    /// Draw the kept lines at the bottom of the screen, newest last
+    /// 
+    /// The text is drawn in palette color 1 over palette color 4.
+    pub fn draw(&self, fb: &mut FrameBuffer) {
+        let prev_colors = read_draw_colors();
+        write_draw_colors(0x0041);
+        let top = 160 - self.len as i32 * 8;
+        for (i, line) in self.lines().enumerate() {
+            fb.text(line, 0, top + i as i32 * 8);
+        }
+        write_draw_colors(prev_colors);
+    }
+}
+
+impl<const N: usize> Default for Console<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,192 @@
+//! An on-screen display of debugging info
+//! 
+//! Without the `debug_overlay` feature, [`DebugOverlay`] does nothing and
+//! takes no space, so it can be left in release carts.
+
+use crate::{Buttons, FrameBuffer, Gamepad};
+
+/// The most values [`DebugOverlay::watch`] can show at once
+pub const MAX_WATCHES: usize = 8;
+
+/// Shows the frame counter, an estimate of free memory, the number of active
+/// entities, and any watched values in the top left corner of the screen
+/// 
+/// Toggled by holding all the buttons in a chord at once. Call
+/// [`DebugOverlay::update`] every frame, and [`DebugOverlay::draw`] after 
+/// drawing everything else.
+pub struct DebugOverlay {
+    #[cfg(feature = "debug_overlay")]
+    inner: enabled::Inner,
+}
+
+impl DebugOverlay {
+    pub const fn new(chord: Buttons) -> Self {
+        #[cfg(not(feature = "debug_overlay"))]
+        let _ = chord;
+        Self {
+            #[cfg(feature = "debug_overlay")]
+            inner: enabled::Inner::new(chord),
+        }
+    }
+
+    /// Check whether the chord was just pressed
+    pub fn update(&mut self, gamepad: &Gamepad) {
+        #[cfg(feature = "debug_overlay")]
+        self.inner.update(gamepad);
+        #[cfg(not(feature = "debug_overlay"))]
+        let _ = gamepad;
+    }
+
+    /// Is the overlay being shown?
+    pub fn is_visible(&self) -> bool {
+        #[cfg(feature = "debug_overlay")]
+        return self.inner.visible;
+        #[cfg(not(feature = "debug_overlay"))]
+        false
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        #[cfg(feature = "debug_overlay")]
+        {
+            self.inner.visible = visible;
+        }
+        #[cfg(not(feature = "debug_overlay"))]
+        let _ = visible;
+    }
+
+    /// Set the number of active entities to show
+    pub fn set_entities(&mut self, count: u32) {
+        #[cfg(feature = "debug_overlay")]
+        {
+            self.inner.entities = Some(count);
+        }
+        #[cfg(not(feature = "debug_overlay"))]
+        let _ = count;
+    }
+
+    /// Show a value, replacing any other value watched with the same name
+    /// 
+    /// Once [`MAX_WATCHES`] names are watched, new ones are ignored
+    pub fn watch(&mut self, name: &'static str, value: i32) {
+        #[cfg(feature = "debug_overlay")]
+        self.inner.watch(name, value);
+        #[cfg(not(feature = "debug_overlay"))]
+        let _ = (name, value);
+    }
+
+    /// Stop showing a watched value
+    pub fn unwatch(&mut self, name: &str) {
+        #[cfg(feature = "debug_overlay")]
+        self.inner.unwatch(name);
+        #[cfg(not(feature = "debug_overlay"))]
+        let _ = name;
+    }
+
+    /// Draw the overlay, if it's visible
+    pub fn draw(&self, fb: &mut FrameBuffer) {
+        #[cfg(feature = "debug_overlay")]
+        self.inner.draw(fb);
+        #[cfg(not(feature = "debug_overlay"))]
+        let _ = fb;
+    }
+}
+
+#[cfg(feature = "debug_overlay")]
+mod enabled {
+    use core::fmt::Write;
+
+    use super::MAX_WATCHES;
+    use crate::{read_draw_colors, write_draw_colors, Buttons, FrameBuffer, Gamepad, StrBuf};
+
+    pub(super) struct Inner {
+        chord: Buttons,
+        chord_held: bool,
+        pub(super) visible: bool,
+        pub(super) entities: Option<u32>,
+        watches: [Option<(&'static str, i32)>; MAX_WATCHES],
+    }
+
+    extern "C" {
+        // Defined by the linker, everything after it is unused by static data
+        // and the stack
+        static __heap_base: u8;
+    }
+
+    /// An estimate of the bytes of memory not used by static data or the 
+    /// stack
+    fn free_memory() -> usize {
+        let heap_base = core::ptr::addr_of!(__heap_base) as usize;
+        0x10000usize.saturating_sub(heap_base)
+    }
+
+    impl Inner {
+        pub(super) const fn new(chord: Buttons) -> Self {
+            Self {
+                chord,
+                chord_held: false,
+                visible: false,
+                entities: None,
+                watches: [None; MAX_WATCHES],
+            }
+        }
+
+        pub(super) fn update(&mut self, gamepad: &Gamepad) {
+            let held = !self.chord.is_empty()
+                && Buttons::from(gamepad).bits() & self.chord.bits() == self.chord.bits();
+            if held && !self.chord_held {
+                self.visible = !self.visible;
+            }
+            self.chord_held = held;
+        }
+
+        pub(super) fn watch(&mut self, name: &'static str, value: i32) {
+            let slot = match self.watches.iter().position(|w| matches!(w, Some((n, _)) if *n == name)) {
+                Some(i) => &mut self.watches[i],
+                None => match self.watches.iter_mut().find(|w| w.is_none()) {
+                    Some(slot) => slot,
+                    None => return,
+                },
+            };
+            *slot = Some((name, value));
+        }
+
+        pub(super) fn unwatch(&mut self, name: &str) {
+            for watch in &mut self.watches {
+                if matches!(watch, Some((n, _)) if *n == name) {
+                    *watch = None;
+                }
+            }
+        }
+
+        pub(super) fn draw(&self, fb: &mut FrameBuffer) {
+            if !self.visible {
+                return;
+            }
+            let prev_colors = read_draw_colors();
+            let mut lines: [StrBuf<20>; 3 + MAX_WATCHES] = core::array::from_fn(|_| StrBuf::new());
+            let mut count = 0;
+            let mut line = |args: core::fmt::Arguments| {
+                let _ = lines[count].write_fmt(args);
+                count += 1;
+            };
+            line(format_args!("F:{}", crate::frame()));
+            line(format_args!("MEM:{}", free_memory()));
+            if let Some(entities) = self.entities {
+                line(format_args!("ENT:{}", entities));
+            }
+            for (name, value) in self.watches.iter().flatten() {
+                line(format_args!("{}:{}", name, value));
+            }
+
+            let width = lines[..count].iter().map(|l| l.len).max().unwrap_or(0) as u32 * 8;
+            // Palette color 4 for the background, with palette color 1 text on top
+            write_draw_colors(0x0004);
+            fb.rect(0, 0, width + 2, count as u32 * 8 + 2);
+            write_draw_colors(0x0001);
+            for (i, line) in lines[..count].iter().enumerate() {
+                fb.text(line.as_str(), 1, 1 + i as i32 * 8);
+            }
+            write_draw_colors(prev_colors);
+        }
+    }
+}
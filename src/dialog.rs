@@ -0,0 +1,214 @@
+//! Text boxes that type out their text, for conversations and signs
+
+use crate::geometry::Rect;
+use crate::{read_draw_colors, write_draw_colors, Button, Buttons, FrameBuffer, Sound, SoundSystem};
+
+// The screen is only 20 lines tall
+const MAX_LINES: usize = 20;
+
+/// What happened to a [`DialogBox`] this frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DialogEvent {
+    None,
+    /// The current page finished typing out
+    PageComplete,
+    /// Moved on to the next page
+    NextPage,
+    /// Moved on from the last page, so the dialog is done
+    Finished,
+}
+
+/// A box which types out text a character at a time, split into pages that 
+/// fit inside it
+/// 
+/// Pressing X shows the whole page at once, or moves on to the next page if 
+/// it's already all shown. Text is wrapped at spaces, and `\n` starts a new
+/// line.
+pub struct DialogBox<'a> {
+    text: &'a str,
+    rect: Rect,
+    frames_per_char: u8,
+    blip: Option<Sound>,
+    // Byte index of the start of the current page
+    page_start: usize,
+    // How many characters of the current page are shown
+    revealed: usize,
+    timer: u8,
+    finished: bool,
+}
+
+// The lines of one page, as byte ranges, along with where the next page starts
+struct Page {
+    lines: [(usize, usize); MAX_LINES],
+    len: usize,
+    end: usize,
+}
+
+impl<'a> DialogBox<'a> {
+    /// A dialog box covering `rect`, typing a character every 2 frames
+    pub const fn new(text: &'a str, rect: Rect) -> Self {
+        Self {
+            text,
+            rect,
+            frames_per_char: 2,
+            blip: None,
+            page_start: 0,
+            revealed: 0,
+            timer: 0,
+            finished: false,
+        }
+    }
+
+    /// How many frames to wait between each character, with 0 showing whole
+    /// pages at once
+    pub const fn speed(self, frames_per_char: u8) -> Self {
+        Self { frames_per_char, ..self }
+    }
+
+    /// A sound to play as each character is typed
+    pub const fn blip(self, sound: Sound) -> Self {
+        Self { blip: Some(sound), ..self }
+    }
+
+    /// Start over from the beginning with new text
+    pub fn set_text(&mut self, text: &'a str) {
+        self.text = text;
+        self.page_start = 0;
+        self.revealed = 0;
+        self.timer = 0;
+        self.finished = false;
+    }
+
+    /// Type out more of the page, and react to the buttons pressed this frame
+    pub fn update(&mut self, pressed: Buttons, sounds: &SoundSystem) -> DialogEvent {
+        if self.finished {
+            return DialogEvent::None;
+        }
+        let page = self.page();
+        let total = page_chars(self.text, &page);
+        if self.revealed >= total {
+            if !pressed.contains(Button::X) {
+                return DialogEvent::None;
+            }
+            if page.end >= self.text.len() {
+                self.finished = true;
+                return DialogEvent::Finished;
+            }
+            self.page_start = page.end;
+            self.revealed = 0;
+            self.timer = 0;
+            return DialogEvent::NextPage;
+        }
+        if pressed.contains(Button::X) || self.frames_per_char == 0 {
+            self.revealed = total;
+            return DialogEvent::PageComplete;
+        }
+        self.timer += 1;
+        if self.timer >= self.frames_per_char {
+            self.timer = 0;
+            self.revealed += 1;
+            if let Some(blip) = self.blip {
+                sounds.play(blip);
+            }
+        }
+        if self.revealed >= total {
+            DialogEvent::PageComplete
+        } else {
+            DialogEvent::None
+        }
+    }
+
+    /// Has the current page been fully typed out?
+    pub fn is_page_complete(&self) -> bool {
+        self.revealed >= page_chars(self.text, &self.page())
+    }
+
+    /// Has the last page been moved on from?
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Is there another page after this one?
+    pub fn has_next_page(&self) -> bool {
+        self.page().end < self.text.len()
+    }
+
+    /// Draw the box and the shown text, unless it's finished
+    /// 
+    /// The box is drawn like [`FrameBuffer::rect`], with draw color 1 for the 
+    /// fill and draw color 2 for the outline, and the text is drawn in draw 
+    /// color 2. A down arrow shows when there's another page to move on to.
+    pub fn draw(&self, fb: &mut FrameBuffer) {
+        if self.finished {
+            return;
+        }
+        let Rect { x, y, width, height } = self.rect;
+        fb.rect_r(self.rect);
+        let colors = read_draw_colors();
+        write_draw_colors((colors >> 4) & 0xF);
+        let page = self.page();
+        let mut remaining = self.revealed;
+        for (i, &(start, end)) in page.lines[..page.len].iter().enumerate() {
+            let line = &self.text[start..end];
+            let shown = line.char_indices().nth(remaining).map_or(line.len(), |(i, _)| i);
+            fb.text(&line[..shown], x + 2, y + 2 + i as i32 * 8);
+            remaining = remaining.saturating_sub(line.chars().count());
+        }
+        if self.is_page_complete() && self.has_next_page() {
+            fb.text(crate::glyphs::DOWN, x + width as i32 - 9, y + height as i32 - 9);
+        }
+        write_draw_colors(colors);
+    }
+
+    fn page(&self) -> Page {
+        let columns = ((self.rect.width.saturating_sub(4)) / 8).max(1) as usize;
+        let rows = ((self.rect.height.saturating_sub(4)) / 8).clamp(1, MAX_LINES as u32) as usize;
+        let mut page = Page {
+            lines: [(0, 0); MAX_LINES],
+            len: 0,
+            end: self.page_start,
+        };
+        let text = self.text;
+        let mut pos = self.page_start;
+        while page.len < rows && pos < text.len() {
+            let (line_end, next) = wrap_line(text, pos, columns);
+            page.lines[page.len] = (pos, line_end);
+            page.len += 1;
+            pos = next;
+        }
+        page.end = pos;
+        page
+    }
+}
+
+// Find where the line starting at `start` ends, and where the next one starts
+fn wrap_line(text: &str, start: usize, columns: usize) -> (usize, usize) {
+    let rest = &text[start..];
+    let mut last_space = None;
+    for (count, (i, c)) in rest.char_indices().enumerate() {
+        if c == '\n' {
+            return (start + i, start + i + 1);
+        }
+        if count == columns {
+            return match last_space {
+                // The line ends exactly at a space
+                _ if c == ' ' => (start + i, start + i + 1),
+                // Break at the last space, skipping over it
+                Some(space) => (start + space, start + space + 1),
+                // One long word, so split it
+                None => (start + i, start + i),
+            };
+        }
+        if c == ' ' {
+            last_space = Some(i);
+        }
+    }
+    (text.len(), text.len())
+}
+
+fn page_chars(text: &str, page: &Page) -> usize {
+    page.lines[..page.len]
+        .iter()
+        .map(|&(start, end)| text[start..end].chars().count())
+        .sum()
+}
@@ -0,0 +1,111 @@
+//! Numbers drawn with large digit sprites, for scores and other HUD counters
+
+use crate::{Align, FrameBuffer, SpriteSheet};
+
+// Enough for every digit of a u32 and its separators, plus padding
+const MAX_GLYPHS: usize = 32;
+
+/// Draws numbers with the digit tiles of a [`SpriteSheet`]
+/// 
+/// Tiles `0` to `9` of the sheet are the digits `0` to `9`, unless moved with
+/// [`Digits::first_digit`].
+/// 
+/// ```ignore
+/// static SCORE: Digits = Digits::new(SpriteSheet::new(DIGITS_SPRITE, 8, 12))
+///     .zero_pad(6)
+///     .separator(10);
+/// 
+/// SCORE.draw(&mut w.frame_buffer, state.score, 156, 4, Align::Right);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Digits<'a> {
+    sheet: SpriteSheet<'a>,
+    first_digit: u32,
+    separator: Option<u32>,
+    min_digits: u8,
+    spacing: i32,
+}
+
+impl<'a> Digits<'a> {
+    /// Digits with no padding, no separators, and no space between them
+    pub const fn new(sheet: SpriteSheet<'a>) -> Self {
+        Self {
+            sheet,
+            first_digit: 0,
+            separator: None,
+            min_digits: 1,
+            spacing: 0,
+        }
+    }
+
+    /// The tile of the digit `0`, with the rest following it in order
+    pub const fn first_digit(self, tile: u32) -> Self {
+        Self { first_digit: tile, ..self }
+    }
+
+    /// Pad numbers with leading zeros up to `digits` long
+    pub const fn zero_pad(self, digits: u8) -> Self {
+        Self { min_digits: digits, ..self }
+    }
+
+    /// Put this tile between every group of three digits
+    pub const fn separator(self, tile: u32) -> Self {
+        Self { separator: Some(tile), ..self }
+    }
+
+    /// Pixels between each tile, which may be negative for digits that
+    /// overlap
+    pub const fn spacing(self, spacing: i32) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// The width in pixels that `value` takes up when drawn
+    pub fn width(&self, value: u32) -> u32 {
+        let (_, len) = self.glyphs(value);
+        if len == 0 {
+            return 0;
+        }
+        let width = len as i32 * self.sheet.tile_width as i32 + (len as i32 - 1) * self.spacing;
+        width.max(0) as u32
+    }
+
+    /// Draw `value` with `anchor_x` at its left edge, center, or right edge,
+    /// and `y` at its top
+    pub fn draw(&self, fb: &mut FrameBuffer, value: u32, anchor_x: i32, y: i32, align: Align) {
+        let width = self.width(value) as i32;
+        let mut x = match align {
+            Align::Left => anchor_x,
+            Align::Center => anchor_x - width / 2,
+            Align::Right => anchor_x - width,
+        };
+        let (glyphs, len) = self.glyphs(value);
+        let step = self.sheet.tile_width as i32 + self.spacing;
+        for &tile in glyphs[..len].iter().rev() {
+            self.sheet.draw(fb, tile, x, y);
+            x += step;
+        }
+    }
+
+    // The tiles to draw, from right to left
+    fn glyphs(&self, mut value: u32) -> ([u32; MAX_GLYPHS], usize) {
+        let mut glyphs = [0; MAX_GLYPHS];
+        let mut len = 0;
+        let mut digits = 0;
+        while len < MAX_GLYPHS && (value != 0 || digits < self.min_digits.max(1)) {
+            if digits > 0 && digits.is_multiple_of(3) {
+                if let Some(separator) = self.separator {
+                    glyphs[len] = separator;
+                    len += 1;
+                    if len == MAX_GLYPHS {
+                        break;
+                    }
+                }
+            }
+            glyphs[len] = self.first_digit + value % 10;
+            len += 1;
+            digits += 1;
+            value /= 10;
+        }
+        (glyphs, len)
+    }
+}
@@ -0,0 +1,109 @@
+//! Tracking which parts of the screen were drawn to
+//! 
+//! With [`SystemFlags::set_preserve_framebuffer`](crate::SystemFlags::set_preserve_framebuffer)
+//! on, a game only needs to redraw what changed. Draws done inside
+//! [`FrameBuffer::track_dirty`](crate::FrameBuffer::track_dirty) mark the area
+//! they cover, which can then be used to only redraw those parts next time.
+
+use crate::geometry::Rect;
+
+/// The most separate rectangles a [`DirtyTracker`] holds before merging them
+pub const MAX_RECTS: usize = 16;
+
+/// The areas of the screen that were drawn to
+/// 
+/// Rectangles are clipped to the screen. Once there are [`MAX_RECTS`] of them,
+/// each new one is merged into whichever rectangle grows the least from it,
+/// so the areas may cover more than was actually drawn.
+/// 
+/// ```ignore
+/// w.frame_buffer.track_dirty(&mut state.dirty, |fb| {
+///     fb.rect(x, y, 16, 16);
+/// });
+/// for rect in state.dirty.rects() {
+///     ...
+/// }
+/// state.dirty.clear();
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct DirtyTracker {
+    rects: [Rect; MAX_RECTS],
+    len: usize,
+}
+
+impl DirtyTracker {
+    pub const fn new() -> Self {
+        Self {
+            rects: [Rect::new(0, 0, 0, 0); MAX_RECTS],
+            len: 0,
+        }
+    }
+
+    /// Mark an area as drawn to
+    pub fn add(&mut self, rect: Rect) {
+        let Some(rect) = rect.intersection(&Rect::SCREEN) else {
+            return;
+        };
+        if self.rects().iter().any(|r| r.contains_rect(&rect)) {
+            return;
+        }
+        if self.len < MAX_RECTS {
+            self.rects[self.len] = rect;
+            self.len += 1;
+            return;
+        }
+        let growth = |r: &Rect| {
+            let union = r.union(&rect);
+            union.width * union.height - r.width * r.height
+        };
+        if let Some(closest) = self.rects.iter_mut().min_by_key(|r| growth(r)) {
+            *closest = closest.union(&rect);
+        }
+    }
+
+    /// Every area that was drawn to since the last clear
+    pub fn rects(&self) -> &[Rect] {
+        &self.rects[..self.len]
+    }
+
+    /// The smallest rectangle containing everything that was drawn to
+    pub fn bounds(&self) -> Option<Rect> {
+        self.rects().iter().copied().reduce(|a, b| a.union(&b))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl Default for DirtyTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[allow(deprecated)]
+static ACTIVE: crate::SyncUnsafeCell<Option<&'static mut DirtyTracker>> = crate::SyncUnsafeCell::new(None);
+
+// Returns the tracker that was active before. The caller must unset it again
+// before its borrow ends
+pub(crate) unsafe fn set_active(tracker: Option<&mut DirtyTracker>) -> Option<&'static mut DirtyTracker> {
+    let tracker = tracker.map(|t| &mut *(t as *mut DirtyTracker));
+    ACTIVE.get().replace(tracker)
+}
+
+pub(crate) fn is_active() -> bool {
+    unsafe { (*ACTIVE.get()).is_some() }
+}
+
+// Mark an area on the active tracker, if there is one
+pub(crate) fn mark(x: i32, y: i32, width: u32, height: u32) {
+    // The tracker is only set while `FrameBuffer::track_dirty` has it borrowed
+    if let Some(tracker) = unsafe { &mut *ACTIVE.get() } {
+        tracker.add(Rect::new(x, y, width, height));
+    }
+}
@@ -0,0 +1,477 @@
+//! Structured storage on top of [`Disk`]
+
+use crate::{assert, Disk};
+
+/// A value with a fixed-size binary layout, so it can be stored on disk
+/// 
+/// Can be derived for structs and enums whose fields are all `Save`.
+pub trait Save: Sized {
+    /// The number of bytes this takes up
+    const SIZE: usize;
+
+    /// Write this to the first [`Save::SIZE`] bytes of `out`
+    fn save(&self, out: &mut [u8]);
+
+    /// Read this back from the first [`Save::SIZE`] bytes of `bytes`
+    fn load(bytes: &[u8]) -> Self;
+
+    /// Like [`Save::load`], but `None` if the bytes aren't a valid value, like
+    /// an enum variant that doesn't exist
+    fn try_load(bytes: &[u8]) -> Option<Self> {
+        Some(Self::load(bytes))
+    }
+}
+
+macro_rules! impl_save_int {
+    ($($t:ty),*) => {
+        $(
+            impl Save for $t {
+                const SIZE: usize = core::mem::size_of::<$t>();
+
+                fn save(&self, out: &mut [u8]) {
+                    out[..Self::SIZE].copy_from_slice(&self.to_le_bytes());
+                }
+
+                fn load(bytes: &[u8]) -> Self {
+                    let mut buf = [0; core::mem::size_of::<$t>()];
+                    buf.copy_from_slice(&bytes[..Self::SIZE]);
+                    <$t>::from_le_bytes(buf)
+                }
+            }
+        )*
+    };
+}
+
+impl_save_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl Save for bool {
+    const SIZE: usize = 1;
+
+    fn save(&self, out: &mut [u8]) {
+        out[0] = *self as u8;
+    }
+
+    fn load(bytes: &[u8]) -> Self {
+        bytes[0] != 0
+    }
+}
+
+impl<T: Save, const N: usize> Save for [T; N] {
+    const SIZE: usize = T::SIZE * N;
+
+    fn save(&self, out: &mut [u8]) {
+        for (x, out) in self.iter().zip(out.chunks_exact_mut(T::SIZE)) {
+            x.save(out);
+        }
+    }
+
+    fn load(bytes: &[u8]) -> Self {
+        core::array::from_fn(|i| T::load(&bytes[i * T::SIZE..]))
+    }
+
+    fn try_load(bytes: &[u8]) -> Option<Self> {
+        let values: [Option<T>; N] = core::array::from_fn(|i| T::try_load(&bytes[i * T::SIZE..]));
+        values.iter().all(Option::is_some).then(|| {
+            values.map(|value| match value {
+                Some(value) => value,
+                None => crate::panic("invalid saved data"),
+            })
+        })
+    }
+}
+
+impl<T: Save> Save for Option<T> {
+    const SIZE: usize = 1 + T::SIZE;
+
+    fn save(&self, out: &mut [u8]) {
+        match self {
+            Some(x) => {
+                out[0] = 1;
+                x.save(&mut out[1..]);
+            }
+            None => out[..Self::SIZE].fill(0),
+        }
+    }
+
+    fn load(bytes: &[u8]) -> Self {
+        (bytes[0] != 0).then(|| T::load(&bytes[1..]))
+    }
+
+    fn try_load(bytes: &[u8]) -> Option<Self> {
+        match bytes[0] {
+            0 => Some(None),
+            _ => T::try_load(&bytes[1..]).map(Some),
+        }
+    }
+}
+
+impl Save for crate::fixed::Fixed {
+    const SIZE: usize = 4;
+
+    fn save(&self, out: &mut [u8]) {
+        self.to_bits().save(out);
+    }
+
+    fn load(bytes: &[u8]) -> Self {
+        Self::from_bits(i32::load(bytes))
+    }
+}
+
+impl Save for crate::Buttons {
+    const SIZE: usize = 1;
+
+    fn save(&self, out: &mut [u8]) {
+        out[0] = self.bits();
+    }
+
+    fn load(bytes: &[u8]) -> Self {
+        Self::from_bits(bytes[0])
+    }
+}
+
+/// A save format that can be upgraded from older versions of itself
+/// 
+/// Data is saved with the current version number, and when loading data from
+/// an older version, each migration between it and the current one is run in
+/// order.
+/// 
+/// ```ignore
+/// impl Migrate for Progress {
+///     const MIGRATIONS: &'static [fn(&mut [u8])] = &[
+///         // Version 1 added a `u16` high score before the `u8` level
+///         |data| {
+///             data.copy_within(0..1, 2);
+///             data[..2].fill(0);
+///         },
+///     ];
+/// }
+/// ```
+pub trait Migrate: Save {
+    /// Upgrades from each older version, where `MIGRATIONS[n]` converts the
+    /// layout of version `n` into that of version `n + 1`, in place
+    /// 
+    /// The slice given to each is large enough for any layout, with anything
+    /// past the old data zeroed.
+    const MIGRATIONS: &'static [fn(&mut [u8])];
+
+    /// The current version of the format, which is the number of migrations
+    const VERSION: u8 = Self::MIGRATIONS.len() as u8;
+}
+
+/// Why stored data couldn't be loaded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadError {
+    /// Nothing has been saved
+    Empty,
+    /// The data was saved by a newer version of the game
+    TooNew(u8),
+    /// The data doesn't match its checksum, so it's damaged or was written by
+    /// something else
+    Corrupted,
+}
+
+/// The CRC-32 (as used by zip and png) of some bytes
+pub fn crc32(bytes: &[u8]) -> u32 {
+    crc32_update(!0, bytes) ^ !0
+}
+
+// Bitwise rather than table-driven, to save cart space
+fn crc32_update(mut crc: u32, bytes: &[u8]) -> u32 {
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB88320 & (crc & 1).wrapping_neg());
+        }
+    }
+    crc
+}
+
+// Versioned data is stored as a version byte, then a CRC-32 of the version and
+// payload, then the payload. The payload's length is however much was written,
+// since older versions may have had different sizes.
+const VERSIONED_HEADER: usize = 5;
+
+fn versioned_crc(version: u8, payload: &[u8]) -> u32 {
+    crc32_update(crc32_update(!0, &[version]), payload) ^ !0
+}
+
+pub(crate) fn save_versioned<T: Migrate>(disk: &Disk, value: &T) {
+    assert(VERSIONED_HEADER + T::SIZE <= Disk::CAPACITY, "data too large for disk");
+    let mut image = [0; Disk::CAPACITY];
+    let (header, payload) = image.split_at_mut(VERSIONED_HEADER);
+    let payload = &mut payload[..T::SIZE];
+    value.save(payload);
+    header[0] = T::VERSION;
+    header[1..].copy_from_slice(&versioned_crc(T::VERSION, payload).to_le_bytes());
+    disk.write_all(&image[..VERSIONED_HEADER + T::SIZE]);
+}
+
+pub(crate) fn load_versioned<T: Migrate>(disk: &Disk) -> Result<T, LoadError> {
+    assert(VERSIONED_HEADER + T::SIZE <= Disk::CAPACITY, "data too large for disk");
+    let (mut image, len) = read_image(disk);
+    if len == 0 {
+        return Err(LoadError::Empty);
+    }
+    if len < VERSIONED_HEADER {
+        return Err(LoadError::Corrupted);
+    }
+    let (header, payload) = image.split_at_mut(VERSIONED_HEADER);
+    let version = header[0];
+    let crc = u32::from_le_bytes([header[1], header[2], header[3], header[4]]);
+    if crc != versioned_crc(version, &payload[..len - VERSIONED_HEADER]) {
+        return Err(LoadError::Corrupted);
+    }
+    if version > T::VERSION {
+        return Err(LoadError::TooNew(version));
+    }
+    for migration in &T::MIGRATIONS[version as usize..] {
+        migration(payload);
+    }
+    T::try_load(payload).ok_or(LoadError::Corrupted)
+}
+
+/// Read the whole disk, with anything past the stored data zeroed
+pub(crate) fn read_image(disk: &Disk) -> ([u8; Disk::CAPACITY], usize) {
+    let mut image = [0; Disk::CAPACITY];
+    let len = disk.read(&mut image);
+    (image, len)
+}
+
+// Marks a slot as holding data. Anything but 0 would do, but a full byte makes
+// it less likely that something else on the disk looks like a save.
+const OCCUPIED: u8 = 0xA5;
+const SLOT_HEADER: usize = 2;
+
+/// Splits (part of) the disk into `N` equally sized save slots
+/// 
+/// Each slot has a small header, recording whether it holds data and a
+/// version number for it.
+/// 
+/// ```ignore
+/// const SLOTS: SaveSlots<3> = SaveSlots::new();
+/// 
+/// SLOTS.save_slot(&w.disk, 0, 1, &progress);
+/// if let Some(version) = SLOTS.load_slot(&w.disk, 0, &mut progress) { .. }
+/// ```
+pub struct SaveSlots<const N: usize> {
+    start: usize,
+    len: usize,
+}
+
+impl<const N: usize> SaveSlots<N> {
+    /// Use the whole disk for slots
+    pub const fn new() -> Self {
+        Self::with_region(0, Disk::CAPACITY)
+    }
+
+    /// Use only `len` bytes of the disk starting at `start` for slots, so
+    /// other data can be stored in the rest
+    pub const fn with_region(start: usize, len: usize) -> Self {
+        core::assert!(N > 0, "there must be at least one slot");
+        core::assert!(start + len <= Disk::CAPACITY, "region goes past the end of the disk");
+        core::assert!(len / N > SLOT_HEADER, "slots are too small");
+        Self { start, len }
+    }
+
+    /// The number of bytes each slot can hold
+    pub const fn payload_size(&self) -> usize {
+        self.len / N - SLOT_HEADER
+    }
+
+    /// Store `data` in a slot with the given version, replacing what was there
+    pub fn save_slot(&self, disk: &Disk, slot: usize, version: u8, data: &[u8]) {
+        assert(data.len() <= self.payload_size(), "data too large for save slot");
+        let (mut image, len) = read_image(disk);
+        let range = self.slot_range(slot);
+        let end = range.end;
+        let slot = &mut image[range];
+        slot.fill(0);
+        slot[0] = OCCUPIED;
+        slot[1] = version;
+        slot[SLOT_HEADER..SLOT_HEADER + data.len()].copy_from_slice(data);
+        disk.write_all(&image[..len.max(end)]);
+    }
+
+    /// Load a slot into `buf`, returning its version, or `None` if it's empty
+    /// 
+    /// `buf` is filled with up to [`SaveSlots::payload_size`] bytes
+    pub fn load_slot(&self, disk: &Disk, slot: usize, buf: &mut [u8]) -> Option<u8> {
+        let (image, _) = read_image(disk);
+        let slot = &image[self.slot_range(slot)];
+        if slot[0] != OCCUPIED {
+            return None;
+        }
+        let payload = &slot[SLOT_HEADER..];
+        let len = buf.len().min(payload.len());
+        buf[..len].copy_from_slice(&payload[..len]);
+        Some(slot[1])
+    }
+
+    /// Empty a slot
+    pub fn erase_slot(&self, disk: &Disk, slot: usize) {
+        let (mut image, len) = read_image(disk);
+        let range = self.slot_range(slot);
+        // Nothing to erase if the disk doesn't reach the slot
+        if range.start < len {
+            image[range].fill(0);
+            disk.write_all(&image[..len]);
+        }
+    }
+
+    /// The version of the data in a slot, or `None` if it's empty
+    pub fn slot_version(&self, disk: &Disk, slot: usize) -> Option<u8> {
+        let (image, _) = read_image(disk);
+        let slot = &image[self.slot_range(slot)];
+        (slot[0] == OCCUPIED).then_some(slot[1])
+    }
+
+    /// Does a slot hold data?
+    pub fn is_occupied(&self, disk: &Disk, slot: usize) -> bool {
+        self.slot_version(disk, slot).is_some()
+    }
+
+    fn slot_range(&self, slot: usize) -> core::ops::Range<usize> {
+        assert(slot < N, "save slot out of range");
+        let size = self.len / N;
+        let start = self.start + slot * size;
+        start..start + size
+    }
+}
+
+impl<const N: usize> Default for SaveSlots<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compress `input` into `out` with run-length encoding, returning the
+/// compressed length, or `None` if it didn't fit
+/// 
+/// Uses the PackBits format: a header byte `n` below 128 is followed by `n + 1`
+/// literal bytes, and a header byte above 128 is followed by one byte to be
+/// repeated `257 - n` times. Incompressible data grows by about one byte in
+/// 128.
+pub fn rle_encode(input: &[u8], out: &mut [u8]) -> Option<usize> {
+    let mut len = 0;
+    let mut push = |bytes: &[u8]| {
+        let dest = out.get_mut(len..len + bytes.len())?;
+        dest.copy_from_slice(bytes);
+        len += bytes.len();
+        Some(())
+    };
+    let mut i = 0;
+    while i < input.len() {
+        let run = input[i..].iter().take(128).take_while(|&&b| b == input[i]).count();
+        if run >= 2 {
+            push(&[(257 - run) as u8, input[i]])?;
+            i += run;
+            continue;
+        }
+        // Gather literals until the next run worth encoding. Runs of 2 would
+        // take as much space as literals, plus a header for the next literals
+        let start = i;
+        while i < input.len() && i - start < 128 {
+            if input[i..].starts_with(&[input[i]; 3]) {
+                break;
+            }
+            i += 1;
+        }
+        push(&[(i - start - 1) as u8])?;
+        push(&input[start..i])?;
+    }
+    Some(len)
+}
+
+/// Decompress data from [`rle_encode`] into `out`, returning the decompressed
+/// length, or `None` if it didn't fit or the data is malformed
+pub fn rle_decode(input: &[u8], out: &mut [u8]) -> Option<usize> {
+    let mut unpacker = Unpacker::new(input);
+    let len = unpacker.fill(out);
+    unpacker.finished().then_some(len)
+}
+
+// Decompresses PackBits data a piece at a time
+pub(crate) struct Unpacker<'a> {
+    input: &'a [u8],
+    i: usize,
+    // Bytes left in the current run or literal
+    left: usize,
+    repeat: bool,
+}
+
+impl<'a> Unpacker<'a> {
+    pub(crate) fn new(input: &'a [u8]) -> Self {
+        Self { input, i: 0, left: 0, repeat: false }
+    }
+
+    // Fill as much of `out` as there's data for, returning how much that was
+    pub(crate) fn fill(&mut self, out: &mut [u8]) -> usize {
+        let mut len = 0;
+        while len < out.len() {
+            if self.left == 0 {
+                let Some(&header) = self.input.get(self.i) else {
+                    break;
+                };
+                self.i += 1;
+                match header {
+                    0..=127 => (self.left, self.repeat) = (header as usize + 1, false),
+                    128 => continue,
+                    _ => (self.left, self.repeat) = (257 - header as usize, true),
+                }
+            }
+            let count = self.left.min(out.len() - len);
+            let dest = &mut out[len..len + count];
+            if self.repeat {
+                let Some(&byte) = self.input.get(self.i) else {
+                    break;
+                };
+                dest.fill(byte);
+                if self.left == count {
+                    self.i += 1;
+                }
+            } else {
+                let Some(src) = self.input.get(self.i..self.i + count) else {
+                    break;
+                };
+                dest.copy_from_slice(src);
+                self.i += count;
+            }
+            self.left -= count;
+            len += count;
+        }
+        len
+    }
+
+    // Whether all of the data has been unpacked, ignoring any no-op headers
+    // left at the end
+    fn finished(&self) -> bool {
+        self.left == 0 && self.input.get(self.i..).is_none_or(|rest| rest.iter().all(|&b| b == 128))
+    }
+}
+
+// Compressed data is stored as its decompressed length followed by the
+// encoded bytes
+const COMPRESSED_HEADER: usize = 2;
+
+pub(crate) fn write_compressed(disk: &Disk, data: &[u8]) -> bool {
+    if data.len() > u16::MAX as usize {
+        return false;
+    }
+    let mut image = [0; Disk::CAPACITY];
+    let Some(len) = rle_encode(data, &mut image[COMPRESSED_HEADER..]) else {
+        return false;
+    };
+    image[..COMPRESSED_HEADER].copy_from_slice(&(data.len() as u16).to_le_bytes());
+    disk.write_all(&image[..COMPRESSED_HEADER + len]);
+    true
+}
+
+pub(crate) fn read_compressed(disk: &Disk, buf: &mut [u8]) -> Option<usize> {
+    let (image, len) = read_image(disk);
+    if len < COMPRESSED_HEADER {
+        return None;
+    }
+    let expected = u16::from_le_bytes([image[0], image[1]]) as usize;
+    let decoded = rle_decode(&image[COMPRESSED_HEADER..len], buf)?;
+    (decoded == expected).then_some(decoded)
+}
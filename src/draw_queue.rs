@@ -0,0 +1,129 @@
+//! Deferred drawing, sorted by layer
+
+use crate::{read_draw_colors, write_draw_colors, FrameBuffer, SpriteFlags};
+
+/// Records draw commands to be drawn later, in order of their layer
+/// 
+/// Commands on lower layers are drawn first, and commands on the same layer
+/// are drawn in the order they were added. The draw colors at the time a
+/// command is added are the ones it will be drawn with.
+pub struct DrawQueue<'a, const N: usize> {
+    entries: [Option<Entry<'a>>; N],
+    len: usize,
+}
+
+struct Entry<'a> {
+    layer: i32,
+    draw_colors: u16,
+    command: Command<'a>,
+}
+
+enum Command<'a> {
+    Sprite {
+        sprite: &'a [u8],
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        flags: u32,
+    },
+    Rect {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
+    Text {
+        s: &'a str,
+        x: i32,
+        y: i32,
+    },
+}
+
+impl<'a, const N: usize> DrawQueue<'a, N> {
+    /// An empty queue
+    pub fn new() -> Self {
+        Self {
+            entries: core::array::from_fn(|_| None),
+            len: 0,
+        }
+    }
+
+    /// The number of queued commands
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Are there no queued commands?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Discard all queued commands without drawing them
+    pub fn clear(&mut self) {
+        self.entries[..self.len].fill_with(|| None);
+        self.len = 0;
+    }
+
+    /// Queue a sprite, see [`FrameBuffer::sprite`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn sprite(
+        &mut self,
+        layer: i32,
+        sprite: &'a [u8],
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        flags: SpriteFlags,
+    ) {
+        self.push(layer, Command::Sprite { sprite, x, y, width, height, flags: flags.0 });
+    }
+
+    /// Queue a rectangle, see [`FrameBuffer::rect`]
+    pub fn rect(&mut self, layer: i32, x: i32, y: i32, width: u32, height: u32) {
+        self.push(layer, Command::Rect { x, y, width, height });
+    }
+
+    /// Queue text, see [`FrameBuffer::text`]
+    pub fn text(&mut self, layer: i32, s: &'a str, x: i32, y: i32) {
+        self.push(layer, Command::Text { s, x, y });
+    }
+
+    /// Draw all queued commands in layer order, emptying the queue
+    /// 
+    /// The draw colors are restored afterwards
+    pub fn flush(&mut self, fb: &mut FrameBuffer) {
+        let prev_colors = read_draw_colors();
+        for entry in self.entries[..self.len].iter_mut().filter_map(Option::take) {
+            write_draw_colors(entry.draw_colors);
+            match entry.command {
+                Command::Sprite { sprite, x, y, width, height, flags } => {
+                    fb.sprite(sprite, x, y, width, height, SpriteFlags(flags))
+                }
+                Command::Rect { x, y, width, height } => fb.rect(x, y, width, height),
+                Command::Text { s, x, y } => fb.text(s, x, y),
+            }
+        }
+        self.len = 0;
+        write_draw_colors(prev_colors);
+    }
+
+    fn push(&mut self, layer: i32, command: Command<'a>) {
+        crate::assert(self.len < N, "draw queue is full");
+        // Insert after every entry on the same layer, to keep insertion order
+        let idx = self.entries[..self.len]
+            .iter()
+            .position(|e| matches!(e, Some(e) if e.layer > layer))
+            .unwrap_or(self.len);
+        self.entries[idx..=self.len].rotate_right(1);
+        self.entries[idx] = Some(Entry { layer, draw_colors: read_draw_colors(), command });
+        self.len += 1;
+    }
+}
+
+impl<const N: usize> Default for DrawQueue<'_, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
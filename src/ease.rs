@@ -0,0 +1,152 @@
+//! Easing functions, for smooth movement without floats
+//! 
+//! Each function takes a progress `t` from 0 to 1, clamping anything outside
+//! that, and gives the eased progress. Most stay within 0 to 1, but the 
+//! elastic ones overshoot.
+//! 
+//! `in` functions start slow, `out` functions end slow, and `in_out` 
+//! functions do both.
+
+use crate::fixed::Fixed;
+
+/// An easing function
+pub type Ease = fn(Fixed) -> Fixed;
+
+/// Ease a `t` from `0..=255` to a result in `0..=255`, saturating if the 
+/// function overshoots
+pub fn byte(ease: Ease, t: u8) -> u8 {
+    let out = ease(Fixed::from_ratio(t as i32, 255)) * 255;
+    out.round().clamp(0, 255) as u8
+}
+
+/// Move between `from` and `to`, with `t` eased by `ease`
+pub fn tween(ease: Ease, from: i32, to: i32, t: Fixed) -> i32 {
+    from + (Fixed::from_int(to - from) * ease(t)).round()
+}
+
+/// Move between `from` and `to` over `duration` frames, with `elapsed` being 
+/// how many frames have passed
+pub fn tween_frames(ease: Ease, from: i32, to: i32, elapsed: u32, duration: u32) -> i32 {
+    if elapsed >= duration {
+        return to;
+    }
+    tween(ease, from, to, Fixed::from_ratio(elapsed as i32, duration as i32))
+}
+
+fn clamp(t: Fixed) -> Fixed {
+    t.clamp(Fixed::ZERO, Fixed::ONE)
+}
+
+// `in` and `in_out` can be made from `out`, and vice versa
+fn flip(out: Ease, t: Fixed) -> Fixed {
+    Fixed::ONE - out(Fixed::ONE - clamp(t))
+}
+
+fn in_out(ease_in: Ease, ease_out: Ease, t: Fixed) -> Fixed {
+    let t = clamp(t);
+    if t < Fixed::HALF {
+        ease_in(t * 2) / 2
+    } else {
+        Fixed::HALF + ease_out(t * 2 - Fixed::ONE) / 2
+    }
+}
+
+pub fn linear(t: Fixed) -> Fixed {
+    clamp(t)
+}
+
+pub fn quad_in(t: Fixed) -> Fixed {
+    let t = clamp(t);
+    t * t
+}
+
+pub fn quad_out(t: Fixed) -> Fixed {
+    flip(quad_in, t)
+}
+
+pub fn quad_in_out(t: Fixed) -> Fixed {
+    in_out(quad_in, quad_out, t)
+}
+
+pub fn cubic_in(t: Fixed) -> Fixed {
+    let t = clamp(t);
+    t * t * t
+}
+
+pub fn cubic_out(t: Fixed) -> Fixed {
+    flip(cubic_in, t)
+}
+
+pub fn cubic_in_out(t: Fixed) -> Fixed {
+    in_out(cubic_in, cubic_out, t)
+}
+
+pub fn sine_in(t: Fixed) -> Fixed {
+    flip(sine_out, t)
+}
+
+pub fn sine_out(t: Fixed) -> Fixed {
+    (clamp(t) / 4).sin_turns()
+}
+
+pub fn sine_in_out(t: Fixed) -> Fixed {
+    (Fixed::ONE - (Fixed::ONE / 4 + clamp(t) / 2).sin_turns()) / 2
+}
+
+pub fn bounce_in(t: Fixed) -> Fixed {
+    flip(bounce_out, t)
+}
+
+pub fn bounce_out(t: Fixed) -> Fixed {
+    // Four parabolas, each bounce a quarter the height of the last
+    let t = clamp(t);
+    let n = Fixed::from_ratio(121, 16);
+    let (offset, height) = if t < Fixed::from_ratio(4, 11) {
+        (Fixed::ZERO, Fixed::ZERO)
+    } else if t < Fixed::from_ratio(8, 11) {
+        (Fixed::from_ratio(6, 11), Fixed::from_ratio(3, 4))
+    } else if t < Fixed::from_ratio(10, 11) {
+        (Fixed::from_ratio(9, 11), Fixed::from_ratio(15, 16))
+    } else {
+        (Fixed::from_ratio(21, 22), Fixed::from_ratio(63, 64))
+    };
+    let t = t - offset;
+    (n * t * t + height).min(Fixed::ONE)
+}
+
+pub fn bounce_in_out(t: Fixed) -> Fixed {
+    in_out(bounce_in, bounce_out, t)
+}
+
+pub fn elastic_in(t: Fixed) -> Fixed {
+    flip(elastic_out, t)
+}
+
+pub fn elastic_out(t: Fixed) -> Fixed {
+    let t = clamp(t);
+    if t == Fixed::ZERO || t == Fixed::ONE {
+        return t;
+    }
+    // 2^(-10t) * sin((10t - 0.75) * 2pi/3) + 1
+    let wave = ((t * 10 - Fixed::from_ratio(3, 4)) / 3).sin_turns();
+    exp2_neg(t * 10) * wave + Fixed::ONE
+}
+
+pub fn elastic_in_out(t: Fixed) -> Fixed {
+    in_out(elastic_in, elastic_out, t)
+}
+
+/// 2 to the power of `-x`, for non-negative `x`
+fn exp2_neg(x: Fixed) -> Fixed {
+    let whole = x.to_int();
+    if whole >= Fixed::FRAC_BITS as i32 {
+        return Fixed::ZERO;
+    }
+    // 2^-f for f in 0 to 1, from its Taylor series
+    let f = x.fract();
+    let c1 = Fixed::from_bits(45426);
+    let c2 = Fixed::from_bits(15743);
+    let c3 = Fixed::from_bits(3638);
+    let frac = Fixed::ONE - f * (c1 - f * (c2 - f * c3));
+    Fixed::from_bits(frac.to_bits() >> whole)
+}
@@ -0,0 +1,160 @@
+//! Storage for game objects, like bullets and enemies, without allocating
+
+/// Refers to a value in a [`Pool`]
+/// 
+/// Once the value is despawned, the handle stops working, even if its slot is
+/// reused by something else.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: u16,
+    generation: u16,
+}
+
+impl Handle {
+    /// The slot in the pool the value is stored in
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+}
+
+struct Slot<T> {
+    generation: u16,
+    value: Option<T>,
+}
+
+/// Up to `N` values, each found by a stable [`Handle`]
+pub struct Pool<T, const N: usize> {
+    slots: [Slot<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> Pool<T, N> {
+    pub fn new() -> Self {
+        Self {
+            slots: core::array::from_fn(|_| Slot {
+                generation: 0,
+                value: None,
+            }),
+            len: 0,
+        }
+    }
+
+    /// Add a value, returning `None` if the pool is full
+    pub fn spawn(&mut self, value: T) -> Option<Handle> {
+        let index = self.slots.iter().position(|slot| slot.value.is_none())?;
+        let slot = &mut self.slots[index];
+        slot.value = Some(value);
+        self.len += 1;
+        Some(Handle {
+            index: index as u16,
+            generation: slot.generation,
+        })
+    }
+
+    /// Remove a value, returning it if it was still there
+    pub fn despawn(&mut self, handle: Handle) -> Option<T> {
+        let slot = self.slots.get_mut(handle.index as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        let value = slot.value.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        let slot = self.slots.get(handle.index as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        slot.value.as_ref()
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        let slot = self.slots.get_mut(handle.index as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        slot.value.as_mut()
+    }
+
+    /// Is the value for `handle` still there?
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// Every value, along with its handle
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(i, slot)| {
+            let handle = Handle {
+                index: i as u16,
+                generation: slot.generation,
+            };
+            slot.value.as_ref().map(|value| (handle, value))
+        })
+    }
+
+    /// Every value, along with its handle
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle, &mut T)> + '_ {
+        self.slots.iter_mut().enumerate().filter_map(|(i, slot)| {
+            let handle = Handle {
+                index: i as u16,
+                generation: slot.generation,
+            };
+            slot.value.as_mut().map(|value| (handle, value))
+        })
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> + '_ {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+    }
+
+    /// Despawn every value that `f` returns `false` for
+    pub fn retain(&mut self, mut f: impl FnMut(Handle, &mut T) -> bool) {
+        for (i, slot) in self.slots.iter_mut().enumerate() {
+            let handle = Handle {
+                index: i as u16,
+                generation: slot.generation,
+            };
+            if let Some(value) = &mut slot.value {
+                if !f(handle, value) {
+                    slot.value = None;
+                    slot.generation = slot.generation.wrapping_add(1);
+                    self.len -= 1;
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Despawn everything
+    pub fn clear(&mut self) {
+        self.retain(|_, _| false);
+    }
+}
+
+impl<T, const N: usize> Default for Pool<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
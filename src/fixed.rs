@@ -0,0 +1,232 @@
+//! Fixed-point numbers, for fractional math without floats
+
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// A signed fixed-point number with 16 integer bits and 16 fractional bits
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed(i32);
+
+impl Fixed {
+    /// The number of fractional bits
+    pub const FRAC_BITS: u32 = 16;
+
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1 << Self::FRAC_BITS);
+    pub const HALF: Self = Self(1 << (Self::FRAC_BITS - 1));
+    pub const MIN: Self = Self(i32::MIN);
+    pub const MAX: Self = Self(i32::MAX);
+    /// The smallest positive value
+    pub const EPSILON: Self = Self(1);
+    /// 1 / sqrt(2)
+    pub const FRAC_1_SQRT_2: Self = Self(46341);
+
+    /// Create from the raw bits, where 1.0 is `1 << FRAC_BITS`
+    pub const fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    /// The raw bits, where 1.0 is `1 << FRAC_BITS`
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    pub const fn from_int(x: i32) -> Self {
+        Self(x << Self::FRAC_BITS)
+    }
+
+    /// Create from the fraction `num / den`
+    pub const fn from_ratio(num: i32, den: i32) -> Self {
+        Self((((num as i64) << Self::FRAC_BITS) / den as i64) as i32)
+    }
+
+    /// Round down to an integer
+    pub const fn to_int(self) -> i32 {
+        self.0 >> Self::FRAC_BITS
+    }
+
+    /// Round to the nearest integer
+    pub const fn round(self) -> i32 {
+        (self.0 + Self::HALF.0) >> Self::FRAC_BITS
+    }
+
+    /// Round down
+    pub const fn floor(self) -> Self {
+        Self(self.0 & !(Self::ONE.0 - 1))
+    }
+
+    /// The fractional part, always in `0..1`
+    pub const fn fract(self) -> Self {
+        Self(self.0 & (Self::ONE.0 - 1))
+    }
+
+    pub const fn abs(self) -> Self {
+        Self(self.0.abs())
+    }
+
+    pub const fn signum(self) -> i32 {
+        self.0.signum()
+    }
+
+    pub const fn min(self, other: Self) -> Self {
+        if self.0 < other.0 {
+            self
+        } else {
+            other
+        }
+    }
+
+    pub const fn max(self, other: Self) -> Self {
+        if self.0 > other.0 {
+            self
+        } else {
+            other
+        }
+    }
+
+    pub const fn clamp(self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+
+    /// Linearly interpolate between `self` and `other`, where `t` is in `0..=1`
+    pub fn lerp(self, other: Self, t: Self) -> Self {
+        self + (other - self) * t
+    }
+
+    /// The sine of an angle measured in turns, so 1 is all the way around
+    pub fn sin_turns(self) -> Self {
+        // Reduce to the first quarter turn, where x is 0 to 1
+        let quarters = self * 4;
+        let quadrant = quarters.to_int() & 0b11;
+        let frac = quarters.fract();
+        let (x, negate) = match quadrant {
+            0 => (frac, false),
+            1 => (Self::ONE - frac, false),
+            2 => (frac, true),
+            _ => (Self::ONE - frac, true),
+        };
+        // The Taylor series of sin(x * pi/2), up to x^7
+        let x2 = x * x;
+        let c1 = Self::from_bits(102944);
+        let c3 = Self::from_bits(42334);
+        let c5 = Self::from_bits(5223);
+        let c7 = Self::from_bits(307);
+        let y = (x * (c1 - x2 * (c3 - x2 * (c5 - x2 * c7)))).min(Self::ONE);
+        if negate {
+            -y
+        } else {
+            y
+        }
+    }
+
+    /// The cosine of an angle measured in turns
+    pub fn cos_turns(self) -> Self {
+        (self + Self::ONE / 4).sin_turns()
+    }
+}
+
+impl From<i32> for Fixed {
+    fn from(x: i32) -> Self {
+        Self::from_int(x)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(((self.0 as i64 * rhs.0 as i64) >> Self::FRAC_BITS) as i32)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Self((((self.0 as i64) << Self::FRAC_BITS) / rhs.0 as i64) as i32)
+    }
+}
+
+impl Mul<i32> for Fixed {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Self(self.0 * rhs)
+    }
+}
+
+impl Div<i32> for Fixed {
+    type Output = Self;
+
+    fn div(self, rhs: i32) -> Self::Output {
+        Self(self.0 / rhs)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Fixed {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Fixed {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl MulAssign<i32> for Fixed {
+    fn mul_assign(&mut self, rhs: i32) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign<i32> for Fixed {
+    fn div_assign(&mut self, rhs: i32) {
+        *self = *self / rhs;
+    }
+}
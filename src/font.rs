@@ -0,0 +1,92 @@
+//! Fonts other than wasm4's own, drawn as sprites
+//! 
+//! Make one from a BDF file or a PNG of glyphs at compile time with
+//! `include_font!`.
+
+use crate::{FrameBuffer, Sprite};
+
+/// A font with every glyph side by side in one 1bpp bitmap
+/// 
+/// Glyphs can each be a different width. Like [`FrameBuffer::text`], draw
+/// color 1 is used for the text and draw color 2 for the background.
+/// 
+/// ```ignore
+/// const PIXEL: Font = include_font!("fonts/pixel.bdf");
+/// 
+/// PIXEL.draw(&mut w.frame_buffer, "Hello!", 10, 10);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Font<'a> {
+    /// The characters that have glyphs, in the same order as the glyphs
+    pub chars: &'a str,
+    /// Where each glyph starts in the bitmap, in pixels from the left
+    pub offsets: &'a [u16],
+    /// How wide each glyph is, in pixels
+    pub widths: &'a [u8],
+    /// Every glyph, as tall as the font, with the text as 0 bits so it draws
+    /// in draw color 1
+    pub bitmap: Sprite<'a>,
+    /// The gap after each glyph, in pixels
+    pub spacing: u8,
+    /// The gap between lines, in pixels
+    pub line_spacing: u8,
+}
+
+impl<'a> Font<'a> {
+    /// How tall each line of text is, not counting `line_spacing`
+    pub const fn height(&self) -> u32 {
+        self.bitmap.height
+    }
+
+    /// The index of a character's glyph, if the font has one
+    pub fn glyph(&self, c: char) -> Option<usize> {
+        self.chars.chars().position(|g| g == c)
+    }
+
+    /// How wide a character's glyph is, or 0 if the font doesn't have one
+    pub fn char_width(&self, c: char) -> u32 {
+        self.glyph(c).map_or(0, |i| self.widths[i] as u32)
+    }
+
+    /// The width and height in pixels that `s` takes up when drawn with this
+    /// font
+    pub fn size(&self, s: &str) -> (u32, u32) {
+        let mut lines = 0;
+        let mut width = 0;
+        for line in s.split('\n') {
+            lines += 1;
+            let glyphs = line.chars().filter_map(|c| self.glyph(c));
+            let (count, line_width) = glyphs.fold((0, 0), |(n, w), i| (n + 1, w + self.widths[i] as u32));
+            width = width.max(line_width + (count as u32).saturating_sub(1) * self.spacing as u32);
+        }
+        let line_height = self.height() + self.line_spacing as u32;
+        (width, (lines * line_height).saturating_sub(self.line_spacing as u32))
+    }
+
+    /// Draw text with its top left corner at `x`, `y`
+    /// 
+    /// Characters the font doesn't have are skipped.
+    pub fn draw(&self, fb: &mut FrameBuffer, s: &str, x: i32, y: i32) {
+        let line_height = (self.height() + self.line_spacing as u32) as i32;
+        for (row, line) in s.split('\n').enumerate() {
+            let mut pen = x;
+            for i in line.chars().filter_map(|c| self.glyph(c)) {
+                let width = self.widths[i] as u32;
+                if width > 0 {
+                    fb.sub_sprite(
+                        self.bitmap.data,
+                        pen,
+                        y + row as i32 * line_height,
+                        width,
+                        self.height(),
+                        self.offsets[i] as u32,
+                        0,
+                        self.bitmap.width,
+                        self.bitmap.flags,
+                    );
+                }
+                pen += (width + self.spacing as u32) as i32;
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Points and rectangles, to avoid passing loose coordinates around
+
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// A 2D point or offset
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Vec2<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Vec2<i32> {
+    pub const ZERO: Self = Self::new(0, 0);
+
+    /// Clamp each component between the components of `min` and `max`
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        Self::new(self.x.clamp(min.x, max.x), self.y.clamp(min.y, max.y))
+    }
+}
+
+impl<T: Add<Output = T>> Add for Vec2<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Vec2<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Vec2<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Div<Output = T> + Copy> Div<T> for Vec2<T> {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self {
+        Self::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Vec2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Vec2<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Vec2<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T> From<(T, T)> for Vec2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T> From<Vec2<T>> for (T, T) {
+    fn from(v: Vec2<T>) -> Self {
+        (v.x, v.y)
+    }
+}
+
+/// An axis-aligned rectangle, from its top left corner
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    /// The whole screen
+    pub const SCREEN: Self = Self::new(0, 0, 160, 160);
+
+    pub const fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    pub const fn from_pos_size(pos: Vec2, width: u32, height: u32) -> Self {
+        Self::new(pos.x, pos.y, width, height)
+    }
+
+    /// The smallest rectangle containing both corners
+    pub fn from_corners(a: Vec2, b: Vec2) -> Self {
+        let x = a.x.min(b.x);
+        let y = a.y.min(b.y);
+        Self::new(x, y, a.x.abs_diff(b.x), a.y.abs_diff(b.y))
+    }
+
+    /// The top left corner
+    pub const fn pos(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    /// One past the rightmost column
+    pub const fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    /// One past the bottom row
+    pub const fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    pub const fn center(&self) -> Vec2 {
+        Vec2::new(
+            self.x + (self.width / 2) as i32,
+            self.y + (self.height / 2) as i32,
+        )
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub const fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.x && point.x < self.right() && point.y >= self.y && point.y < self.bottom()
+    }
+
+    /// Is `other` entirely inside this rectangle?
+    pub const fn contains_rect(&self, other: &Rect) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    pub const fn intersects(&self, other: &Rect) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+
+    /// The overlapping area of both rectangles, if there is any
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        if !self.intersects(other) {
+            return None;
+        }
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        Some(Rect::new(x, y, (right - x) as u32, (bottom - y) as u32))
+    }
+
+    /// The smallest rectangle containing both, ignoring either if it's empty
+    pub fn union(&self, other: &Rect) -> Rect {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return *other;
+        }
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        Rect::new(x, y, (right - x) as u32, (bottom - y) as u32)
+    }
+
+    /// The closest point inside the rectangle to `point`
+    /// 
+    /// An empty rectangle clamps everything to its position
+    pub fn clamp(&self, point: Vec2) -> Vec2 {
+        let max = Vec2::new(
+            self.x.max(self.right() - 1),
+            self.y.max(self.bottom() - 1),
+        );
+        point.clamp(self.pos(), max)
+    }
+
+    /// The same rectangle moved by `offset`
+    pub const fn offset(&self, offset: Vec2) -> Rect {
+        Rect::new(self.x + offset.x, self.y + offset.y, self.width, self.height)
+    }
+
+    /// Grow the rectangle by `amount` on every side, or shrink it if negative
+    pub const fn inflate(&self, amount: i32) -> Rect {
+        let width = self.width as i32 + amount * 2;
+        let height = self.height as i32 + amount * 2;
+        Rect::new(
+            self.x - amount,
+            self.y - amount,
+            if width > 0 { width as u32 } else { 0 },
+            if height > 0 { height as u32 } else { 0 },
+        )
+    }
+}
@@ -0,0 +1,81 @@
+//! The gamepad glyphs in wasm4's font
+//! 
+//! The font draws buttons and arrows for the characters `\u{80}` to 
+//! `\u{87}`. These give them names, and [`glyph_str!`](crate::glyph_str) 
+//! puts them into string literals:
+//! 
+//! ```ignore
+//! w.frame_buffer.text(glyph_str!("Press {X} to start"), 20, 80);
+//! w.frame_buffer.text_fmt(format_args!("{} to jump", Glyph::from(Button::Z)), 20, 90);
+//! ```
+
+use core::fmt;
+
+use crate::Button;
+
+/// The X button
+pub const X: &str = "\u{80}";
+/// The Z button
+pub const Z: &str = "\u{81}";
+/// The left arrow on the d-pad
+pub const LEFT: &str = "\u{84}";
+/// The right arrow on the d-pad
+pub const RIGHT: &str = "\u{85}";
+/// The up arrow on the d-pad
+pub const UP: &str = "\u{86}";
+/// The down arrow on the d-pad
+pub const DOWN: &str = "\u{87}";
+
+/// A glyph from wasm4's font, which formats as its character
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Glyph {
+    X,
+    Z,
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Glyph {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Glyph::X => X,
+            Glyph::Z => Z,
+            Glyph::Left => LEFT,
+            Glyph::Right => RIGHT,
+            Glyph::Up => UP,
+            Glyph::Down => DOWN,
+        }
+    }
+
+    pub const fn as_char(self) -> char {
+        match self {
+            Glyph::X => '\u{80}',
+            Glyph::Z => '\u{81}',
+            Glyph::Left => '\u{84}',
+            Glyph::Right => '\u{85}',
+            Glyph::Up => '\u{86}',
+            Glyph::Down => '\u{87}',
+        }
+    }
+}
+
+impl From<Button> for Glyph {
+    fn from(button: Button) -> Self {
+        match button {
+            Button::X => Glyph::X,
+            Button::Z => Glyph::Z,
+            Button::Left => Glyph::Left,
+            Button::Right => Glyph::Right,
+            Button::Up => Glyph::Up,
+            Button::Down => Glyph::Down,
+        }
+    }
+}
+
+impl fmt::Display for Glyph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
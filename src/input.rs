@@ -0,0 +1,861 @@
+//! Input state tracked across frames
+
+use crate::collections::RingBuffer;
+use crate::{Button, Buttons, Gamepad, Mouse, MouseButton};
+
+/// How many frames apart two clicks can be to count as a double click
+pub const DOUBLE_CLICK_FRAMES: u32 = 20;
+
+/// Tracks the mouse between frames
+/// 
+/// Call [`MouseTracker::update`] once at the start of every frame.
+pub struct MouseTracker {
+    x: i16,
+    y: i16,
+    prev_x: i16,
+    prev_y: i16,
+    started: bool,
+    buttons: u8,
+    prev_buttons: u8,
+    frame: u32,
+    // The frame each button was last clicked on, for double click detection
+    last_click: [Option<u32>; 3],
+    double_clicks: u8,
+    drag_start: Option<(i16, i16)>,
+    dragging: bool,
+}
+
+/// A drag with the left mouse button
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Drag {
+    /// Where the button was pressed
+    pub start: (i16, i16),
+    /// Where the mouse cursor is now
+    pub current: (i16, i16),
+}
+
+impl MouseTracker {
+    pub const fn new() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            prev_x: 0,
+            prev_y: 0,
+            started: false,
+            buttons: 0,
+            prev_buttons: 0,
+            frame: 0,
+            last_click: [None; 3],
+            double_clicks: 0,
+            drag_start: None,
+            dragging: false,
+        }
+    }
+
+    /// Record this frame's mouse state
+    pub fn update(&mut self, mouse: &Mouse) {
+        let (x, y) = (mouse.x(), mouse.y());
+        if self.started {
+            self.prev_x = self.x;
+            self.prev_y = self.y;
+        } else {
+            // Don't report a jump from (0, 0) on the first frame
+            self.prev_x = x;
+            self.prev_y = y;
+            self.started = true;
+        }
+        self.x = x;
+        self.y = y;
+
+        self.frame = self.frame.wrapping_add(1);
+        self.prev_buttons = self.buttons;
+        self.buttons = mouse.buttons & 0b111;
+
+        self.double_clicks = 0;
+        for (i, last_click) in self.last_click.iter_mut().enumerate() {
+            let bit = 1 << i;
+            if self.buttons & !self.prev_buttons & bit == 0 {
+                continue;
+            }
+            match *last_click {
+                Some(frame) if self.frame.wrapping_sub(frame) <= DOUBLE_CLICK_FRAMES => {
+                    self.double_clicks |= bit;
+                    // So a third click doesn't count as another double click
+                    *last_click = None;
+                }
+                _ => *last_click = Some(self.frame),
+            }
+        }
+
+        if self.held(MouseButton::Left) {
+            match self.drag_start {
+                Some(start) => self.dragging |= start != (x, y),
+                None => self.drag_start = Some((x, y)),
+            }
+        } else {
+            self.drag_start = None;
+            self.dragging = false;
+        }
+    }
+
+    /// Is the button held down this frame?
+    pub fn held(&self, button: MouseButton) -> bool {
+        self.buttons & (button as u8) != 0
+    }
+
+    /// Was the button pressed this frame?
+    pub fn clicked(&self, button: MouseButton) -> bool {
+        self.buttons & !self.prev_buttons & (button as u8) != 0
+    }
+
+    /// Was the button let go of this frame?
+    pub fn released(&self, button: MouseButton) -> bool {
+        !self.buttons & self.prev_buttons & (button as u8) != 0
+    }
+
+    /// Was the button pressed this frame, shortly after being pressed before?
+    /// 
+    /// See [`DOUBLE_CLICK_FRAMES`]
+    pub fn double_clicked(&self, button: MouseButton) -> bool {
+        self.double_clicks & (button as u8) != 0
+    }
+
+    /// The current drag, if the mouse has moved while the left button is held
+    pub fn drag(&self) -> Option<Drag> {
+        match self.drag_start {
+            Some(start) if self.dragging => Some(Drag {
+                start,
+                current: (self.x, self.y),
+            }),
+            _ => None,
+        }
+    }
+
+    /// The X coordinate of the mouse cursor this frame
+    pub fn x(&self) -> i16 {
+        self.x
+    }
+    /// The Y coordinate of the mouse cursor this frame
+    pub fn y(&self) -> i16 {
+        self.y
+    }
+
+    /// The X coordinate of the mouse cursor last frame
+    pub fn prev_x(&self) -> i16 {
+        self.prev_x
+    }
+    /// The Y coordinate of the mouse cursor last frame
+    pub fn prev_y(&self) -> i16 {
+        self.prev_y
+    }
+
+    /// How far the mouse cursor moved horizontally since last frame
+    pub fn dx(&self) -> i16 {
+        self.x.wrapping_sub(self.prev_x)
+    }
+    /// How far the mouse cursor moved vertically since last frame
+    pub fn dy(&self) -> i16 {
+        self.y.wrapping_sub(self.prev_y)
+    }
+
+    /// Did the mouse cursor move since last frame?
+    pub fn moved(&self) -> bool {
+        self.dx() != 0 || self.dy() != 0
+    }
+}
+
+impl Default for MouseTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Tracks a gamepad between frames, to tell when buttons change
+/// 
+/// Call [`GamepadTracker::update`] once at the start of every frame.
+#[derive(Clone, Copy, Default)]
+pub struct GamepadTracker {
+    buttons: Buttons,
+    prev_buttons: Buttons,
+}
+
+impl GamepadTracker {
+    pub const fn new() -> Self {
+        Self {
+            buttons: Buttons::NONE,
+            prev_buttons: Buttons::NONE,
+        }
+    }
+
+    /// Record this frame's gamepad state
+    pub fn update(&mut self, gamepad: &Gamepad) {
+        self.prev_buttons = self.buttons;
+        self.buttons = Buttons::from(gamepad);
+    }
+
+    /// Is the button held down this frame?
+    pub fn held(&self, button: Button) -> bool {
+        self.buttons.contains(button)
+    }
+
+    /// Was the button pressed this frame?
+    pub fn just_pressed(&self, button: Button) -> bool {
+        self.just_pressed_buttons().contains(button)
+    }
+
+    /// Was the button let go of this frame?
+    pub fn just_released(&self, button: Button) -> bool {
+        self.just_released_buttons().contains(button)
+    }
+
+    /// All the buttons held down this frame
+    pub fn held_buttons(&self) -> Buttons {
+        self.buttons
+    }
+
+    /// All the buttons pressed this frame
+    pub fn just_pressed_buttons(&self) -> Buttons {
+        Buttons::from_bits(self.buttons.bits() & !self.prev_buttons.bits())
+    }
+
+    /// All the buttons let go of this frame
+    pub fn just_released_buttons(&self) -> Buttons {
+        Buttons::from_bits(!self.buttons.bits() & self.prev_buttons.bits())
+    }
+}
+
+/// Anything with buttons held down, which [`buttons!`](crate::buttons) can
+/// match on
+/// 
+/// Matching on just pressed buttons needs a [`GamepadTracker`].
+pub trait ButtonState {
+    /// The buttons held down right now
+    fn held_buttons(&self) -> Buttons;
+}
+
+impl ButtonState for Gamepad {
+    fn held_buttons(&self) -> Buttons {
+        Buttons::from(self)
+    }
+}
+
+impl ButtonState for Buttons {
+    fn held_buttons(&self) -> Buttons {
+        *self
+    }
+}
+
+impl ButtonState for GamepadTracker {
+    fn held_buttons(&self) -> Buttons {
+        self.buttons
+    }
+}
+
+/// Notices a button being tapped twice in quick succession, like for dashing
+/// by double tapping a direction
+/// 
+/// Call [`DoubleTap::update`] once every frame, after updating the
+/// [`GamepadTracker`] it watches.
+pub struct DoubleTap {
+    max_gap: u32,
+    frame: u32,
+    // The frame each button was last pressed on
+    last_press: [Option<u32>; 6],
+    tapped: Buttons,
+}
+
+impl DoubleTap {
+    /// Count two presses as a double tap if the second comes at most
+    /// `max_gap` frames after the first
+    pub const fn new(max_gap: u32) -> Self {
+        Self {
+            max_gap,
+            frame: 0,
+            last_press: [None; 6],
+            tapped: Buttons::NONE,
+        }
+    }
+
+    /// Record this frame's presses
+    pub fn update(&mut self, gamepad: &GamepadTracker) {
+        self.frame = self.frame.wrapping_add(1);
+        self.tapped = Buttons::NONE;
+        for button in gamepad.just_pressed_buttons() {
+            let last_press = &mut self.last_press[button.index()];
+            match *last_press {
+                Some(frame) if self.frame.wrapping_sub(frame) <= self.max_gap => {
+                    self.tapped.insert(button);
+                    // So a third tap doesn't count as another double tap
+                    *last_press = None;
+                }
+                _ => *last_press = Some(self.frame),
+            }
+        }
+    }
+
+    /// Was the button pressed this frame, shortly after being pressed before?
+    pub fn tapped(&self, button: Button) -> bool {
+        self.tapped.contains(button)
+    }
+
+    /// All the buttons double tapped this frame
+    pub fn tapped_buttons(&self) -> Buttons {
+        self.tapped
+    }
+
+    /// Forget earlier presses, so the next press can't complete a double tap
+    pub fn reset(&mut self) {
+        self.last_press = [None; 6];
+        self.tapped = Buttons::NONE;
+    }
+}
+
+/// Turns held buttons into repeated presses, like holding a key on a keyboard
+/// 
+/// A button fires on the frame it's pressed, then again after `delay` frames,
+/// then every `interval` frames after that for as long as it's held.
+/// Call [`Repeater::update`] once at the start of every frame.
+pub struct Repeater {
+    delay: u16,
+    interval: u16,
+    // Frames until each held button fires again
+    timers: [Option<u16>; 6],
+    fired: Buttons,
+}
+
+impl Repeater {
+    pub const fn new(delay: u16, interval: u16) -> Self {
+        Self {
+            delay,
+            interval,
+            timers: [None; 6],
+            fired: Buttons::NONE,
+        }
+    }
+
+    /// Record this frame's gamepad state
+    pub fn update(&mut self, gamepad: &Gamepad) {
+        self.fired = Buttons::NONE;
+        for button in Button::ALL {
+            let timer = &mut self.timers[button.index()];
+            *timer = match *timer {
+                _ if !gamepad.pressed(button) => None,
+                // Just pressed
+                None => {
+                    self.fired.insert(button);
+                    Some(self.delay)
+                }
+                Some(0 | 1) => {
+                    self.fired.insert(button);
+                    Some(self.interval)
+                }
+                Some(n) => Some(n - 1),
+            };
+        }
+    }
+
+    /// Did the button fire this frame?
+    pub fn fired(&self, button: Button) -> bool {
+        self.fired.contains(button)
+    }
+
+    /// All the buttons that fired this frame
+    pub fn fired_buttons(&self) -> Buttons {
+        self.fired
+    }
+}
+
+// What every player held, and newly pressed, on one frame
+#[derive(Clone, Copy)]
+struct BufferedFrame {
+    held: [Buttons; 4],
+    pressed: [Buttons; 4],
+}
+
+/// The last `N` frames of every player's buttons, for asking what happened
+/// within a few frames instead of on exactly this one
+/// 
+/// This makes controls forgiving: a jump pressed a few frames before landing
+/// still happens (jump buffering), and a combo's buttons don't have to be hit
+/// on the same frame. Coyote time is the same check with the window counted
+/// from when the player left the ground. Call [`InputBuffer::update`] once at
+/// the start of every frame. A window of 1 is just this frame.
+/// 
+/// ```ignore
+/// state.input.update(&w.gamepads);
+/// // Jump if X was pressed up to 6 frames before landing
+/// if player.on_ground && state.input.pressed_within(0, Button::X, 6) {
+///     state.input.consume(0, Button::X);
+///     player.jump();
+/// }
+/// ```
+pub struct InputBuffer<const N: usize> {
+    frames: RingBuffer<BufferedFrame, N>,
+    prev: [Buttons; 4],
+}
+
+impl<const N: usize> InputBuffer<N> {
+    pub const fn new() -> Self {
+        Self {
+            frames: RingBuffer::new(),
+            prev: [Buttons::NONE; 4],
+        }
+    }
+
+    /// Record this frame's gamepad state
+    pub fn update(&mut self, gamepads: &[Gamepad; 4]) {
+        let held: [Buttons; 4] = core::array::from_fn(|player| Buttons::from(&gamepads[player]));
+        let mut pressed = [Buttons::NONE; 4];
+        for player in 0..4 {
+            pressed[player] = Buttons::from_bits(held[player].bits() & !self.prev[player].bits());
+        }
+        self.prev = held;
+        self.frames.push(BufferedFrame { held, pressed });
+    }
+
+    // The last `window` frames, newest first
+    fn recent(&self, window: u32) -> impl Iterator<Item = &BufferedFrame> + '_ {
+        self.frames.iter().rev().take(window as usize)
+    }
+
+    /// The buttons a player held `frames_ago` frames ago, with 0 being this
+    /// frame, or none if that's further back than the buffer goes
+    pub fn buttons(&self, player: usize, frames_ago: usize) -> Buttons {
+        crate::assert(player < 4, "player out of range");
+        let i = self.frames.len().checked_sub(frames_ago + 1);
+        i.and_then(|i| self.frames.get(i)).map_or(Buttons::NONE, |f| f.held[player])
+    }
+
+    /// Is the button held down this frame?
+    pub fn held(&self, player: usize, button: Button) -> bool {
+        self.buttons(player, 0).contains(button)
+    }
+
+    /// Was the button pressed in the last `window` frames, and not consumed
+    /// since?
+    pub fn pressed_within(&self, player: usize, button: Button, window: u32) -> bool {
+        crate::assert(player < 4, "player out of range");
+        self.recent(window).any(|f| f.pressed[player].contains(button))
+    }
+
+    /// Was the button let go of in the last `window` frames?
+    pub fn released_within(&self, player: usize, button: Button, window: u32) -> bool {
+        crate::assert(player < 4, "player out of range");
+        let mut held = self.recent(window.saturating_add(1)).map(|f| f.held[player].contains(button));
+        let Some(mut newer) = held.next() else {
+            return false;
+        };
+        held.any(|older| {
+            let released = older && !newer;
+            newer = older;
+            released
+        })
+    }
+
+    /// Was the button held down on any of the last `window` frames?
+    pub fn held_within(&self, player: usize, button: Button, window: u32) -> bool {
+        crate::assert(player < 4, "player out of range");
+        self.recent(window).any(|f| f.held[player].contains(button))
+    }
+
+    /// How many frames in a row the button has been held, up to `N`
+    pub fn held_for(&self, player: usize, button: Button) -> u32 {
+        crate::assert(player < 4, "player out of range");
+        self.recent(u32::MAX).take_while(|f| f.held[player].contains(button)).count() as u32
+    }
+
+    /// Forget the presses of a button, so a buffered press is only acted on
+    /// once
+    pub fn consume(&mut self, player: usize, button: Button) {
+        crate::assert(player < 4, "player out of range");
+        for frame in self.frames.iter_mut() {
+            frame.pressed[player].remove(button);
+        }
+    }
+
+    /// Forget every recorded frame
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+impl<const N: usize> Default for InputBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The input for a single frame: every gamepad, and the mouse
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InputFrame {
+    pub gamepads: [Buttons; 4],
+    pub mouse_x: i16,
+    pub mouse_y: i16,
+    pub mouse_buttons: u8,
+}
+
+impl InputFrame {
+    const ENCODED_LEN: usize = 9;
+
+    /// This frame's input
+    pub fn capture(w: &crate::Wasm4) -> Self {
+        Self {
+            gamepads: [
+                Buttons::from(&w.gamepads[0]),
+                Buttons::from(&w.gamepads[1]),
+                Buttons::from(&w.gamepads[2]),
+                Buttons::from(&w.gamepads[3]),
+            ],
+            mouse_x: w.mouse.x(),
+            mouse_y: w.mouse.y(),
+            mouse_buttons: w.mouse.buttons,
+        }
+    }
+
+    /// Overwrite this frame's input
+    pub fn apply(&self, w: &mut crate::Wasm4) {
+        for (gamepad, buttons) in w.gamepads.iter_mut().zip(self.gamepads) {
+            gamepad.0 = buttons.bits();
+        }
+        w.mouse.x = self.mouse_x.to_le_bytes();
+        w.mouse.y = self.mouse_y.to_le_bytes();
+        w.mouse.buttons = self.mouse_buttons;
+    }
+
+    fn encode(&self) -> [u8; Self::ENCODED_LEN] {
+        let [g0, g1, g2, g3] = self.gamepads.map(Buttons::bits);
+        let [x0, x1] = self.mouse_x.to_le_bytes();
+        let [y0, y1] = self.mouse_y.to_le_bytes();
+        [g0, g1, g2, g3, x0, x1, y0, y1, self.mouse_buttons]
+    }
+
+    fn decode(b: &[u8]) -> Self {
+        Self {
+            gamepads: [
+                Buttons::from_bits(b[0]),
+                Buttons::from_bits(b[1]),
+                Buttons::from_bits(b[2]),
+                Buttons::from_bits(b[3]),
+            ],
+            mouse_x: i16::from_le_bytes([b[4], b[5]]),
+            mouse_y: i16::from_le_bytes([b[6], b[7]]),
+            mouse_buttons: b[8],
+        }
+    }
+}
+
+// Recordings are a sequence of runs of identical frames, each a count byte
+// followed by the encoded frame
+const RUN_LEN: usize = 1 + InputFrame::ENCODED_LEN;
+
+/// Records input every frame into a run-length encoded buffer
+/// 
+/// Play it back with [`InputPlayer`].
+pub struct InputRecorder<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> InputRecorder<N> {
+    pub const fn new() -> Self {
+        Self { buf: [0; N], len: 0 }
+    }
+
+    /// Record this frame's input
+    /// 
+    /// Returns `false` if the buffer is full, in which case nothing was recorded
+    pub fn record(&mut self, w: &crate::Wasm4) -> bool {
+        self.push(InputFrame::capture(w))
+    }
+
+    /// Record a frame of input
+    /// 
+    /// Returns `false` if the buffer is full, in which case nothing was recorded
+    pub fn push(&mut self, frame: InputFrame) -> bool {
+        let encoded = frame.encode();
+        if self.len >= RUN_LEN {
+            let last = &mut self.buf[self.len - RUN_LEN..self.len];
+            if last[0] < u8::MAX && last[1..] == encoded {
+                last[0] += 1;
+                return true;
+            }
+        }
+        if self.len + RUN_LEN > N {
+            return false;
+        }
+        self.buf[self.len] = 1;
+        self.buf[self.len + 1..self.len + RUN_LEN].copy_from_slice(&encoded);
+        self.len += RUN_LEN;
+        true
+    }
+
+    /// The recording so far
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Erase the recording
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for InputRecorder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Plays back input recorded by an [`InputRecorder`]
+pub struct InputPlayer<'a> {
+    data: &'a [u8],
+    pos: usize,
+    // How many frames of the current run have been played
+    played: u8,
+}
+
+impl<'a> InputPlayer<'a> {
+    pub const fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            played: 0,
+        }
+    }
+
+    /// Replace this frame's input with the next recorded frame
+    /// 
+    /// Call this at the start of `update`, before reading any input. Returns
+    /// `false` once the recording is over, leaving the input untouched.
+    pub fn play(&mut self, w: &mut crate::Wasm4) -> bool {
+        match self.next() {
+            Some(frame) => {
+                frame.apply(w);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Has the whole recording been played?
+    pub fn finished(&self) -> bool {
+        self.pos + RUN_LEN > self.data.len()
+    }
+
+    /// Start playing from the beginning again
+    pub fn restart(&mut self) {
+        self.pos = 0;
+        self.played = 0;
+    }
+}
+
+impl Iterator for InputPlayer<'_> {
+    type Item = InputFrame;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished() {
+            return None;
+        }
+        let run = &self.data[self.pos..self.pos + RUN_LEN];
+        let frame = InputFrame::decode(&run[1..]);
+        self.played += 1;
+        if self.played >= run[0] {
+            self.pos += RUN_LEN;
+            self.played = 0;
+        }
+        Some(frame)
+    }
+}
+
+/// A sequence of button presses, see [`ComboMatcher`]
+pub struct Combo<'a> {
+    /// The buttons held down at each step
+    /// 
+    /// D-pad directions in a step must match exactly, so `Down` won't match
+    /// down-right, while other buttons only need to be held.
+    pub steps: &'a [Buttons],
+    /// How many frames the whole sequence can take
+    pub window: u32,
+}
+
+impl<'a> Combo<'a> {
+    pub const fn new(steps: &'a [Buttons], window: u32) -> Self {
+        Self { steps, window }
+    }
+}
+
+const COMBO_HISTORY: usize = 16;
+const DIRECTIONS: u8 = 0b1111_0000;
+
+/// Watches a gamepad for [`Combo`]s, like fighting game special moves or cheat
+/// codes
+/// 
+/// Every change in held buttons is an input; a combo matches when its steps
+/// appear in order (with other inputs allowed in between) within its window,
+/// ending on the current frame.
+pub struct ComboMatcher<'a> {
+    combos: &'a [Combo<'a>],
+    // Ring buffer of (held buttons, frame)
+    history: [(Buttons, u32); COMBO_HISTORY],
+    len: usize,
+    head: usize,
+    frame: u32,
+    prev: Buttons,
+}
+
+impl<'a> ComboMatcher<'a> {
+    pub const fn new(combos: &'a [Combo<'a>]) -> Self {
+        Self {
+            combos,
+            history: [(Buttons::NONE, 0); COMBO_HISTORY],
+            len: 0,
+            head: 0,
+            frame: 0,
+            prev: Buttons::NONE,
+        }
+    }
+
+    /// Record this frame's gamepad state
+    /// 
+    /// Returns the index of the combo completed this frame, if any
+    pub fn update(&mut self, gamepad: &Gamepad) -> Option<usize> {
+        self.frame = self.frame.wrapping_add(1);
+        let held = Buttons::from(gamepad);
+        if held == self.prev {
+            return None;
+        }
+        self.prev = held;
+        if held.is_empty() {
+            return None;
+        }
+        self.history[self.head] = (held, self.frame);
+        self.head = (self.head + 1) % COMBO_HISTORY;
+        self.len = (self.len + 1).min(COMBO_HISTORY);
+
+        let matched = self.combos.iter().position(|combo| self.matches(combo));
+        if matched.is_some() {
+            // So the same inputs can't complete another combo
+            self.len = 0;
+        }
+        matched
+    }
+
+    /// Forget all past inputs
+    pub fn reset(&mut self) {
+        self.len = 0;
+    }
+
+    fn matches(&self, combo: &Combo) -> bool {
+        let mut events = (0..self.len).map(|i| {
+            self.history[(self.head + COMBO_HISTORY - 1 - i) % COMBO_HISTORY]
+        });
+        let mut steps = combo.steps.iter().rev();
+        // The newest input has to be the last step
+        match (events.next(), steps.next()) {
+            (Some((held, _)), Some(&step)) if step_matches(step, held) => (),
+            _ => return false,
+        }
+        let mut events = events.take_while(|&(_, frame)| self.frame.wrapping_sub(frame) < combo.window);
+        steps.all(|&step| events.any(|(held, _)| step_matches(step, held)))
+    }
+}
+
+fn step_matches(step: Buttons, held: Buttons) -> bool {
+    let (step, held) = (step.bits(), held.bits());
+    let directions_match = step & DIRECTIONS == 0 || step & DIRECTIONS == held & DIRECTIONS;
+    directions_match && held & step == step
+}
+
+/// A game action that buttons can be bound to, see [`ActionMap`]
+pub trait Action: Copy {
+    /// A unique index for this action, less than the number of actions
+    fn index(self) -> usize;
+}
+
+/// Bindings from game actions to gamepad buttons, for each player
+/// 
+/// `N` is the number of actions. Each action can be bound to any number of
+/// buttons, and is active while any of them are held.
+pub struct ActionMap<A, const N: usize> {
+    bindings: [[Buttons; N]; 4],
+    _action: core::marker::PhantomData<A>,
+}
+
+impl<A: Action, const N: usize> ActionMap<A, N> {
+    /// The number of bytes used by [`ActionMap::write_bytes`]
+    pub const BYTES: usize = 4 * N;
+
+    /// Create a map where every player has the same bindings
+    /// 
+    /// `defaults` is indexed by [`Action::index`]
+    pub const fn new(defaults: [Buttons; N]) -> Self {
+        Self {
+            bindings: [defaults; 4],
+            _action: core::marker::PhantomData,
+        }
+    }
+
+    /// The buttons bound to an action
+    pub fn bindings(&self, player: usize, action: A) -> Buttons {
+        self.bindings[player][action.index()]
+    }
+
+    /// Replace the buttons bound to an action
+    pub fn bind(&mut self, player: usize, action: A, buttons: Buttons) {
+        self.bindings[player][action.index()] = buttons;
+    }
+
+    /// Bind another button to an action, keeping its existing bindings
+    pub fn add_binding(&mut self, player: usize, action: A, button: Button) {
+        self.bindings[player][action.index()].insert(button);
+    }
+
+    /// Remove every button bound to an action
+    pub fn unbind(&mut self, player: usize, action: A) {
+        self.bindings[player][action.index()] = Buttons::NONE;
+    }
+
+    /// Is the action active for a player?
+    pub fn held(&self, w: &crate::Wasm4, player: usize, action: A) -> bool {
+        self.triggered_by(player, Buttons::from(&w.gamepads[player]), action)
+    }
+
+    /// Does a set of buttons activate the action?
+    /// 
+    /// Useful with the sets from [`GamepadTracker`], like
+    /// `map.triggered_by(0, tracker.just_pressed_buttons(), Jump)`
+    pub fn triggered_by(&self, player: usize, buttons: Buttons, action: A) -> bool {
+        self.bindings(player, action).bits() & buttons.bits() != 0
+    }
+
+    /// Rebind an action to the first of `pressed`, for "press a button" 
+    /// control menus
+    /// 
+    /// Returns whether there was a button to bind
+    pub fn capture_binding(&mut self, player: usize, action: A, pressed: Buttons) -> bool {
+        match pressed.iter().next() {
+            Some(button) => {
+                self.bind(player, action, button.into());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Write all the bindings to `out`, which must be at least 
+    /// [`ActionMap::BYTES`] long, so they can be saved to disk
+    pub fn write_bytes(&self, out: &mut [u8]) {
+        crate::assert(out.len() >= Self::BYTES, "buffer too small for bindings");
+        for (out, buttons) in out.iter_mut().zip(self.bindings.iter().flatten()) {
+            *out = buttons.bits();
+        }
+    }
+
+    /// Load bindings written by [`ActionMap::write_bytes`]
+    pub fn read_bytes(&mut self, bytes: &[u8]) {
+        crate::assert(bytes.len() >= Self::BYTES, "not enough binding data");
+        for (buttons, &byte) in self.bindings.iter_mut().flatten().zip(bytes) {
+            *buttons = Buttons::from_bits(byte);
+        }
+    }
+}
@@ -12,6 +12,52 @@ pub use sw4_macros::*;
 
 use core::fmt::Write;
 
+pub mod achievements;
+pub mod assets;
+pub mod audio;
+pub mod charset;
+pub mod checksum;
+pub mod collections;
+pub mod collide;
+pub mod console;
+pub mod debug_overlay;
+pub mod dialog;
+pub mod digits;
+pub mod dirty;
+pub mod disk;
+pub mod draw_queue;
+pub mod ease;
+pub mod entities;
+pub mod fixed;
+pub mod font;
+pub mod geometry;
+pub mod glyphs;
+pub mod input;
+#[cfg(feature = "log")]
+pub mod logger;
+pub mod menu;
+pub mod music;
+pub mod notes;
+pub mod numfmt;
+pub mod palette;
+pub mod parallax;
+pub mod particles;
+pub mod path;
+pub mod platformer;
+pub mod pause;
+pub mod raycast;
+pub mod rle;
+pub mod rng;
+pub mod scenes;
+pub mod settings;
+pub mod sfx;
+pub mod shake;
+pub mod state;
+pub mod strings;
+pub mod tilemap;
+pub mod timer;
+pub mod ui;
+pub mod wire3d;
 mod raw_api;
 
 const _SIZE_ASSERTIONS: () = {
@@ -42,6 +88,132 @@ pub struct Wasm4 {
     pub disk: Disk,
 }
 
+impl Wasm4 {
+    /// The number of frames that have finished, so 0 during the first
+    /// `update`
+    /// 
+    /// See [`frame`]
+    pub fn frame(&self) -> u64 {
+        frame()
+    }
+
+    /// The gamepad of the player on this client
+    /// 
+    /// Without netplay, this is the first gamepad
+    pub fn local_gamepad(&self) -> &Gamepad {
+        &self.gamepads[self.netplay.player_idx() as usize]
+    }
+
+    /// Every player, and which of them are playing on this client
+    pub fn players(&self) -> Players<'_> {
+        Players::new(&self.gamepads, &self.netplay)
+    }
+}
+
+/// A zero-sized stand-in for `&'a mut Wasm4`, for handing out only the parts a
+/// helper function needs
+/// 
+/// `#[update]` can take one instead of `&mut Wasm4`. Pass
+/// [`Frame::reborrow`] to helpers that need everything, or split it with
+/// [`Frame::parts`] to use several parts at once.
+/// 
+/// ```ignore
+/// #[sw4::update]
+/// fn update(mut frame: Frame, game: &mut Game) {
+///     let parts = frame.reborrow().parts();
+///     draw_world(parts.frame_buffer, &game.world);
+///     play_footsteps(parts.sounds, &game.player);
+/// }
+/// ```
+pub struct Frame<'a>(core::marker::PhantomData<&'a mut Wasm4>);
+
+/// Every part of [`Wasm4`] that can be changed, borrowed separately
+pub struct FrameParts<'a> {
+    pub palette: &'a mut Palette,
+    pub draw_colors: &'a mut DrawColors,
+    pub system_flags: &'a mut SystemFlags,
+    pub frame_buffer: &'a mut FrameBuffer,
+    pub sounds: &'a mut SoundSystem,
+    pub disk: &'a mut Disk,
+    pub gamepads: &'a [Gamepad; 4],
+    pub mouse: &'a Mouse,
+    pub netplay: &'a Netplay,
+}
+
+impl<'a> Frame<'a> {
+    pub fn new(w: &'a mut Wasm4) -> Self {
+        // There's only ever the one `Wasm4`, at address 4, so holding on to
+        // the borrow is enough
+        let _ = w;
+        Self(core::marker::PhantomData)
+    }
+
+    // Address 4 is where wasm4 puts its memory, not just `Wasm4`'s alignment.
+    // Accessors borrow only the part they hand out, so the shared parts can be
+    // held at the same time
+    #[allow(clippy::manual_dangling_ptr)]
+    const W: *mut Wasm4 = 4 as *mut Wasm4;
+
+    /// A shorter-lived `Frame`, leaving this one usable afterwards
+    pub fn reborrow(&mut self) -> Frame<'_> {
+        Frame(core::marker::PhantomData)
+    }
+
+    /// The whole [`Wasm4`]
+    pub fn wasm4(&mut self) -> &mut Wasm4 {
+        unsafe { &mut *Self::W }
+    }
+
+    pub fn palette(&mut self) -> &mut Palette {
+        unsafe { &mut (*Self::W).palette }
+    }
+
+    pub fn draw_colors(&mut self) -> &mut DrawColors {
+        unsafe { &mut (*Self::W).draw_colors }
+    }
+
+    pub fn frame_buffer(&mut self) -> &mut FrameBuffer {
+        unsafe { &mut (*Self::W).frame_buffer }
+    }
+
+    pub fn sounds(&mut self) -> &mut SoundSystem {
+        unsafe { &mut (*Self::W).sounds }
+    }
+
+    pub fn disk(&mut self) -> &mut Disk {
+        unsafe { &mut (*Self::W).disk }
+    }
+
+    pub fn gamepads(&self) -> &[Gamepad; 4] {
+        unsafe { &*core::ptr::addr_of!((*Self::W).gamepads) }
+    }
+
+    pub fn mouse(&self) -> &Mouse {
+        unsafe { &*core::ptr::addr_of!((*Self::W).mouse) }
+    }
+
+    pub fn players(&self) -> Players<'_> {
+        let netplay = unsafe { &*core::ptr::addr_of!((*Self::W).netplay) };
+        Players::new(self.gamepads(), netplay)
+    }
+
+    /// Split into every part, so several can be used at once
+    pub fn parts(self) -> FrameParts<'a> {
+        let w = unsafe { &mut *Self::W };
+        FrameParts {
+            palette: &mut w.palette,
+            draw_colors: &mut w.draw_colors,
+            system_flags: &mut w.system_flags,
+            frame_buffer: &mut w.frame_buffer,
+            sounds: &mut w.sounds,
+            disk: &mut w.disk,
+            gamepads: &w.gamepads,
+            mouse: &w.mouse,
+            netplay: &w.netplay,
+        }
+    }
+}
+
 /// The game's color palette
 #[repr(C)]
 pub struct Palette {
@@ -51,6 +223,40 @@ pub struct Palette {
     pub d: Color,
 }
 
+impl Palette {
+    /// Get all four colors, in order
+    pub fn get_all(&self) -> [Color; 4] {
+        [self.a, self.b, self.c, self.d]
+    }
+
+    /// Set all four colors, in order
+    pub fn set_all(&mut self, colors: [Color; 4]) {
+        [self.a, self.b, self.c, self.d] = colors;
+    }
+
+    /// Get the color at a palette index (0-3)
+    pub fn get(&self, index: u8) -> Color {
+        match index {
+            0 => self.a,
+            1 => self.b,
+            2 => self.c,
+            3 => self.d,
+            _ => panic("palette index out of range"),
+        }
+    }
+
+    /// Set the color at a palette index (0-3)
+    pub fn set(&mut self, index: u8, color: Color) {
+        match index {
+            0 => self.a = color,
+            1 => self.b = color,
+            2 => self.c = color,
+            3 => self.d = color,
+            _ => panic("palette index out of range"),
+        }
+    }
+}
+
 // `align(4)` to ensure size_of<Color> = 4
 #[repr(C, align(4))]
 #[derive(Clone, Copy)]
@@ -84,6 +290,7 @@ impl Color {
 pub struct DrawColors(u16);
 
 #[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DrawColor {
     Transparent = 0,
     A = 1,
@@ -92,7 +299,48 @@ pub enum DrawColor {
     D = 4,
 }
 
+impl DrawColor {
+    // wasm4 only looks at the low 2 bits of a non-zero draw color (minus 1), 
+    // so out-of-range values wrap around the palette
+    const fn from_nibble(x: u16) -> Self {
+        match x & 0b1111 {
+            0 => DrawColor::Transparent,
+            x => match (x - 1) & 0b11 {
+                0 => DrawColor::A,
+                1 => DrawColor::B,
+                2 => DrawColor::C,
+                _ => DrawColor::D,
+            },
+        }
+    }
+}
+
 impl DrawColors {
+    /// Get draw color 1
+    pub fn get_1(&self) -> DrawColor {
+        DrawColor::from_nibble(self.0)
+    }
+
+    /// Get draw color 2
+    pub fn get_2(&self) -> DrawColor {
+        DrawColor::from_nibble(self.0 >> 4)
+    }
+
+    /// Get draw color 3
+    pub fn get_3(&self) -> DrawColor {
+        DrawColor::from_nibble(self.0 >> 8)
+    }
+
+    /// Get draw color 4
+    pub fn get_4(&self) -> DrawColor {
+        DrawColor::from_nibble(self.0 >> 12)
+    }
+
+    /// Get all the draw colors, in order
+    pub fn get_all(&self) -> [DrawColor; 4] {
+        [self.get_1(), self.get_2(), self.get_3(), self.get_4()]
+    }
+
     pub fn set_all(&mut self, c1: DrawColor, c2: DrawColor, c3: DrawColor, c4: DrawColor) {
         self.0 = (c1 as u16) | ((c2 as u16) << 4) | ((c3 as u16) << 8) | ((c4 as u16) << 12)
     }
@@ -112,6 +360,85 @@ impl DrawColors {
     pub fn set_4(&mut self, color: DrawColor) {
         self.0 = (self.0 & 0b0000_1111_1111_1111) | ((color as u16) << 12);
     }
+
+    /// Set all the draw colors until the returned guard is dropped, at which
+    /// point the previous draw colors are restored
+    pub fn scoped(
+        &mut self,
+        c1: DrawColor,
+        c2: DrawColor,
+        c3: DrawColor,
+        c4: DrawColor,
+    ) -> DrawColorsGuard<'_> {
+        let prev = self.0;
+        self.set_all(c1, c2, c3, c4);
+        DrawColorsGuard { colors: self, prev }
+    }
+
+    /// Run `f`, restoring the draw colors to what they were before afterwards
+    pub fn with<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let prev = self.0;
+        let out = f(self);
+        self.0 = prev;
+        out
+    }
+}
+
+impl From<[DrawColor; 4]> for DrawColors {
+    fn from([c1, c2, c3, c4]: [DrawColor; 4]) -> Self {
+        let mut colors = DrawColors(0);
+        colors.set_all(c1, c2, c3, c4);
+        colors
+    }
+}
+
+impl core::fmt::Debug for DrawColors {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("DrawColors")
+            .field(&self.get_1())
+            .field(&self.get_2())
+            .field(&self.get_3())
+            .field(&self.get_4())
+            .finish()
+    }
+}
+
+/// Restores the previous draw colors when dropped
+/// 
+/// Created by [`DrawColors::scoped`]
+pub struct DrawColorsGuard<'a> {
+    colors: &'a mut DrawColors,
+    prev: u16,
+}
+
+impl core::ops::Deref for DrawColorsGuard<'_> {
+    type Target = DrawColors;
+
+    fn deref(&self) -> &Self::Target {
+        self.colors
+    }
+}
+
+impl core::ops::DerefMut for DrawColorsGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.colors
+    }
+}
+
+impl Drop for DrawColorsGuard<'_> {
+    fn drop(&mut self) {
+        self.colors.0 = self.prev;
+    }
+}
+
+// Direct access to the draw colors register, for draws that need to change it
+// temporarily without holding on to a `&mut DrawColors`
+pub(crate) fn read_draw_colors() -> u16 {
+    unsafe { (0x14 as *const u16).read() }
+}
+
+pub(crate) fn write_draw_colors(value: u16) {
+    unsafe { (0x14 as *mut u16).write(value) }
 }
 
 #[repr(C)]
@@ -143,6 +470,214 @@ impl Gamepad {
     pub fn down(&self) -> bool {
         self.0 & 0b1000_0000 != 0
     }
+
+    /// Is the given button pressed?
+    pub fn pressed(&self, button: Button) -> bool {
+        self.0 & (button as u8) != 0
+    }
+
+    /// Is any button pressed?
+    /// 
+    /// Handy for "press any button" screens, or for noticing when nobody's
+    /// been playing for a while.
+    pub fn any_pressed(&self) -> bool {
+        self.0 != 0
+    }
+
+    /// The raw button bits, the same as [`Button`]'s values
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// All of the buttons currently pressed
+    pub fn buttons(&self) -> ButtonsIter {
+        Buttons::from(self).iter()
+    }
+
+    /// The direction the d-pad is held in, as (x, y)
+    /// 
+    /// Right and down are positive, like screen coordinates. Holding opposite
+    /// directions cancels them out.
+    pub fn direction(&self) -> (i8, i8) {
+        let x = self.right() as i8 - self.left() as i8;
+        let y = self.down() as i8 - self.up() as i8;
+        (x, y)
+    }
+
+    /// Like [`Gamepad::direction`], but scaled so diagonals have a length of 1
+    pub fn direction_normalized(&self) -> (fixed::Fixed, fixed::Fixed) {
+        use fixed::Fixed;
+        let (x, y) = self.direction();
+        let len = if x != 0 && y != 0 {
+            Fixed::FRAC_1_SQRT_2
+        } else {
+            Fixed::ONE
+        };
+        (len * x as i32, len * y as i32)
+    }
+}
+
+/// A button on a gamepad
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Button {
+    X = 0b0000_0001,
+    Z = 0b0000_0010,
+    Left = 0b0001_0000,
+    Right = 0b0010_0000,
+    Up = 0b0100_0000,
+    Down = 0b1000_0000,
+}
+
+impl Button {
+    /// The glyph for this button in wasm4's font
+    pub fn glyph(self) -> glyphs::Glyph {
+        self.into()
+    }
+
+    /// Every button, in bit order
+    pub const ALL: [Button; 6] = [
+        Button::X,
+        Button::Z,
+        Button::Left,
+        Button::Right,
+        Button::Up,
+        Button::Down,
+    ];
+
+    /// The bit for this button in the gamepad's state
+    pub const fn bit(self) -> u8 {
+        self as u8
+    }
+
+    /// This button's index in [`Button::ALL`]
+    pub const fn index(self) -> usize {
+        match self {
+            Button::X => 0,
+            Button::Z => 1,
+            Button::Left => 2,
+            Button::Right => 3,
+            Button::Up => 4,
+            Button::Down => 5,
+        }
+    }
+}
+
+impl core::ops::BitOr for Button {
+    type Output = Buttons;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Buttons(self as u8 | rhs as u8)
+    }
+}
+
+/// A set of gamepad buttons
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Buttons(u8);
+
+impl Buttons {
+    /// No buttons
+    pub const NONE: Self = Self(0);
+    /// Every button
+    pub const ALL: Self = Self(0b1111_0011);
+
+    /// Create a set from the gamepad bit layout, ignoring unused bits
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits & Self::ALL.0)
+    }
+
+    /// The set in the gamepad bit layout
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Is the button in the set?
+    pub const fn contains(self, button: Button) -> bool {
+        self.0 & (button as u8) != 0
+    }
+
+    /// Is the set empty?
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The set with a button added, for building sets in `const`s
+    pub const fn with(self, button: Button) -> Self {
+        Self(self.0 | button as u8)
+    }
+
+    /// Add a button to the set
+    pub fn insert(&mut self, button: Button) {
+        self.0 |= button as u8;
+    }
+
+    /// Remove a button from the set
+    pub fn remove(&mut self, button: Button) {
+        self.0 &= !(button as u8);
+    }
+
+    /// Iterate over the buttons in the set, in bit order
+    pub fn iter(self) -> ButtonsIter {
+        ButtonsIter(self.0)
+    }
+}
+
+impl From<Button> for Buttons {
+    fn from(button: Button) -> Self {
+        Self(button as u8)
+    }
+}
+
+impl From<&Gamepad> for Buttons {
+    fn from(gamepad: &Gamepad) -> Self {
+        Self::from_bits(gamepad.0)
+    }
+}
+
+impl core::ops::BitOr for Buttons {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOr<Button> for Buttons {
+    type Output = Self;
+
+    fn bitor(self, rhs: Button) -> Self::Output {
+        Self(self.0 | rhs as u8)
+    }
+}
+
+impl IntoIterator for Buttons {
+    type Item = Button;
+    type IntoIter = ButtonsIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the buttons in a [`Buttons`] set
+pub struct ButtonsIter(u8);
+
+impl Iterator for ButtonsIter {
+    type Item = Button;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let button = match self.0 & self.0.wrapping_neg() {
+            0 => return None,
+            0b0000_0001 => Button::X,
+            0b0000_0010 => Button::Z,
+            0b0001_0000 => Button::Left,
+            0b0010_0000 => Button::Right,
+            0b0100_0000 => Button::Up,
+            _ => Button::Down,
+        };
+        self.0 &= !(button as u8);
+        Some(button)
+    }
 }
 
 #[repr(C)]
@@ -180,20 +715,63 @@ impl Mouse {
     pub fn middle(&self) -> bool {
         self.buttons & 0b100 != 0
     }
+    /// Is the given mouse button pressed?
+    pub fn pressed(&self, button: MouseButton) -> bool {
+        self.buttons & (button as u8) != 0
+    }
+}
+
+/// A button on the mouse
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseButton {
+    Left = 0b001,
+    Right = 0b010,
+    Middle = 0b100,
 }
 
 #[repr(C)]
 pub struct SystemFlags(u8);
 
 impl SystemFlags {
+    /// Is the frame buffer kept between frames?
+    pub fn preserve_framebuffer(&self) -> bool {
+        self.0 & 0b1 != 0
+    }
+    /// Is the gamepad overlay hidden on mobile?
+    pub fn hide_gamepad_overlay(&self) -> bool {
+        self.0 & 0b10 != 0
+    }
+
     /// Set whether the frame buffer should be kept between frames
-    pub fn preserve_framebuffer(&mut self, b: bool) {
+    pub fn set_preserve_framebuffer(&mut self, b: bool) {
         self.0 = (self.0 & 0b10) | (b as u8);
     }
     /// Set whether to hide the gamepad overlay on mobile
-    pub fn hide_gamepad_overlay(&mut self, b: bool) {
+    pub fn set_hide_gamepad_overlay(&mut self, b: bool) {
         self.0 = (self.0 & 0b1) | ((b as u8) << 1);
     }
+
+    /// Get all the flags at once
+    pub fn get(&self) -> SystemFlagsValue {
+        SystemFlagsValue {
+            preserve_framebuffer: self.preserve_framebuffer(),
+            hide_gamepad_overlay: self.hide_gamepad_overlay(),
+        }
+    }
+    /// Set all the flags at once
+    pub fn set(&mut self, flags: SystemFlagsValue) {
+        self.0 = (flags.preserve_framebuffer as u8) | ((flags.hide_gamepad_overlay as u8) << 1);
+    }
+}
+
+/// A copy of the values in [`SystemFlags`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemFlagsValue {
+    /// Whether the frame buffer should be kept between frames
+    pub preserve_framebuffer: bool,
+    /// Whether to hide the gamepad overlay on mobile
+    pub hide_gamepad_overlay: bool,
 }
 
 #[repr(C)]
@@ -212,8 +790,113 @@ impl Netplay {
     pub fn enabled(&self) -> bool {
         (self.0 & 0b100) != 0
     }
+
+    /// Is the player with the given index playing on this client?
+    /// 
+    /// Without netplay, every player is local
+    pub fn is_local(&self, player_idx: u8) -> bool {
+        !self.enabled() || player_idx == self.player_idx()
+    }
+}
+
+/// Every player's gamepad, and whether they're on this client, so the same
+/// code works for local multiplayer and netplay
+/// 
+/// ```ignore
+/// let players = w.players();
+/// // The one controlling the camera and menus on this client
+/// state.cursor.update(players.local().gamepad);
+/// for player in players {
+///     state.ships[player.index as usize].steer(player.gamepad);
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Players<'a> {
+    gamepads: &'a [Gamepad; 4],
+    netplay: &'a Netplay,
+}
+
+/// One player, see [`Players`]
+#[derive(Clone, Copy)]
+pub struct Player<'a> {
+    /// The index into `gamepads`, from 0 to 3
+    pub index: u8,
+    pub gamepad: &'a Gamepad,
+    /// Is this player playing on this client?
+    /// 
+    /// Without netplay, every player is local
+    pub is_local: bool,
+}
+
+impl<'a> Players<'a> {
+    pub fn new(gamepads: &'a [Gamepad; 4], netplay: &'a Netplay) -> Self {
+        Self { gamepads, netplay }
+    }
+
+    /// Is netplay enabled?
+    pub fn is_netplay(&self) -> bool {
+        self.netplay.enabled()
+    }
+
+    /// The player with the given index, from 0 to 3
+    pub fn get(&self, index: u8) -> Player<'a> {
+        assert(index < 4, "player out of range");
+        Player {
+            index,
+            gamepad: &self.gamepads[index as usize],
+            is_local: self.netplay.is_local(index),
+        }
+    }
+
+    /// The player on this client
+    /// 
+    /// Without netplay, this is the first player
+    pub fn local(&self) -> Player<'a> {
+        self.get(self.netplay.player_idx())
+    }
+
+    /// The players on other clients, which there are none of without netplay
+    pub fn remote(&self) -> impl Iterator<Item = Player<'a>> {
+        self.iter().filter(|player| !player.is_local)
+    }
+
+    /// Every player, in order
+    pub fn iter(&self) -> PlayersIter<'a> {
+        self.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for Players<'a> {
+    type Item = Player<'a>;
+    type IntoIter = PlayersIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        PlayersIter { players: self, next: 0 }
+    }
+}
+
+/// Iterator over every player, see [`Players`]
+pub struct PlayersIter<'a> {
+    players: Players<'a>,
+    next: u8,
 }
 
+impl<'a> Iterator for PlayersIter<'a> {
+    type Item = Player<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= 4 {
+            return None;
+        }
+        self.next += 1;
+        Some(self.players.get(self.next - 1))
+    }
+}
+
+// Whether the safe sprite draws check their data is long enough. The
+// `unchecked_sprites` feature turns this off outside of debug builds
+const CHECK_SPRITES: bool = cfg!(any(debug_assertions, not(feature = "unchecked_sprites")));
+
 #[repr(C)]
 pub struct FrameBuffer {
     buf: [u8; (160 * 160) / 4],
@@ -221,6 +904,9 @@ pub struct FrameBuffer {
 
 impl FrameBuffer {
     /// Draw a sprite to the screen
+    /// 
+    /// Traps if `sprite` is too short for its size, unless the
+    /// `unchecked_sprites` feature is on in a release build
     pub fn sprite(
         &mut self,
         sprite: &[u8],
@@ -230,14 +916,70 @@ impl FrameBuffer {
         height: u32,
         flags: SpriteFlags,
     ) {
-        assert(
-            (width * height) as usize <= sprite.len() * ((((!flags.0 & 1) + 1) * 4) as usize),
-            "not enough sprite data"
-        );
-        unsafe { raw_api::blit(sprite.as_ptr(), x, y, width, height, flags.0) }
+        if CHECK_SPRITES {
+            assert(
+                (width * height) as usize <= sprite.len() * ((((!flags.0 & 1) + 1) * 4) as usize),
+                "not enough sprite data"
+            );
+        }
+        unsafe { self.sprite_unchecked(sprite, x, y, width, height, flags) }
+    }
+
+    /// Draw a sprite to the screen, without checking there's enough data for
+    /// its size
+    /// 
+    /// # Safety
+    /// 
+    /// `sprite` must hold at least `width * height` pixels in the format given
+    /// by `flags`
+    pub unsafe fn sprite_unchecked(
+        &mut self,
+        sprite: &[u8],
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        flags: SpriteFlags,
+    ) {
+        if current_clip() == geometry::Rect::SCREEN {
+            mark_sprite(x, y, width, height, flags);
+            raw_api::blit(sprite.as_ptr(), x, y, width, height, flags.0)
+        } else {
+            self.sub_sprite_unchecked(sprite, x, y, width, height, 0, 0, width, flags)
+        }
+    }
+
+    /// Draw a sprite with its colors swapped for `remap`, without changing
+    /// the draw colors
+    /// 
+    /// `remap` takes the place of the four draw colors for this one sprite,
+    /// so the same art can be drawn in several color schemes.
+    #[allow(clippy::too_many_arguments)]
+    pub fn sprite_remapped(
+        &mut self,
+        sprite: &[u8],
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        flags: SpriteFlags,
+        remap: [DrawColor; 4],
+    ) {
+        let colors = read_draw_colors();
+        let [c1, c2, c3, c4] = remap.map(|c| c as u16);
+        write_draw_colors(c1 | (c2 << 4) | (c3 << 8) | (c4 << 12));
+        self.sprite(sprite, x, y, width, height, flags);
+        write_draw_colors(colors);
+    }
+
+    /// Draw a [`Sprite`] with its top left corner at `pos`
+    pub fn sprite_at(&mut self, sprite: &Sprite, pos: geometry::Vec2) {
+        self.sprite(sprite.data, pos.x, pos.y, sprite.width, sprite.height, sprite.flags)
     }
 
     /// Draw a part of a sprite to the screen
+    /// 
+    /// Checked like [`FrameBuffer::sprite`]
     #[allow(clippy::too_many_arguments)]
     pub fn sub_sprite(
         &mut self,
@@ -251,45 +993,412 @@ impl FrameBuffer {
         stride: u32,
         flags: SpriteFlags,
     ) {
-        assert(
-            ((width + src_x) + ((height + src_y) * stride)) as usize
-                <= sprite.len() * ((((!flags.0 & 1) + 1) * 4) as usize),
-            "not enough sprite data"
-        );
-        unsafe {
-            raw_api::blit_sub(
-                sprite.as_ptr(),
-                x,
-                y,
-                width,
-                height,
-                src_x,
-                src_y,
-                stride,
-                flags.0,
-            )
+        if CHECK_SPRITES {
+            // The last row only needs to reach the right edge of the area
+            let needed = match height {
+                0 => 0,
+                _ => (src_y + height - 1) as usize * stride as usize + (src_x + width) as usize,
+            };
+            assert(
+                needed <= sprite.len() * ((((!flags.0 & 1) + 1) * 4) as usize),
+                "not enough sprite data"
+            );
         }
+        unsafe { self.sub_sprite_unchecked(sprite, x, y, width, height, src_x, src_y, stride, flags) }
     }
 
-    /// Draw a pixel onto the screen
+    /// Draw a part of a sprite to the screen, without checking there's enough
+    /// data for it
     /// 
-    /// Draw color 1 is used for the pixel color
-    pub fn pixel(&mut self, x: i32, y: i32) {
-        let color = unsafe { (0x14 as *const u8).read() } & 0b1111;
-        if color == 0 {
-            return;
-        }
-        let color = (color - 1) & 0b11;
-        let idx = (y as usize * 40) + (x as usize >> 2);
-        let shift = (x as u8 & 0b11) << 1;
+    /// # Safety
+    /// 
+    /// `sprite` must hold every pixel of the `width` by `height` area at
+    /// `src_x`, `src_y`, in rows `stride` pixels wide in the format given by
+    /// `flags`
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn sub_sprite_unchecked(
+        &mut self,
+        sprite: &[u8],
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        src_x: u32,
+        src_y: u32,
+        stride: u32,
+        flags: SpriteFlags,
+    ) {
+        let Some((x, y, width, height, src_x, src_y)) = clip_blit(x, y, width, height, src_x, src_y, flags) else {
+            return;
+        };
+        mark_sprite(x, y, width, height, flags);
+        raw_api::blit_sub(
+            sprite.as_ptr(),
+            x,
+            y,
+            width,
+            height,
+            src_x,
+            src_y,
+            stride,
+            flags.0,
+        )
+    }
+
+    /// Draw a pixel onto the screen
+    /// 
+    /// Draw color 1 is used for the pixel color
+    pub fn pixel(&mut self, x: i32, y: i32) {
+        let color = unsafe { (0x14 as *const u8).read() } & 0b1111;
+        if color == 0 {
+            return;
+        }
+        self.put(x, y, (color - 1) & 0b11);
+    }
+
+    /// The palette index (0-3) of the pixel at `(x, y)`, or `None` if it's off
+    /// the screen
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<u8> {
+        if !(0..160).contains(&x) || !(0..160).contains(&y) {
+            return None;
+        }
+        let byte = self.buf[(y as usize * 40) + (x as usize >> 2)];
+        Some((byte >> ((x as u8 & 0b11) << 1)) & 0b11)
+    }
+
+    /// Fill the area of same colored pixels around `(x, y)` with draw color 1
+    /// 
+    /// Pixels join up with the ones above, below, and to either side. The
+    /// clip rectangle and active [`Mask`] act as a border. Returns `false` if
+    /// the area was too broken up to keep track of, in which case part of it
+    /// is left unfilled.
+    pub fn flood_fill(&mut self, x: i32, y: i32) -> bool {
+        const MAX_SEEDS: usize = 512;
+
+        let color = read_draw_colors() as u8 & 0b1111;
+        if color == 0 {
+            return true;
+        }
+        let color = (color - 1) & 0b11;
+        let Some(target) = self.get_pixel(x, y) else {
+            return true;
+        };
+        if target == color {
+            return true;
+        }
+        let mask = unsafe { *ACTIVE_MASK.get() };
+        let clip = current_clip();
+        let fillable = |fb: &Self, x: i32, y: i32| {
+            fb.get_pixel(x, y) == Some(target)
+                && clip.contains(geometry::Vec2::new(x, y))
+                && mask.is_none_or(|mask| mask.get(x, y))
+        };
+        // Points to fill outwards from, which fit in bytes on a 160x160 screen
+        let mut seeds = [(0u8, 0u8); MAX_SEEDS];
+        seeds[0] = (x as u8, y as u8);
+        let mut len = 1;
+        let mut complete = true;
+        while len > 0 {
+            len -= 1;
+            let (x, y) = (seeds[len].0 as i32, seeds[len].1 as i32);
+            if !fillable(self, x, y) {
+                continue;
+            }
+            let mut left = x;
+            while fillable(self, left - 1, y) {
+                left -= 1;
+            }
+            let mut right = x;
+            while fillable(self, right + 1, y) {
+                right += 1;
+            }
+            for x in left..=right {
+                self.put(x, y, color);
+            }
+            // One seed for each run of fillable pixels in the rows next to it
+            for y in [y - 1, y + 1] {
+                let mut in_run = false;
+                for x in left..=right {
+                    let open = fillable(self, x, y);
+                    if open && !in_run {
+                        if len < MAX_SEEDS {
+                            seeds[len] = (x as u8, y as u8);
+                            len += 1;
+                        } else {
+                            complete = false;
+                        }
+                    }
+                    in_run = open;
+                }
+            }
+        }
+        complete
+    }
+
+    /// Draw a pixel at each point, in draw color 1
+    /// 
+    /// Like [`FrameBuffer::set_pixels`]
+    pub fn pixels(&mut self, points: &[(i32, i32)]) {
+        self.set_pixels(points.iter().copied());
+    }
+
+    /// Draw a pixel at each point, in draw color 1
+    /// 
+    /// Much faster than calling [`FrameBuffer::pixel`] for each one. Points
+    /// next to each other in the same frame buffer byte (4 pixels in a row,
+    /// aligned) are written together, so sorting them by row helps.
+    pub fn set_pixels(&mut self, points: impl IntoIterator<Item = (i32, i32)>) {
+        let color = read_draw_colors() as u8 & 0b1111;
+        if color == 0 {
+            return;
+        }
+        let color = (color - 1) & 0b11;
+        let active_mask = unsafe { *ACTIVE_MASK.get() };
+        let clip = current_clip();
+        // (byte index, bits to clear, bits to set)
+        let mut pending: Option<(usize, u8, u8)> = None;
+        let mut drawn: Option<geometry::Rect> = None;
+        for (x, y) in points {
+            if !clip.contains(geometry::Vec2::new(x, y)) {
+                continue;
+            }
+            if active_mask.is_some_and(|mask| !mask.get(x, y)) {
+                continue;
+            }
+            if dirty::is_active() {
+                let pixel = geometry::Rect::new(x, y, 1, 1);
+                drawn = Some(drawn.map_or(pixel, |r| r.union(&pixel)));
+            }
+            let idx = (y as usize * 40) + (x as usize >> 2);
+            let shift = (x as u8 & 0b11) << 1;
+            match &mut pending {
+                Some((pending_idx, clear, set)) if *pending_idx == idx => {
+                    *clear |= 0b11 << shift;
+                    *set |= color << shift;
+                }
+                _ => {
+                    if let Some((idx, clear, set)) = pending {
+                        self.buf[idx] = (self.buf[idx] & !clear) | set;
+                    }
+                    pending = Some((idx, 0b11 << shift, color << shift));
+                }
+            }
+        }
+        if let Some((idx, clear, set)) = pending {
+            self.buf[idx] = (self.buf[idx] & !clear) | set;
+        }
+        if let Some(r) = drawn {
+            dirty::mark(r.x, r.y, r.width, r.height);
+        }
+    }
+
+    /// Set a pixel to a palette index (0-3)
+    /// 
+    /// This is what all of the software-implemented draws go through, so
+    /// clipping and the active [`Mask`] are handled here
+    pub(crate) fn put(&mut self, x: i32, y: i32, color: u8) {
+        self.put_masked(x, y, color, unsafe { *ACTIVE_MASK.get() });
+    }
+
+    /// Set the pixels of a column from `start` down to before `end`, with
+    /// `color` giving each row's palette index (0-3), or `None` to leave it
+    /// 
+    /// Like [`FrameBuffer::put`], but clipped and marked dirty once for the
+    /// whole span, for draws that go a column at a time
+    pub(crate) fn put_column(&mut self, x: i32, start: i32, end: i32, mut color: impl FnMut(i32) -> Option<u8>) {
+        let clip = current_clip();
+        let (start, end) = (start.max(clip.y), end.min(clip.bottom()));
+        if x < clip.x || x >= clip.right() || start >= end {
+            return;
+        }
+        let mask = unsafe { *ACTIVE_MASK.get() };
+        dirty::mark(x, start, 1, (end - start) as u32);
+        let shift = (x as u8 & 0b11) << 1;
+        let mut idx = (start as usize * 40) + (x as usize >> 2);
+        for y in start..end {
+            if let Some(color) = color(y).filter(|_| mask.is_none_or(|mask| mask.get(x, y))) {
+                self.buf[idx] = (color << shift) | (self.buf[idx] & !(0b11 << shift));
+            }
+            idx += 40;
+        }
+    }
+
+    fn put_masked(&mut self, x: i32, y: i32, color: u8, mask: Option<&Mask>) {
+        if !current_clip().contains(geometry::Vec2::new(x, y)) {
+            return;
+        }
+        if let Some(mask) = mask {
+            if !mask.get(x, y) {
+                return;
+            }
+        }
+        dirty::mark(x, y, 1, 1);
+        let idx = (y as usize * 40) + (x as usize >> 2);
+        let shift = (x as u8 & 0b11) << 1;
         let mask = !(0b11 << shift);
-        self.buf[idx] = (color << shift) | (self.buf[idx] & !mask);
+        self.buf[idx] = (color << shift) | (self.buf[idx] & mask);
+    }
+
+    /// Start drawing lots of pixels in draw color 1
+    /// 
+    /// The draw color and [`Mask`] are read once here rather than for every
+    /// pixel like [`FrameBuffer::pixel`], so changes to them aren't seen
+    /// until the next brush.
+    pub fn brush(&mut self) -> PixelBrush<'_> {
+        let color = read_draw_colors() as u8 & 0b1111;
+        PixelBrush {
+            color: color.checked_sub(1).map(|c| c & 0b11),
+            mask: unsafe { *ACTIVE_MASK.get() },
+            fb: self,
+        }
+    }
+
+    /// Restrict drawing to a rectangle, until it's changed again
+    /// 
+    /// Everything sw4 draws in software is clipped exactly. Of the draws done
+    /// by wasm4, rectangles, lines, and sprites are shrunk to fit, except for
+    /// rotated sprites. Ovals and text aren't clipped at all.
+    pub fn set_clip(&mut self, clip: geometry::Rect) {
+        let clip = clip.intersection(&geometry::Rect::SCREEN).unwrap_or_default();
+        unsafe { CLIP.get().write(clip) };
+    }
+
+    /// The rectangle drawing is currently restricted to
+    pub fn clip(&self) -> geometry::Rect {
+        current_clip()
+    }
+
+    /// Allow drawing on the whole screen again
+    pub fn reset_clip(&mut self) {
+        self.set_clip(geometry::Rect::SCREEN);
+    }
+
+    /// Run `f` with drawing restricted to the part of `clip` inside the
+    /// current clip rectangle
+    pub fn with_clip<R>(&mut self, clip: geometry::Rect, f: impl FnOnce(&mut Self) -> R) -> R {
+        let prev = current_clip();
+        self.set_clip(clip.intersection(&prev).unwrap_or_default());
+        let out = f(self);
+        self.set_clip(prev);
+        out
+    }
+
+    /// Run `f` with drawing restricted to the pixels set in `mask`
+    /// 
+    /// Only pixel-level draws done by `sw4` itself (such as [`FrameBuffer::pixel`])
+    /// honor the mask, as the draw functions provided by wasm4 write straight
+    /// to the frame buffer.
+    pub fn with_mask<R>(&mut self, mask: &Mask, f: impl FnOnce(&mut Self) -> R) -> R {
+        // The mask is removed again before `f` returns, so it can't outlive
+        // its borrow
+        let prev = unsafe { ACTIVE_MASK.get().replace(Some(&*(mask as *const Mask))) };
+        let out = f(self);
+        unsafe { ACTIVE_MASK.get().write(prev) };
+        out
+    }
+
+    /// Run `f`, adding the area covered by each draw to `tracker`
+    /// 
+    /// See [`dirty`]
+    pub fn track_dirty<R>(&mut self, tracker: &mut dirty::DirtyTracker, f: impl FnOnce(&mut Self) -> R) -> R {
+        // Like `with_mask`, the tracker is unset again before it's released
+        let prev = unsafe { dirty::set_active(Some(tracker)) };
+        let out = f(self);
+        unsafe { dirty::set_active(prev) };
+        out
+    }
+
+    /// The 40 bytes of row `y`, each holding 4 pixels with the leftmost in
+    /// the lowest 2 bits
+    /// 
+    /// Traps if `y` is off the screen
+    pub fn row(&self, y: u32) -> &[u8; 40] {
+        assert(y < 160, "row is off the screen");
+        &self.rows()[y as usize]
+    }
+
+    /// The 40 bytes of row `y`, for custom renderers to write directly
+    /// 
+    /// Writing here ignores the active [`Mask`]. See [`FrameBuffer::row`]
+    pub fn row_mut(&mut self, y: u32) -> &mut [u8; 40] {
+        assert(y < 160, "row is off the screen");
+        dirty::mark(0, y as i32, 160, 1);
+        &mut self.rows_mut()[y as usize]
+    }
+
+    fn rows(&self) -> &[[u8; 40]; 160] {
+        // Same size and alignment
+        unsafe { &*(self.buf.as_ptr() as *const [[u8; 40]; 160]) }
+    }
+
+    fn rows_mut(&mut self) -> &mut [[u8; 40]; 160] {
+        unsafe { &mut *(self.buf.as_mut_ptr() as *mut [[u8; 40]; 160]) }
+    }
+
+    /// Set `len` pixels of row `y` to a palette index (0-3), starting at `x`
+    /// 
+    /// Whole bytes are filled at once, and it's clipped like the other
+    /// draws. Honors the active [`Mask`], the fast path is only taken without
+    /// one.
+    pub fn fill_span(&mut self, x: i32, y: i32, len: u32, color: u8) {
+        let clip = current_clip();
+        if y < clip.y || y >= clip.bottom() {
+            return;
+        }
+        let start = x.max(clip.x);
+        let end = x.saturating_add_unsigned(len).min(clip.right());
+        if start >= end {
+            return;
+        }
+        let color = color & 0b11;
+        if unsafe { (*ACTIVE_MASK.get()).is_some() } {
+            for x in start..end {
+                self.put(x, y, color);
+            }
+            return;
+        }
+        dirty::mark(start, y, (end - start) as u32, 1);
+        let row = &mut self.rows_mut()[y as usize];
+        let (start, end) = (start as usize, end as usize);
+        // Partial bytes at either end, whole bytes in between
+        let first_full = start.div_ceil(4);
+        let last_full = end / 4;
+        if first_full > last_full {
+            // Within a single byte
+            let bits = span_bits(start & 0b11, end & 0b11);
+            row[start / 4] = (row[start / 4] & !bits) | (packed(color) & bits);
+            return;
+        }
+        if start & 0b11 != 0 {
+            let bits = span_bits(start & 0b11, 4);
+            row[start / 4] = (row[start / 4] & !bits) | (packed(color) & bits);
+        }
+        row[first_full..last_full].fill(packed(color));
+        if end & 0b11 != 0 {
+            let bits = span_bits(0, end & 0b11);
+            row[last_full] = (row[last_full] & !bits) | (packed(color) & bits);
+        }
+    }
+
+    /// Set all of row `y` to a palette index (0-3)
+    /// 
+    /// See [`FrameBuffer::fill_span`]
+    pub fn fill_row(&mut self, y: i32, color: u8) {
+        self.fill_span(0, y, 160, color);
     }
 
     /// Draw a line onto the screen
     /// 
     /// Draw color 1 is used for the line color
     pub fn line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) {
+        let bounds = geometry::Rect::new(x1.min(x2), y1.min(y2), x1.abs_diff(x2) + 1, y1.abs_diff(y2) + 1);
+        let clip = current_clip();
+        if clip != geometry::Rect::SCREEN && !clip.contains_rect(&bounds) {
+            // Lines crossing the clip edge are drawn in software instead
+            self.line_masked(x1, y1, x2, y2, |_| true);
+            return;
+        }
+        dirty::mark(bounds.x, bounds.y, bounds.width, bounds.height);
         unsafe { raw_api::line(x1, y1, x2, y2) }
     }
 
@@ -297,21 +1406,30 @@ impl FrameBuffer {
     /// 
     /// Draw color 1 is used for the line color
     pub fn hline(&mut self, x: i32, y: i32, len: u32) {
-        unsafe { raw_api::hline(x, y, len) }
+        let Some(r) = geometry::Rect::new(x, y, len, 1).intersection(&current_clip()) else {
+            return;
+        };
+        dirty::mark(r.x, r.y, r.width, 1);
+        unsafe { raw_api::hline(r.x, r.y, r.width) }
     }
 
     /// Draw a vertical line onto the screen
     /// 
     /// Draw color 1 is used for the line's color
     pub fn vline(&mut self, x: i32, y: i32, len: u32) {
-        unsafe { raw_api::vline(x, y, len) }
+        let Some(r) = geometry::Rect::new(x, y, 1, len).intersection(&current_clip()) else {
+            return;
+        };
+        dirty::mark(r.x, r.y, 1, r.height);
+        unsafe { raw_api::vline(r.x, r.y, r.height) }
     }
 
     /// Draw an oval onto the screen
     /// 
     /// Draw color 1 is used for the fill color, draw color 2 is used for the 
-    /// outline color
+    /// outline color. Ovals aren't clipped, see [`FrameBuffer::set_clip`]
     pub fn oval(&mut self, x: i32, y: i32, width: u32, height: u32) {
+        dirty::mark(x, y, width, height);
         unsafe { raw_api::oval(x, y, width, height) }
     }
 
@@ -320,37 +1438,600 @@ impl FrameBuffer {
     /// Draw color 1 is used for the fill color, draw color 2 is used for the 
     /// outline color
     pub fn rect(&mut self, x: i32, y: i32, width: u32, height: u32) {
-        unsafe { raw_api::rect(x, y, width, height) }
+        let full = geometry::Rect::new(x, y, width, height);
+        let Some(visible) = full.intersection(&current_clip()) else {
+            return;
+        };
+        dirty::mark(visible.x, visible.y, visible.width, visible.height);
+        if visible == full {
+            unsafe { raw_api::rect(x, y, width, height) }
+            return;
+        }
+        // Clipped, so the fill and outline are drawn separately to keep the
+        // outline off of the clip edges
+        let colors = read_draw_colors();
+        if colors & 0xF != 0 {
+            write_draw_colors(colors & 0xF);
+            unsafe { raw_api::rect(visible.x, visible.y, visible.width, visible.height) }
+        }
+        if (colors >> 4) & 0xF != 0 {
+            write_draw_colors((colors >> 4) & 0xF);
+            self.hline(x, y, width);
+            self.hline(x, full.bottom() - 1, width);
+            self.vline(x, y, height);
+            self.vline(full.right() - 1, y, height);
+        }
+        write_draw_colors(colors);
+    }
+
+    /// Draw a rectangle with its corners rounded off to `radius`
+    /// 
+    /// Draw color 1 is used for the fill color, draw color 2 is used for the
+    /// outline color, like [`FrameBuffer::rect`]. The radius is capped to
+    /// half the shorter side.
+    pub fn round_rect(&mut self, x: i32, y: i32, width: u32, height: u32, radius: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        let colors = read_draw_colors();
+        // Palette indices, or `None` if transparent
+        let palette = |nibble: u16| (nibble & 0b1111).checked_sub(1).map(|c| c as u8 & 0b11);
+        let (fill, outline) = (palette(colors), palette(colors >> 4));
+        let (w, h) = (width as i32, height as i32);
+        let r = (radius as i32).min(w / 2).min(h / 2);
+        // How far in from the side the corner curve is, `row` rows from the
+        // top or bottom
+        let inset = |row: i32| {
+            if row >= r {
+                return 0;
+            }
+            let dy = 2 * (r - row) - 1;
+            r - (collide::isqrt((4 * r * r - dy * dy) as u64) as i32 + 1) / 2
+        };
+        for row in 0..h {
+            let from_edge = row.min(h - 1 - row);
+            let start = inset(from_edge);
+            // The outline runs along to where the row nearer the edge starts,
+            // so it doesn't have gaps where the curve is shallow
+            let outline_len = if from_edge == 0 {
+                w - 2 * start
+            } else {
+                (inset(from_edge - 1) - start).max(1)
+            };
+            let outline_len = outline_len.min(w - 2 * start);
+            let py = y + row;
+            if let Some(color) = outline {
+                self.fill_span(x + start, py, outline_len as u32, color);
+                self.fill_span(x + w - start - outline_len, py, outline_len as u32, color);
+            }
+            let inner = w - 2 * (start + outline_len);
+            if let (Some(color), true) = (fill, inner > 0) {
+                self.fill_span(x + start + outline_len, py, inner as u32, color);
+            }
+        }
+    }
+
+    /// Draw a [`Rect`](geometry::Rect) onto the screen
+    /// 
+    /// See [`FrameBuffer::rect`]
+    pub fn rect_r(&mut self, rect: geometry::Rect) {
+        self.rect(rect.x, rect.y, rect.width, rect.height)
+    }
+
+    /// Draw an oval filling a [`Rect`](geometry::Rect) onto the screen
+    /// 
+    /// See [`FrameBuffer::oval`]
+    pub fn oval_r(&mut self, rect: geometry::Rect) {
+        self.oval(rect.x, rect.y, rect.width, rect.height)
+    }
+
+    /// Draw a line between two points
+    /// 
+    /// See [`FrameBuffer::line`]
+    pub fn line_v(&mut self, a: geometry::Vec2, b: geometry::Vec2) {
+        self.line(a.x, a.y, b.x, b.y)
+    }
+
+    /// Draw a dashed line, with `on_len` pixels drawn then `off_len` pixels
+    /// skipped, starting with a dash at `(x1, y1)`
+    /// 
+    /// Draw color 1 is used for the line color
+    pub fn line_dashed(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, on_len: u32, off_len: u32) {
+        let period = (on_len + off_len).max(1);
+        self.line_masked(x1, y1, x2, y2, |i| i % period < on_len);
+    }
+
+    /// Draw a stippled line, with the pixels drawn following the bits of
+    /// `pattern`, lowest bit first, repeated every 16 pixels
+    /// 
+    /// Rotating the pattern each frame makes the line crawl along, for
+    /// selection boxes. Draw color 1 is used for the line color.
+    pub fn line_pattern(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, pattern: u16) {
+        self.line_masked(x1, y1, x2, y2, |i| pattern & (1 << (i % 16)) != 0);
+    }
+
+    // Step along the line in software, drawing the pixels `draw` picks by
+    // how far along they are
+    fn line_masked(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, mut draw: impl FnMut(u32) -> bool) {
+        let color = read_draw_colors() as u8 & 0b1111;
+        if color == 0 {
+            return;
+        }
+        let color = (color - 1) & 0b11;
+        let (dx, dy) = ((x2 - x1).abs(), -(y2 - y1).abs());
+        let (sx, sy) = ((x2 - x1).signum(), (y2 - y1).signum());
+        let (mut x, mut y) = (x1, y1);
+        let mut err = dx + dy;
+        for i in 0.. {
+            if draw(i) {
+                self.put(x, y, color);
+            }
+            if x == x2 && y == y2 {
+                break;
+            }
+            let e2 = err * 2;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Draw a line `thickness` pixels wide, with round ends
+    /// 
+    /// Drawn in software, so lines joined end to end don't leave gaps at
+    /// the corners. Draw color 1 is used for the line color.
+    pub fn line_thick(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, thickness: u32) {
+        let color = read_draw_colors() as u8 & 0b1111;
+        if color == 0 || thickness == 0 {
+            return;
+        }
+        // In half pixels, with pixel centers on even numbers. Lines with an
+        // even thickness run along pixel edges instead, to stay symmetric
+        let shift = 1 - (thickness as i64 & 1);
+        let a = (x1 as i64 * 2 + shift, y1 as i64 * 2 + shift);
+        let b = (x2 as i64 * 2 + shift, y2 as i64 * 2 + shift);
+        let radius = thickness as i64;
+        let top = ((a.1.min(b.1) - radius).div_euclid(2)).max(0);
+        let bottom = ((a.1.max(b.1) + radius).div_euclid(2) + 1).min(160);
+        for y in top..bottom {
+            if let Some((start, end)) = capsule_span(a, b, radius, y * 2) {
+                self.fill_span(start as i32, y as i32, (end - start + 1) as u32, (color - 1) & 0b11);
+            }
+        }
+    }
+
+    /// Draw a curve from `p0` to `p2`, bending towards `p1`
+    /// 
+    /// Draw color 1 is used for the line color. See
+    /// [`FrameBuffer::bezier_cubic`]
+    pub fn bezier_quad(&mut self, p0: geometry::Vec2, p1: geometry::Vec2, p2: geometry::Vec2) {
+        // The same curve as a cubic, with its control points 2/3 of the way
+        // to `p1`, in 1/16ths of a pixel
+        let (p0, p1, p2) = (p0 * 16, p1 * 16, p2 * 16);
+        let c1 = p0 + (p1 - p0) * 2 / 3;
+        let c2 = p2 + (p1 - p2) * 2 / 3;
+        self.bezier_split([p0, c1, c2, p2], BEZIER_DEPTH);
+    }
+
+    /// Draw a curve from `p0` to `p3`, leaving `p0` towards `p1` and arriving
+    /// at `p3` from `p2`
+    /// 
+    /// The curve is split into line segments, with more of them where it
+    /// bends more. Draw color 1 is used for the line color.
+    pub fn bezier_cubic(&mut self, p0: geometry::Vec2, p1: geometry::Vec2, p2: geometry::Vec2, p3: geometry::Vec2) {
+        self.bezier_split([p0 * 16, p1 * 16, p2 * 16, p3 * 16], BEZIER_DEPTH);
+    }
+
+    // Draw a cubic curve with points in 1/16ths of a pixel, halving it until
+    // each part is close enough to a straight line
+    fn bezier_split(&mut self, [p0, p1, p2, p3]: [geometry::Vec2; 4], depth: u32) {
+        // How far the control points are from being evenly spaced along the
+        // line between the ends
+        let off = |p: geometry::Vec2, q: geometry::Vec2| (p.x - q.x).abs().max((p.y - q.y).abs());
+        let d1 = off(p1 * 3, p0 * 2 + p3);
+        let d2 = off(p2 * 3, p0 + p3 * 2);
+        // Within half a pixel
+        if depth == 0 || d1.max(d2) <= 8 * 3 {
+            let round = |p: geometry::Vec2| geometry::Vec2::new((p.x + 8) >> 4, (p.y + 8) >> 4);
+            self.line_v(round(p0), round(p3));
+            return;
+        }
+        let mid = |a: geometry::Vec2, b: geometry::Vec2| geometry::Vec2::new((a.x + b.x) >> 1, (a.y + b.y) >> 1);
+        let (a, b, c) = (mid(p0, p1), mid(p1, p2), mid(p2, p3));
+        let (ab, bc) = (mid(a, b), mid(b, c));
+        let center = mid(ab, bc);
+        self.bezier_split([p0, a, ab, center], depth - 1);
+        self.bezier_split([center, bc, c, p3], depth - 1);
     }
 
     /// Draw text to the screen
     /// 
     /// Draw color 1 is used for the text, Draw color 2 is used for the 
-    /// background
+    /// background. Text isn't clipped, see [`FrameBuffer::set_clip`]
     pub fn text(&mut self, s: &str, x: i32, y: i32) {
+        if dirty::is_active() {
+            let (width, height) = text_size(s);
+            dirty::mark(x, y, width, height);
+        }
         unsafe { raw_api::text_utf8(s.as_ptr(), s.len(), x, y) }
     }
 
+    /// Draw text, replacing characters the font doesn't have with the closest
+    /// ones it does
+    /// 
+    /// Text is cut off after 256 bytes. See [`charset`]
+    pub fn text_sanitized(&mut self, s: &str, x: i32, y: i32) {
+        let mut buf = [0; 256];
+        self.text(charset::sanitize(s, &mut buf), x, y);
+    }
+
+    /// Draw text in draw color 1 with a shadow one pixel down and right in
+    /// `shadow`
+    /// 
+    /// Neither has a background, and the draw colors are left as they were
+    pub fn text_shadow(&mut self, s: &str, x: i32, y: i32, shadow: DrawColor) {
+        self.text_layered(s, x, y, shadow, &[(1, 1)]);
+    }
+
+    /// Draw text in draw color 1 with a one pixel outline in `outline`
+    /// 
+    /// Neither has a background, and the draw colors are left as they were
+    pub fn text_outlined(&mut self, s: &str, x: i32, y: i32, outline: DrawColor) {
+        const OFFSETS: [(i32, i32); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+        self.text_layered(s, x, y, outline, &OFFSETS);
+    }
+
+    // Draw the text at each offset in `back`, then normally on top
+    fn text_layered(&mut self, s: &str, x: i32, y: i32, back: DrawColor, offsets: &[(i32, i32)]) {
+        let colors = read_draw_colors();
+        write_draw_colors(back as u16);
+        for (dx, dy) in offsets {
+            self.text(s, x + dx, y + dy);
+        }
+        write_draw_colors(colors & 0xF);
+        self.text(s, x, y);
+        write_draw_colors(colors);
+    }
+
+    /// Draw text with `anchor_x` at its left edge, center, or right edge
+    /// 
+    /// Each line is aligned separately. See [`FrameBuffer::text`]
+    pub fn text_aligned(&mut self, s: &str, anchor_x: i32, y: i32, align: Align) {
+        for (i, line) in s.split('\n').enumerate() {
+            let width = text_size(line).0 as i32;
+            let x = match align {
+                Align::Left => anchor_x,
+                Align::Center => anchor_x - width / 2,
+                Align::Right => anchor_x - width,
+            };
+            self.text(line, x, y + i as i32 * 8);
+        }
+    }
+
+    /// Draw text aligned horizontally within a [`Rect`](geometry::Rect), and
+    /// centered vertically
+    /// 
+    /// Each line is aligned separately. See [`FrameBuffer::text`]
+    pub fn text_in_rect(&mut self, s: &str, rect: geometry::Rect, align: Align) {
+        let height = text_size(s).1 as i32;
+        let y = rect.y + (rect.height as i32 - height) / 2;
+        let anchor_x = match align {
+            Align::Left => rect.x,
+            Align::Center => rect.center().x,
+            Align::Right => rect.right(),
+        };
+        self.text_aligned(s, anchor_x, y, align);
+    }
+
     /// Draw formatted text to the screen
+    /// 
+    /// The text is formatted before being drawn all at once, so it's laid out
+    /// the same as [`FrameBuffer::text`]. Text longer than 256 bytes is cut 
+    /// off with "..."
     pub fn text_fmt(&mut self, args: core::fmt::Arguments<'_>, x: i32, y: i32) {
-        use core::fmt;
+        let mut buf = StrBuf::<256>::new();
+        self.text(buf.format(args), x, y);
+    }
+
+    /// Trace the screen's contents and the palette, so it can be recovered 
+    /// outside of the game
+    /// 
+    /// The first line is a header with the palette colors as `RRGGBB` hex, 
+    /// followed by the 6400 framebuffer bytes as base64 split over lines of 
+    /// 76 characters, and then an end line:
+    /// 
+    /// ```text
+    /// sw4 dump 160x160 2bpp palette e0f8cf 86c06c 306850 071821
+    /// AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA
+    /// ...
+    /// sw4 dump end
+    /// ```
+    /// 
+    /// Each byte holds 4 pixels, with the leftmost in the lowest 2 bits, and 
+    /// each row is 40 bytes.
+    pub fn dump(&self) {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+        let mut header = *b"sw4 dump 160x160 2bpp palette 000000 000000 000000 000000";
+        let palette = unsafe { (0x04 as *const [u8; 16]).read() };
+        for (i, color) in palette.chunks(4).enumerate() {
+            let color = u32::from_le_bytes([color[0], color[1], color[2], color[3]]);
+            let start = 30 + i * 7;
+            for (j, c) in header[start..start + 6].iter_mut().enumerate() {
+                *c = HEX[((color >> ((5 - j) * 4)) & 0xF) as usize];
+            }
+        }
+        // Everything is ASCII
+        trace(unsafe { core::str::from_utf8_unchecked(&header) });
+
+        // 57 bytes make 76 characters of base64
+        for chunk in self.buf.chunks(57) {
+            let mut line = [0u8; 76];
+            let mut len = 0;
+            for group in chunk.chunks(3) {
+                let bits = (group[0] as u32) << 16
+                    | (*group.get(1).unwrap_or(&0) as u32) << 8
+                    | *group.get(2).unwrap_or(&0) as u32;
+                for i in 0..4 {
+                    line[len + i] = if i <= group.len() {
+                        BASE64[((bits >> ((3 - i) * 6)) & 0x3F) as usize]
+                    } else {
+                        b'='
+                    };
+                }
+                len += 4;
+            }
+            trace(unsafe { core::str::from_utf8_unchecked(&line[..len]) });
+        }
+        trace("sw4 dump end");
+    }
+
+}
+
+/// Where text goes relative to the position it's drawn at
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// The width and height in pixels that `s` takes up when drawn with 
+/// [`FrameBuffer::text`]
+pub fn text_size(s: &str) -> (u32, u32) {
+    let mut lines = 0;
+    let mut width = 0;
+    for line in s.split('\n') {
+        lines += 1;
+        width = width.max(line.chars().count() as u32 * 8);
+    }
+    (width, lines * 8)
+}
+
+#[allow(deprecated)]
+static CLIP: SyncUnsafeCell<geometry::Rect> = SyncUnsafeCell::new(geometry::Rect::SCREEN);
 
-        struct TextWriter(i32, i32);
+fn current_clip() -> geometry::Rect {
+    unsafe { *CLIP.get() }
+}
+
+// Shrink a sprite draw to the clip rectangle, giving the new position, size,
+// and source position, or `None` if none of it is visible. Rotated sprites
+// are left as they are
+fn clip_blit(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    src_x: u32,
+    src_y: u32,
+    flags: SpriteFlags,
+) -> Option<(i32, i32, u32, u32, u32, u32)> {
+    let clip = current_clip();
+    if flags.0 & SpriteFlags::ROTATE.0 != 0 || clip.contains_rect(&geometry::Rect::new(x, y, width, height)) {
+        return Some((x, y, width, height, src_x, src_y));
+    }
+    let left = (clip.x - x).max(0) as u32;
+    let top = (clip.y - y).max(0) as u32;
+    let right = (x + width as i32 - clip.right()).max(0) as u32;
+    let bottom = (y + height as i32 - clip.bottom()).max(0) as u32;
+    if left + right >= width || top + bottom >= height {
+        return None;
+    }
+    // A flipped sprite has its source cut from the other side
+    let src_x = src_x + if flags.0 & SpriteFlags::FLIP_X.0 != 0 { right } else { left };
+    let src_y = src_y + if flags.0 & SpriteFlags::FLIP_Y.0 != 0 { bottom } else { top };
+    Some((x + left as i32, y + top as i32, width - left - right, height - top - bottom, src_x, src_y))
+}
+
+// The screen area a sprite covers, which has its sides swapped when rotated
+fn mark_sprite(x: i32, y: i32, width: u32, height: u32, flags: SpriteFlags) {
+    if flags.0 & SpriteFlags::ROTATE.0 != 0 {
+        dirty::mark(x, y, height, width);
+    } else {
+        dirty::mark(x, y, width, height);
+    }
+}
+
+// The pixels of row `y` within `radius` of the segment from `a` to `b`, with
+// everything in half pixels. Returned in whole pixels, inclusive
+fn capsule_span(a: (i64, i64), b: (i64, i64), radius: i64, y: i64) -> Option<(i64, i64)> {
+    // Pixel centers `x` with `lo <= k * x + m <= hi`, in half pixels
+    fn solve(k: i64, m: i64, lo: i64, hi: i64) -> Option<(i64, i64)> {
+        match k.signum() {
+            0 => (lo <= m && m <= hi).then_some((i64::MIN / 4, i64::MAX / 4)),
+            1 => Some(((lo - m).div_euclid(k) + ((lo - m).rem_euclid(k) != 0) as i64, (hi - m).div_euclid(k))),
+            _ => solve(-k, -m, -hi, -lo),
+        }
+    }
+    // Inside a circle at either end
+    let disc = |c: (i64, i64)| {
+        let dy = y - c.1;
+        let left = radius * radius - dy * dy;
+        if left < 0 {
+            return None;
+        }
+        let dx = collide::isqrt(left as u64) as i64;
+        Some((c.0 - dx, c.0 + dx))
+    };
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len_sq = dx * dx + dy * dy;
+    // Inside the rectangle between them: along the segment, and close
+    // enough to either side of it
+    let body = if len_sq == 0 {
+        None
+    } else {
+        let width = collide::isqrt((radius * radius * len_sq) as u64) as i64;
+        let along = solve(dx, dy * (y - a.1) - dx * a.0, 0, len_sq);
+        let across = solve(-dy, dx * (y - a.1) + dy * a.0, -width, width);
+        along.zip(across).map(|(p, q)| (p.0.max(q.0), p.1.min(q.1))).filter(|r| r.0 <= r.1)
+    };
+    // The capsule is convex, so the parts overlap into a single span
+    let (start, end) = [disc(a), disc(b), body]
+        .into_iter()
+        .flatten()
+        .reduce(|p, q| (p.0.min(q.0), p.1.max(q.1)))?;
+    // Back to whole pixels, keeping only the even (centered) half pixels
+    let (start, end) = ((start + 1).div_euclid(2), end.div_euclid(2));
+    (start <= end).then_some((start, end))
+}
+
+// How many times a Bezier curve can be halved, for at most 256 segments
+const BEZIER_DEPTH: u32 = 8;
+
+/// A frame buffer byte with every pixel set to a palette index (0-3), for
+/// filling rows from [`FrameBuffer::row_mut`]
+pub const fn packed(color: u8) -> u8 {
+    (color & 0b11) * 0b0101_0101
+}
+
+// The bits of a frame buffer byte for pixels `start` up to (not including)
+// `end`, both 0-4
+const fn span_bits(start: usize, end: usize) -> u8 {
+    ((0xFFu16 << (start * 2)) & !(0xFFu16 << (end * 2))) as u8
+}
+
+/// Draws single pixels with a cached color, from [`FrameBuffer::brush`]
+pub struct PixelBrush<'a> {
+    fb: &'a mut FrameBuffer,
+    // The palette index, or `None` if draw color 1 is transparent
+    color: Option<u8>,
+    mask: Option<&'static Mask>,
+}
+
+impl PixelBrush<'_> {
+    /// Draw a pixel, if it's on screen and inside the mask
+    pub fn put(&mut self, x: i32, y: i32) {
+        if let Some(color) = self.color {
+            self.fb.put_masked(x, y, color, self.mask);
+        }
+    }
+
+    /// The palette index (0-3) being drawn with, or `None` if draw color 1
+    /// was transparent
+    pub fn color(&self) -> Option<u8> {
+        self.color
+    }
+}
+
+#[allow(deprecated)]
+static ACTIVE_MASK: SyncUnsafeCell<Option<&'static Mask>> = SyncUnsafeCell::new(None);
+
+/// A 1-bit-per-pixel mask covering the whole screen
+/// 
+/// Used with [`FrameBuffer::with_mask`] to restrict drawing to arbitrary
+/// shapes, like spotlights or portrait cutouts.
+#[derive(Clone)]
+pub struct Mask {
+    bits: [u8; (160 * 160) / 8],
+}
 
-        impl fmt::Write for TextWriter {
-            fn write_str(&mut self, s: &str) -> fmt::Result {
-                unsafe { raw_api::text_utf8(s.as_ptr(), s.len(), self.0, self.1) }
-                self.0 += s.len() as i32 * 8;
-                Ok(())
+impl Mask {
+    /// A mask with no pixels set
+    pub const fn new() -> Self {
+        Self { bits: [0; (160 * 160) / 8] }
+    }
+
+    /// A mask with every pixel set
+    pub const fn full() -> Self {
+        Self { bits: [0xFF; (160 * 160) / 8] }
+    }
+
+    /// Is the pixel at (x, y) set?
+    /// 
+    /// Pixels outside of the screen are never set
+    pub fn get(&self, x: i32, y: i32) -> bool {
+        if !(0..160).contains(&x) || !(0..160).contains(&y) {
+            return false;
+        }
+        let idx = (y * 160 + x) as usize;
+        self.bits[idx >> 3] & (1 << (idx & 0b111)) != 0
+    }
+
+    /// Set or clear the pixel at (x, y)
+    pub fn set(&mut self, x: i32, y: i32, value: bool) {
+        if !(0..160).contains(&x) || !(0..160).contains(&y) {
+            return;
+        }
+        let idx = (y * 160 + x) as usize;
+        let bit = 1 << (idx & 0b111);
+        if value {
+            self.bits[idx >> 3] |= bit;
+        } else {
+            self.bits[idx >> 3] &= !bit;
+        }
+    }
+
+    /// Set or clear every pixel
+    pub fn fill(&mut self, value: bool) {
+        self.bits = [if value { 0xFF } else { 0 }; (160 * 160) / 8];
+    }
+
+    /// Flip every pixel
+    pub fn invert(&mut self) {
+        for byte in &mut self.bits {
+            *byte = !*byte;
+        }
+    }
+
+    /// Set or clear every pixel in a rectangle
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: u32, height: u32, value: bool) {
+        for py in y.max(0)..(y + height as i32).min(160) {
+            for px in x.max(0)..(x + width as i32).min(160) {
+                self.set(px, py, value);
             }
         }
+    }
 
-        let _ = TextWriter(x, y).write_fmt(args);
+    /// Set or clear every pixel in a circle centered on (x, y)
+    pub fn fill_circle(&mut self, x: i32, y: i32, radius: u32, value: bool) {
+        let r = radius as i32;
+        for py in (y - r).max(0)..=(y + r).min(159) {
+            for px in (x - r).max(0)..=(x + r).min(159) {
+                let (dx, dy) = (px - x, py - y);
+                if dx * dx + dy * dy <= r * r {
+                    self.set(px, py, value);
+                }
+            }
+        }
     }
+}
 
+impl Default for Mask {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Sprite render flags
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SpriteFlags(u32);
 
 impl SpriteFlags {
@@ -364,6 +2045,11 @@ impl SpriteFlags {
     pub const FLIP_Y: Self = Self(0b0100);
     /// Rotate the sprite 90 degrees counter-clockwise
     pub const ROTATE: Self = Self(0b1000);
+
+    /// Both sets of flags, like `|` but usable in constants
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
 }
 
 impl core::ops::BitOr for SpriteFlags {
@@ -374,21 +2060,112 @@ impl core::ops::BitOr for SpriteFlags {
     }
 }
 
+/// Sprite data along with its size and format
+#[derive(Clone, Copy, Debug)]
+pub struct Sprite<'a> {
+    pub data: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+    pub flags: SpriteFlags,
+}
+
+impl<'a> Sprite<'a> {
+    pub const fn new(data: &'a [u8], width: u32, height: u32, flags: SpriteFlags) -> Self {
+        Self { data, width, height, flags }
+    }
+
+    /// The value of the pixel at `(x, y)`, ignoring the flip and rotate
+    /// flags
+    /// 
+    /// This is 0 or 1 for 1bpp sprites, and 0 to 3 for 2bpp sprites, which
+    /// draw in that draw color plus 1. Traps if it's outside the sprite.
+    pub fn pixel(&self, x: u32, y: u32) -> u8 {
+        assert(x < self.width && y < self.height, "pixel is outside the sprite");
+        let i = (y * self.width + x) as usize;
+        if self.flags.0 & SpriteFlags::TWO_BPP.0 != 0 {
+            (self.data[i / 4] >> (6 - (i % 4) * 2)) & 0b11
+        } else {
+            (self.data[i / 8] >> (7 - i % 8)) & 0b1
+        }
+    }
+}
+
+/// A sprite split into a grid of equally sized tiles, numbered left to right
+/// then top to bottom
+#[derive(Clone, Copy, Debug)]
+pub struct SpriteSheet<'a> {
+    pub sprite: Sprite<'a>,
+    pub tile_width: u32,
+    pub tile_height: u32,
+}
+
+impl<'a> SpriteSheet<'a> {
+    pub const fn new(sprite: Sprite<'a>, tile_width: u32, tile_height: u32) -> Self {
+        Self { sprite, tile_width, tile_height }
+    }
+
+    /// The number of tiles in each row
+    pub const fn columns(&self) -> u32 {
+        self.sprite.width / self.tile_width
+    }
+
+    /// The number of tiles in the sheet
+    pub const fn len(&self) -> u32 {
+        self.columns() * (self.sprite.height / self.tile_height)
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Draw tile `index` with its top left corner at `x`, `y`
+    pub fn draw(&self, fb: &mut FrameBuffer, index: u32, x: i32, y: i32) {
+        assert(index < self.len(), "sprite sheet tile out of range");
+        let columns = self.columns();
+        fb.sub_sprite(
+            self.sprite.data,
+            x,
+            y,
+            self.tile_width,
+            self.tile_height,
+            (index % columns) * self.tile_width,
+            (index / columns) * self.tile_height,
+            self.sprite.width,
+            self.sprite.flags,
+        );
+    }
+}
+
 pub struct SoundSystem {
     _a: (),
 }
 
 impl SoundSystem {
     pub fn play(&self, sound: Sound) {
-        let Sound { start_freq, end_freq, attack, decay, sustain, release, peak_vol, sustain_vol, channel } = sound;
+        let Sound { start_freq, end_freq, attack, decay, sustain, release, peak_vol, sustain_vol, channel, pan } = sound;
         let frequency = (start_freq as u32) | ((end_freq as u32) << 16);
         let duration = u32::from_le_bytes([sustain, release, decay, attack]);
         let volume = u32::from_le_bytes([sustain_vol, peak_vol, 0, 0]);
-        let flags = channel.to_num();
+        let flags = channel.to_num() | ((pan as u32) << 4);
         unsafe { raw_api::tone(frequency, duration, volume, flags) }
     }
+
+    /// Take exclusive use of a channel, if nothing else has it
+    /// 
+    /// See [`audio::ChannelGuard`]
+    pub fn reserve<C: audio::ChannelKind>(&self) -> Option<audio::ChannelGuard<C>> {
+        audio::ChannelGuard::reserve()
+    }
+
+    /// Take exclusive use of a channel, even if something else has it
+    /// 
+    /// See [`audio::ChannelGuard`]
+    pub fn steal<C: audio::ChannelKind>(&self) -> audio::ChannelGuard<C> {
+        audio::ChannelGuard::steal()
+    }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Sound {
     pub start_freq: u16,
     pub end_freq: u16,
@@ -399,9 +2176,175 @@ pub struct Sound {
     pub peak_vol: u8,
     pub sustain_vol: u8,
     pub channel: Channel,
+    pub pan: Pan,
 }
 
-#[derive(Clone, Copy)]
+impl Sound {
+    /// The size of [`Sound::to_bytes`]
+    pub const BYTES: usize = 11;
+
+    /// Pack the sound into bytes, for storing it in asset data or on disk
+    pub const fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let [f0, f1] = self.start_freq.to_le_bytes();
+        let [f2, f3] = self.end_freq.to_le_bytes();
+        let flags = self.channel.to_num() as u8 | ((self.pan as u8) << 4);
+        [
+            f0, f1, f2, f3,
+            self.attack, self.decay, self.sustain, self.release,
+            self.peak_vol, self.sustain_vol, flags,
+        ]
+    }
+
+    /// Unpack a sound from [`Sound::to_bytes`]
+    pub const fn from_bytes(bytes: &[u8; Self::BYTES]) -> Self {
+        let flags = bytes[10];
+        let duty = match (flags >> 2) & 0b11 {
+            0 => DutyCycle::Eighth,
+            1 => DutyCycle::Quarter,
+            2 => DutyCycle::Half,
+            _ => DutyCycle::ThreeQuarters,
+        };
+        let channel = match flags & 0b11 {
+            0 => Channel::Pulse1(duty),
+            1 => Channel::Pulse2(duty),
+            2 => Channel::Triangle,
+            _ => Channel::Noise,
+        };
+        let pan = match (flags >> 4) & 0b11 {
+            1 => Pan::Left,
+            2 => Pan::Right,
+            _ => Pan::Center,
+        };
+        Sound {
+            start_freq: u16::from_le_bytes([bytes[0], bytes[1]]),
+            end_freq: u16::from_le_bytes([bytes[2], bytes[3]]),
+            attack: bytes[4],
+            decay: bytes[5],
+            sustain: bytes[6],
+            release: bytes[7],
+            peak_vol: bytes[8],
+            sustain_vol: bytes[9],
+            channel,
+            pan,
+        }
+    }
+
+    /// Start building a sound
+    /// 
+    /// By default, it's a 440 Hz tone on [`Channel::Pulse1`] with a 50% duty
+    /// cycle, held at full volume for 10 frames
+    pub const fn builder() -> SoundBuilder {
+        SoundBuilder(Sound {
+            start_freq: 440,
+            end_freq: 440,
+            attack: 0,
+            decay: 0,
+            sustain: 10,
+            release: 0,
+            peak_vol: 100,
+            sustain_vol: 100,
+            channel: Channel::Pulse1(DutyCycle::Half),
+            pan: Pan::Center,
+        })
+    }
+}
+
+/// Builds a [`Sound`] one property at a time
+/// 
+/// Created by [`Sound::builder`]
+#[derive(Clone, Copy, Debug)]
+pub struct SoundBuilder(Sound);
+
+impl SoundBuilder {
+    /// Play a constant frequency, in Hz
+    pub const fn freq(mut self, freq: u16) -> Self {
+        self.0.start_freq = freq;
+        self.0.end_freq = freq;
+        self
+    }
+
+    /// Slide from one frequency to another over the sound's duration, in Hz
+    pub const fn freq_slide(mut self, start: u16, end: u16) -> Self {
+        self.0.start_freq = start;
+        self.0.end_freq = end;
+        self
+    }
+
+    /// How many frames it takes to reach the peak volume
+    pub const fn attack_frames(mut self, frames: u8) -> Self {
+        self.0.attack = frames;
+        self
+    }
+
+    /// How many frames it takes to fall from the peak volume to the sustain 
+    /// volume
+    pub const fn decay_frames(mut self, frames: u8) -> Self {
+        self.0.decay = frames;
+        self
+    }
+
+    /// How many frames the sustain volume is held
+    pub const fn sustain_frames(mut self, frames: u8) -> Self {
+        self.0.sustain = frames;
+        self
+    }
+
+    /// How many frames it takes to fade out at the end
+    pub const fn release_frames(mut self, frames: u8) -> Self {
+        self.0.release = frames;
+        self
+    }
+
+    /// Set both the peak and sustain volume, from 0 to 100
+    pub const fn volume(mut self, volume: u8) -> Self {
+        self.0.peak_vol = volume;
+        self.0.sustain_vol = volume;
+        self
+    }
+
+    /// Set the volume reached at the end of the attack, from 0 to 100
+    pub const fn peak_volume(mut self, volume: u8) -> Self {
+        self.0.peak_vol = volume;
+        self
+    }
+
+    /// Set the volume held during the sustain, from 0 to 100
+    pub const fn sustain_volume(mut self, volume: u8) -> Self {
+        self.0.sustain_vol = volume;
+        self
+    }
+
+    pub const fn channel(mut self, channel: Channel) -> Self {
+        self.0.channel = channel;
+        self
+    }
+
+    pub const fn pan(mut self, pan: Pan) -> Self {
+        self.0.pan = pan;
+        self
+    }
+
+    pub const fn build(self) -> Sound {
+        self.0
+    }
+}
+
+impl From<SoundBuilder> for Sound {
+    fn from(builder: SoundBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Which speaker a sound plays from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Pan {
+    #[default]
+    Center = 0,
+    Left = 1,
+    Right = 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Channel {
     Pulse1(DutyCycle),
     Pulse2(DutyCycle),
@@ -420,7 +2363,7 @@ impl Channel {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DutyCycle {
     Eighth = 0,
     Quarter = 1,
@@ -431,19 +2374,247 @@ pub enum DutyCycle {
 pub struct Disk(());
 
 impl Disk {
-    pub fn read(&self, buf: &mut [u8]) {
-        unsafe { raw_api::diskr(buf.as_mut_ptr(), buf.len()) }
+    /// The most bytes that can be stored on disk
+    pub const CAPACITY: usize = 1024;
+
+    /// Read from the start of the disk into `buf`
+    /// 
+    /// Returns the number of bytes read, which is less than `buf.len()` if
+    /// there's not that much data on the disk
+    pub fn read(&self, buf: &mut [u8]) -> usize {
+        unsafe { raw_api::diskr(buf.as_mut_ptr(), buf.len()) as usize }
     }
     
-    pub fn write(&self, buf: &[u8]) {
-        unsafe { raw_api::diskw(buf.as_ptr(), buf.len()) }
+    /// Replace the disk's contents with `buf`
+    /// 
+    /// Returns the number of bytes written, which is less than `buf.len()` if
+    /// it's more than [`Disk::CAPACITY`]
+    pub fn write(&self, buf: &[u8]) -> usize {
+        unsafe { raw_api::diskw(buf.as_ptr(), buf.len()) as usize }
+    }
+
+    /// Fill `buf` from the start of the disk, trapping if there's not enough
+    /// data
+    pub fn read_exact(&self, buf: &mut [u8]) {
+        assert(self.read(buf) == buf.len(), "not enough data on disk");
+    }
+
+    /// Replace the disk's contents with `buf`, trapping if it doesn't all fit
+    pub fn write_all(&self, buf: &[u8]) {
+        assert(self.write(buf) == buf.len(), "data too large for disk");
+    }
+
+    /// Compress `data` and replace the disk's contents with it, so more can
+    /// fit than [`Disk::CAPACITY`]
+    /// 
+    /// Returns `false` if it still didn't fit, in which case the disk is left
+    /// untouched. See [`disk::rle_encode`]
+    pub fn write_compressed(&self, data: &[u8]) -> bool {
+        disk::write_compressed(self, data)
+    }
+
+    /// Read and decompress data written by [`Disk::write_compressed`] into
+    /// `buf`
+    /// 
+    /// Returns the decompressed length, or `None` if there's no valid 
+    /// compressed data or it doesn't fit in `buf`
+    pub fn read_compressed(&self, buf: &mut [u8]) -> Option<usize> {
+        disk::read_compressed(self, buf)
+    }
+
+    /// Replace the disk's contents with a value, in its
+    /// [`disk::Save`] layout
+    pub fn save<T: disk::Save>(&self, value: &T) {
+        assert(T::SIZE <= Self::CAPACITY, "data too large for disk");
+        let mut buf = [0; Self::CAPACITY];
+        value.save(&mut buf[..T::SIZE]);
+        self.write_all(&buf[..T::SIZE]);
+    }
+
+    /// Load a value written by [`Disk::save`], or `None` if there's not
+    /// enough data on the disk or it isn't a valid `T`
+    pub fn load<T: disk::Save>(&self) -> Option<T> {
+        let (image, len) = disk::read_image(self);
+        if len < T::SIZE {
+            return None;
+        }
+        T::try_load(&image)
+    }
+
+    /// Save a value along with its format version and a checksum
+    /// 
+    /// See [`disk::Migrate`]
+    pub fn save_versioned<T: disk::Migrate>(&self, value: &T) {
+        disk::save_versioned(self, value)
+    }
+
+    /// Load a value saved by [`Disk::save_versioned`], upgrading it from an
+    /// older version if needed
+    /// 
+    /// Fails with [`disk::LoadError::Corrupted`] if the checksum doesn't
+    /// match, such as when the disk was written by a different cart.
+    /// See [`disk::Migrate`]
+    pub fn load_versioned<T: disk::Migrate>(&self) -> Result<T, disk::LoadError> {
+        disk::load_versioned(self)
     }
 }
 
+/// A game, as an alternative to the [`start`] and [`update`] attributes
+/// 
+/// Wire it up to wasm4 with [`run_game!`].
+/// 
+/// ```ignore
+/// struct MyGame { x: i32 }
+/// 
+/// impl sw4::Game for MyGame {
+///     fn start(w: &mut Wasm4) -> Self {
+///         MyGame { x: 0 }
+///     }
+/// 
+///     fn update(&mut self, w: &mut Wasm4) {
+///         self.x += 1;
+///     }
+/// }
+/// 
+/// sw4::run_game!(MyGame);
+/// ```
+pub trait Game: 'static {
+    /// Called once, before the first frame
+    fn start(w: &mut Wasm4) -> Self;
+
+    /// Called every frame
+    fn update(&mut self, w: &mut Wasm4);
+}
+
+/// Generate the wasm4 `start` and `update` exports for a [`Game`]
+#[macro_export]
+macro_rules! run_game {
+    ($game:ty) => {
+        #[allow(deprecated)]
+        static SW4_USER_STATE: $crate::SyncUnsafeCell<::core::mem::MaybeUninit<$game>> =
+            $crate::SyncUnsafeCell::new(::core::mem::MaybeUninit::uninit());
+
+        #[no_mangle]
+        pub extern "C" fn start() {
+            unsafe {
+                let state = &mut *(4 as *mut $crate::Wasm4);
+                SW4_USER_STATE
+                    .get()
+                    .cast::<$game>()
+                    .write(<$game as $crate::Game>::start(state));
+                // Set an initialization flag at memory addres 0x0001
+                (1 as *mut u8).write(1)
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn update() {
+            unsafe {
+                // Same as `#[update]`, don't touch the game before `start` ran
+                if *(1 as *mut u8) != 1 {
+                    return;
+                }
+                let state = &mut *(4 as *mut $crate::Wasm4);
+                let game = (&mut *SW4_USER_STATE.get()).assume_init_mut();
+                <$game as $crate::Game>::update(game, state);
+            }
+            $crate::__end_frame();
+        }
+    };
+}
+
+#[allow(deprecated)]
+static FRAME: SyncUnsafeCell<u64> = SyncUnsafeCell::new(0);
+
+/// The number of frames that have finished, so 0 during the first `update`
+/// 
+/// This is counted by the `update` export generated by [`update`] and 
+/// [`run_game!`]
+pub fn frame() -> u64 {
+    unsafe { *FRAME.get() }
+}
+
+#[doc(hidden)]
+pub fn __end_frame() {
+    unsafe { *FRAME.get() += 1 }
+}
+
 pub fn trace(s: &str) {
     unsafe { raw_api::trace_utf8(s.as_ptr(), s.len()) }
 }
 
+/// Trace bytes as a hex dump, with offsets and ASCII
+/// 
+/// ```text
+/// 00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 00 ff 10  |Hello, world!...|
+/// ```
+pub fn trace_hex(bytes: &[u8]) {
+    for (i, row) in bytes.chunks(16).enumerate() {
+        trace_hex_row(i * 16, row);
+    }
+}
+
+/// Trace a region of memory as a hex dump, using addresses as the offsets
+/// 
+/// # Safety
+/// 
+/// The region must be within wasm4's 64KiB of memory, and nothing may be
+/// writing to it during the call
+pub unsafe fn trace_hex_region(addr: usize, len: usize) {
+    assert(addr.saturating_add(len) <= 0x10000, "region outside of memory");
+    // Read byte by byte instead of making a slice, since the region can start
+    // at address 0, right before the registers
+    let mut row = [0; 16];
+    for start in (addr..addr + len).step_by(16) {
+        let row = &mut row[..(addr + len - start).min(16)];
+        for (i, byte) in row.iter_mut().enumerate() {
+            *byte = core::ptr::read_volatile((start + i) as *const u8);
+        }
+        trace_hex_row(start, row);
+    }
+}
+
+// Written by hand rather than with `core::fmt`, to keep it small
+fn trace_hex_row(offset: usize, row: &[u8]) {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let mut line = [b' '; 78];
+    for (i, c) in line[..8].iter_mut().enumerate() {
+        *c = HEX[(offset >> ((7 - i) * 4)) & 0xF];
+    }
+    for (i, &byte) in row.iter().enumerate() {
+        // An extra space between the two halves
+        let pos = 10 + i * 3 + (i >= 8) as usize;
+        line[pos] = HEX[(byte >> 4) as usize];
+        line[pos + 1] = HEX[(byte & 0xF) as usize];
+    }
+    line[60] = b'|';
+    for (i, &byte) in row.iter().enumerate() {
+        line[61 + i] = if byte.is_ascii_graphic() || byte == b' ' { byte } else { b'.' };
+    }
+    let end = 61 + row.len();
+    line[end] = b'|';
+    // Everything is ASCII
+    trace(unsafe { core::str::from_utf8_unchecked(&line[..=end]) });
+}
+
+/// Trace formatted text, in a single message
+/// 
+/// Messages longer than 256 bytes are cut off with "...". See also [`tracef!`]
+pub fn trace_fmt(args: core::fmt::Arguments<'_>) {
+    let mut buf = StrBuf::<256>::new();
+    trace(buf.format(args));
+}
+
+/// Trace formatted text, like `println!`
+/// 
+/// See [`trace_fmt`]
+#[macro_export]
+macro_rules! tracef {
+    ($($arg:tt)*) => {
+        $crate::trace_fmt(::core::format_args!($($arg)*))
+    };
+}
+
 pub fn panic(s: &str) -> ! {
     trace(s);
     core::arch::wasm32::unreachable()
@@ -455,10 +2626,153 @@ pub fn assert(x: bool, s: &str) {
     }
 }
 
+/// Text formatted into a fixed-size buffer, cutting off anything that doesn't
+/// fit
+/// 
+/// [`FrameBuffer::text_fmt`] and [`trace_fmt`] use one of these, and
+/// [`format_buf!`] is the quickest way to fill one.
+/// 
+/// ```ignore
+/// let mut buf = StrBuf::<32>::new();
+/// w.frame_buffer.text(sw4::format_buf!(buf, "HP {}/{}", hp, max_hp), 4, 4);
+/// ```
+#[derive(Clone, Copy)]
+pub struct StrBuf<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    truncated: bool,
+}
+
+impl<const N: usize> StrBuf<N> {
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+            truncated: false,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only whole `str`s, or prefixes of them cut at a char boundary, are
+        // ever written
+        unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+
+    /// The length in bytes
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Whether anything written so far was cut off
+    pub const fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.truncated = false;
+    }
+
+    /// If anything was cut off, replace the end with "..." to show it
+    pub fn ellipsize(&mut self) {
+        if !self.truncated || N < 3 {
+            return;
+        }
+        let mut len = self.len.min(N - 3);
+        while !self.as_str().is_char_boundary(len) {
+            len -= 1;
+        }
+        self.buf[len..len + 3].copy_from_slice(b"...");
+        self.len = len + 3;
+    }
+
+    /// Replace the contents with formatted text, ending in "..." if it was cut
+    /// off
+    /// 
+    /// See [`format_buf!`]
+    pub fn format(&mut self, args: core::fmt::Arguments<'_>) -> &str {
+        self.clear();
+        let _ = self.write_fmt(args);
+        self.ellipsize();
+        self.as_str()
+    }
+}
+
+impl<const N: usize> Default for StrBuf<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> core::ops::Deref for StrBuf<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> core::fmt::Display for StrBuf<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> core::fmt::Debug for StrBuf<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> core::fmt::Write for StrBuf<N> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let mut len = s.len().min(N - self.len);
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.buf[self.len..self.len + len].copy_from_slice(&s.as_bytes()[..len]);
+        self.len += len;
+        self.truncated |= len < s.len();
+        Ok(())
+    }
+}
+
+/// Format text into a [`StrBuf`], returning it as a `&str`
+/// 
+/// Anything already in the buffer is replaced, and text that doesn't fit is
+/// cut off with "...".
+#[macro_export]
+macro_rules! format_buf {
+    ($buf:expr, $($arg:tt)*) => {
+        $crate::StrBuf::format(&mut $buf, ::core::format_args!($($arg)*))
+    };
+}
+
 #[panic_handler]
 #[cfg(all(not(test), feature = "panic_handler"))] // To quiet RA
-fn panic_handler(_info: &core::panic::PanicInfo) -> ! {
-    panic("panicked via core")
+fn panic_handler(info: &core::panic::PanicInfo) -> ! {
+    let mut buf = StrBuf::<256>::new();
+    let _ = match info.location() {
+        Some(location) => write!(
+            buf,
+            "panicked at {}:{}:{}:\n{}",
+            location.file(),
+            location.line(),
+            location.column(),
+            info.message()
+        ),
+        None => write!(buf, "panicked: {}", info.message()),
+    };
+    buf.ellipsize();
+    panic(buf.as_str())
 }
 
 #[doc(hidden)]
@@ -0,0 +1,42 @@
+//! A [`log`] backend that sends messages to [`trace`](crate::trace)
+//! 
+//! To compile logging out of release carts entirely, use `log`'s 
+//! `release_max_level_*` features, like
+//! 
+//! ```toml
+//! log = { version = "0.4", features = ["release_max_level_off"] }
+//! ```
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+struct TraceLogger;
+
+impl Log for TraceLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        crate::tracef!("[{}] {}: {}", record.level(), record.target(), record.args());
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: TraceLogger = TraceLogger;
+
+/// Send every log message to `trace`
+/// 
+/// Does nothing if a logger was already set
+pub fn init() {
+    init_with_level(LevelFilter::Trace);
+}
+
+/// Send log messages at or above `level` to `trace`
+/// 
+/// Does nothing if a logger was already set
+pub fn init_with_level(level: LevelFilter) {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
@@ -0,0 +1,137 @@
+//! Lists of options picked with the gamepad, for title screens and pause 
+//! menus
+
+use crate::{Button, Buttons, FrameBuffer};
+
+/// What happened to a [`Menu`] this frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuEvent<T> {
+    None,
+    /// The cursor moved to a different item
+    Moved,
+    /// An item was chosen with X
+    Selected(T),
+    /// The menu was backed out of with Z
+    Back,
+}
+
+/// A vertical list of items, each with a label and an id
+/// 
+/// The ids can be anything `Copy`, such as an enum of what each item does,
+/// or a `fn` to call.
+/// 
+/// ```ignore
+/// static ITEMS: &[(&str, Item)] = &[("Start", Item::Start), ("Options", Item::Options)];
+/// 
+/// match state.menu.update(state.gamepad.just_pressed_buttons()) {
+///     MenuEvent::Selected(Item::Start) => ...,
+///     ...
+/// }
+/// state.menu.draw(&mut w.frame_buffer, 40, 80);
+/// ```
+pub struct Menu<'a, T> {
+    items: &'a [(&'a str, T)],
+    cursor: usize,
+    wrap: bool,
+    cursor_glyph: &'a str,
+    spacing: i32,
+}
+
+impl<'a, T: Copy> Menu<'a, T> {
+    /// A menu with `>` as its cursor, which wraps around
+    pub const fn new(items: &'a [(&'a str, T)]) -> Self {
+        Self {
+            items,
+            cursor: 0,
+            wrap: true,
+            cursor_glyph: ">",
+            spacing: 10,
+        }
+    }
+
+    /// Whether moving past the last item goes back to the first, and vice
+    /// versa
+    pub const fn wrap(self, wrap: bool) -> Self {
+        Self { wrap, ..self }
+    }
+
+    /// The text drawn to the left of the item the cursor is on
+    pub const fn cursor_glyph(self, cursor_glyph: &'a str) -> Self {
+        Self { cursor_glyph, ..self }
+    }
+
+    /// The distance between the tops of each item
+    pub const fn spacing(self, spacing: i32) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// React to the buttons pressed this frame
+    /// 
+    /// `pressed` should only have buttons on the frame they're pressed, such 
+    /// as from [`GamepadTracker::just_pressed_buttons`](crate::input::GamepadTracker::just_pressed_buttons)
+    /// or [`Repeater::fired_buttons`](crate::input::Repeater::fired_buttons)
+    pub fn update(&mut self, pressed: Buttons) -> MenuEvent<T> {
+        if self.items.is_empty() {
+            return MenuEvent::None;
+        }
+        if pressed.contains(Button::X) {
+            return MenuEvent::Selected(self.items[self.cursor].1);
+        }
+        if pressed.contains(Button::Z) {
+            return MenuEvent::Back;
+        }
+        let last = self.items.len() - 1;
+        let prev = self.cursor;
+        if pressed.contains(Button::Up) {
+            self.cursor = match self.cursor {
+                0 if self.wrap => last,
+                0 => 0,
+                n => n - 1,
+            };
+        }
+        if pressed.contains(Button::Down) {
+            self.cursor = match self.cursor {
+                n if n == last && self.wrap => 0,
+                n if n == last => last,
+                n => n + 1,
+            };
+        }
+        if self.cursor != prev {
+            MenuEvent::Moved
+        } else {
+            MenuEvent::None
+        }
+    }
+
+    /// Draw the items with their top left corner at `(x, y)`
+    /// 
+    /// Uses the current draw colors, see [`FrameBuffer::text`]
+    pub fn draw(&self, fb: &mut FrameBuffer, x: i32, y: i32) {
+        let indent = (self.cursor_glyph.chars().count() as i32 + 1) * 8;
+        for (i, (label, _)) in self.items.iter().enumerate() {
+            let item_y = y + i as i32 * self.spacing;
+            if i == self.cursor {
+                fb.text(self.cursor_glyph, x, item_y);
+            }
+            fb.text(label, x + indent, item_y);
+        }
+    }
+
+    /// The index of the item the cursor is on
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.items.len().saturating_sub(1));
+    }
+
+    /// The id of the item the cursor is on
+    pub fn current(&self) -> Option<T> {
+        self.items.get(self.cursor).map(|(_, id)| *id)
+    }
+
+    pub fn items(&self) -> &'a [(&'a str, T)] {
+        self.items
+    }
+}
@@ -0,0 +1,154 @@
+//! Songs made of patterns of notes, played like a tracker
+//! 
+//! A song has up to four instruments. Each row of a pattern has one [`Step`]
+//! for each instrument, and rows are played one after another, `speed` frames
+//! apart. Write songs with `music!`, and play them with a [`MusicPlayer`].
+
+use crate::notes::Pitch;
+use crate::{Sound, SoundSystem};
+
+// The longest a note is held, in frames
+const MAX_NOTE_FRAMES: u32 = 1020;
+
+/// What one instrument does in a row
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// Carry on with what it was doing
+    Empty,
+    /// Start a note, held until the instrument's next note or [`Step::Off`]
+    Note(Pitch),
+    /// End the current note
+    Off,
+}
+
+/// A block of rows, stored row by row with one [`Step`] for each of the
+/// song's instruments
+#[derive(Clone, Copy, Debug)]
+pub struct Pattern<'a> {
+    pub steps: &'a [Step],
+}
+
+/// A whole song, usually made with `music!`
+#[derive(Clone, Copy, Debug)]
+pub struct Song<'a> {
+    /// How many frames each row lasts
+    pub speed: u8,
+    /// What each column of the patterns plays, with the frequency set to the
+    /// note and the sustain stretched to fill it
+    pub instruments: &'a [Sound],
+    pub patterns: &'a [Pattern<'a>],
+    /// Indices into `patterns`, in the order they're played
+    pub order: &'a [u8],
+    /// The place in `order` to go back to after the end, or `None` to stop
+    pub loop_to: Option<u8>,
+}
+
+impl<'a> Song<'a> {
+    // The steps of a row, or `None` if it's past the end of its pattern
+    fn row(&self, (order, row): (usize, usize)) -> Option<&'a [Step]> {
+        let columns = self.instruments.len();
+        let pattern = self.patterns.get(*self.order.get(order)? as usize)?;
+        pattern.steps.get(row * columns..(row + 1) * columns)
+    }
+
+    // The row after `pos`, following the loop
+    fn advance(&self, (order, row): (usize, usize)) -> Option<(usize, usize)> {
+        if self.row((order, row + 1)).is_some() {
+            Some((order, row + 1))
+        } else if order + 1 < self.order.len() {
+            Some((order + 1, 0))
+        } else {
+            self.loop_to.map(|order| (order as usize, 0))
+        }
+    }
+}
+
+/// Plays a [`Song`], one row at a time
+/// 
+/// Call [`MusicPlayer::tick`] once every frame.
+/// 
+/// ```ignore
+/// static SONG: Song = music! { ... };
+/// 
+/// let mut music = MusicPlayer::new(SONG);
+/// 
+/// music.tick(&w.sounds);
+/// ```
+pub struct MusicPlayer<'a> {
+    song: Song<'a>,
+    // The next row to play, as a place in the order and a row in its pattern
+    pos: Option<(usize, usize)>,
+    // Frames until that row plays
+    timer: u32,
+}
+
+impl<'a> MusicPlayer<'a> {
+    /// Start playing a song from the beginning
+    pub const fn new(song: Song<'a>) -> Self {
+        Self { song, pos: Some((0, 0)), timer: 0 }
+    }
+
+    /// Advance one frame, playing the next row when it's due
+    pub fn tick(&mut self, sounds: &SoundSystem) {
+        if self.timer == 0 {
+            let Some(pos) = self.pos else {
+                return;
+            };
+            if let Some(steps) = self.song.row(pos) {
+                for (column, (step, instrument)) in steps.iter().zip(self.song.instruments).enumerate() {
+                    if let Step::Note(pitch) = *step {
+                        sounds.play(self.note(pos, column, pitch, *instrument));
+                    }
+                }
+            }
+            self.pos = self.song.row(pos).and(self.song.advance(pos));
+            self.timer = self.song.speed.max(1) as u32;
+        }
+        self.timer -= 1;
+    }
+
+    // The sound for a note starting at `pos`, lasting until the column's next
+    // step
+    fn note(&self, pos: (usize, usize), column: usize, pitch: Pitch, mut sound: Sound) -> Sound {
+        let speed = self.song.speed.max(1) as u32;
+        let mut frames = speed;
+        let mut next = self.song.advance(pos);
+        while let Some(step) = next.and_then(|pos| self.song.row(pos)).and_then(|row| row.get(column)) {
+            if *step != Step::Empty || frames >= MAX_NOTE_FRAMES {
+                break;
+            }
+            frames += speed;
+            next = next.and_then(|pos| self.song.advance(pos));
+        }
+        let envelope = sound.attack as u32 + sound.decay as u32 + sound.release as u32;
+        sound.start_freq = pitch.freq();
+        sound.end_freq = sound.start_freq;
+        sound.sustain = frames.saturating_sub(envelope).min(255) as u8;
+        sound
+    }
+
+    /// Is there anything left to play?
+    /// 
+    /// Always true for a song that loops, until [`MusicPlayer::stop`]
+    pub fn is_playing(&self) -> bool {
+        self.pos.is_some() || self.timer > 0
+    }
+
+    /// Stop playing, letting any notes already started finish
+    pub fn stop(&mut self) {
+        self.pos = None;
+        self.timer = 0;
+    }
+
+    /// Play the song again from the beginning
+    pub fn restart(&mut self) {
+        self.pos = Some((0, 0));
+        self.timer = 0;
+    }
+
+    /// The place in the song's order and the row in its pattern that plays
+    /// next, or `None` if it's finished
+    pub fn position(&self) -> Option<(usize, usize)> {
+        self.pos
+    }
+}
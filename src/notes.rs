@@ -0,0 +1,129 @@
+//! Musical notes, and their frequencies for [`Sound`](crate::Sound)
+//! 
+//! Frequencies use equal temperament, tuned to A4 = 440 Hz.
+
+/// A note in an octave
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Note {
+    C = 0,
+    CSharp = 1,
+    D = 2,
+    DSharp = 3,
+    E = 4,
+    F = 5,
+    FSharp = 6,
+    G = 7,
+    GSharp = 8,
+    A = 9,
+    ASharp = 10,
+    B = 11,
+}
+
+impl Note {
+    const ALL: [Note; 12] = [
+        Note::C,
+        Note::CSharp,
+        Note::D,
+        Note::DSharp,
+        Note::E,
+        Note::F,
+        Note::FSharp,
+        Note::G,
+        Note::GSharp,
+        Note::A,
+        Note::ASharp,
+        Note::B,
+    ];
+
+    /// This note in the given octave, where middle C is in octave 4
+    pub const fn octave(self, octave: i8) -> Pitch {
+        Pitch::new(self, octave)
+    }
+}
+
+/// A specific pitch, stored as a MIDI note number
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pitch(u8);
+
+impl Pitch {
+    /// Middle C
+    pub const MIDDLE_C: Self = Self(60);
+    /// The A above middle C, at 440 Hz
+    pub const A4: Self = Self(69);
+
+    /// A note in an octave, where middle C is in octave 4
+    /// 
+    /// Pitches outside of the MIDI range are clamped to it
+    pub const fn new(note: Note, octave: i8) -> Self {
+        Self::from_i32((octave as i32 + 1) * 12 + note as i32)
+    }
+
+    /// Create from a MIDI note number, where 60 is middle C
+    /// 
+    /// Numbers above 127 are clamped
+    pub const fn from_midi(midi: u8) -> Self {
+        Self(if midi > 127 { 127 } else { midi })
+    }
+
+    /// The MIDI note number, where 60 is middle C
+    pub const fn midi(self) -> u8 {
+        self.0
+    }
+
+    pub const fn note(self) -> Note {
+        Note::ALL[(self.0 % 12) as usize]
+    }
+
+    /// The octave, where middle C is in octave 4
+    pub const fn octave(self) -> i8 {
+        (self.0 / 12) as i8 - 1
+    }
+
+    /// Shift by some number of semitones, clamping to the MIDI range
+    pub const fn transpose(self, semitones: i32) -> Self {
+        Self::from_i32(self.0 as i32 + semitones)
+    }
+
+    /// Shift by some number of octaves, clamping to the MIDI range
+    pub const fn shift_octaves(self, octaves: i32) -> Self {
+        self.transpose(octaves * 12)
+    }
+
+    /// The frequency in Hz, rounded to the nearest integer
+    pub const fn freq(self) -> u16 {
+        midi_to_freq(self.0)
+    }
+
+    const fn from_i32(midi: i32) -> Self {
+        Self(if midi < 0 {
+            0
+        } else if midi > 127 {
+            127
+        } else {
+            midi as u8
+        })
+    }
+}
+
+impl From<Pitch> for u16 {
+    fn from(pitch: Pitch) -> Self {
+        pitch.freq()
+    }
+}
+
+// Frequencies of MIDI notes 132 to 143 in Hz, times 16 for precision. Every
+// lower octave is half of the one above it.
+const TOP_OCTAVE: [u32; 12] = [
+    267905, 283835, 300713, 318594, 337539, 357610, 378874, 401403, 425272, 450560, 477352, 505737,
+];
+
+/// The frequency of a MIDI note number in Hz, rounded to the nearest integer
+/// 
+/// Numbers above 127 are clamped
+pub const fn midi_to_freq(midi: u8) -> u16 {
+    let midi = if midi > 127 { 127 } else { midi };
+    let shift = (11 - midi / 12) as u32 + 4;
+    let scaled = TOP_OCTAVE[(midi % 12) as usize];
+    ((scaled + (1 << (shift - 1))) >> shift) as u16
+}
@@ -0,0 +1,108 @@
+//! Integers turned into text without `core::fmt`, which adds a lot to a cart's
+//! size
+//! 
+//! ```ignore
+//! let mut buf = [0; numfmt::MAX_LEN];
+//! w.frame_buffer.text(numfmt::fmt_u32(state.score, &mut buf), 4, 4);
+//! 
+//! const SCORE: IntFormat = IntFormat::PLAIN.zero_pad(6).separator(b',');
+//! let score = SCORE.fmt_u32(state.score, &mut buf); // "001,234"
+//! ```
+
+/// Enough bytes for any `u32` or `i32`, with separators but without padding
+pub const MAX_LEN: usize = 14;
+
+/// How integers are written, for padding them or grouping their digits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntFormat {
+    min_digits: u8,
+    separator: Option<u8>,
+}
+
+impl IntFormat {
+    /// Just the digits, and a `-` for negative numbers
+    pub const PLAIN: Self = Self {
+        min_digits: 1,
+        separator: None,
+    };
+
+    /// Pad numbers with leading zeros up to `digits` long
+    pub const fn zero_pad(self, digits: u8) -> Self {
+        Self { min_digits: digits, ..self }
+    }
+
+    /// Put this character between every group of three digits, like `b','`
+    pub const fn separator(self, separator: u8) -> Self {
+        assert!(separator.is_ascii(), "number separators must be ASCII");
+        Self {
+            separator: Some(separator),
+            ..self
+        }
+    }
+
+    /// Write `value` at the start of `buf`, returning what was written
+    /// 
+    /// Traps if `buf` is too short, which [`MAX_LEN`] bytes never are without
+    /// padding.
+    pub fn fmt_u32<'a>(&self, value: u32, buf: &'a mut [u8]) -> &'a str {
+        self.write(value, false, buf)
+    }
+
+    /// Write `value` at the start of `buf`, returning what was written
+    /// 
+    /// Traps if `buf` is too short, which [`MAX_LEN`] bytes never are without
+    /// padding.
+    pub fn fmt_i32<'a>(&self, value: i32, buf: &'a mut [u8]) -> &'a str {
+        self.write(value.unsigned_abs(), value < 0, buf)
+    }
+
+    fn write<'a>(&self, mut value: u32, negative: bool, buf: &'a mut [u8]) -> &'a str {
+        // Written from the end backwards, then moved to the start
+        let mut start = buf.len();
+        let mut push = |byte: u8| {
+            crate::assert(start > 0, "buffer too short for number");
+            start -= 1;
+            buf[start] = byte;
+        };
+        let mut digits = 0;
+        while value != 0 || digits < self.min_digits.max(1) {
+            if digits > 0 && digits.is_multiple_of(3) {
+                if let Some(separator) = self.separator {
+                    push(separator);
+                }
+            }
+            push(b'0' + (value % 10) as u8);
+            digits += 1;
+            value /= 10;
+        }
+        if negative {
+            push(b'-');
+        }
+        let len = buf.len() - start;
+        buf.copy_within(start.., 0);
+        // Only ASCII is ever written
+        unsafe { core::str::from_utf8_unchecked(&buf[..len]) }
+    }
+}
+
+impl Default for IntFormat {
+    fn default() -> Self {
+        Self::PLAIN
+    }
+}
+
+/// Write `value` at the start of `buf`, returning what was written
+/// 
+/// Traps if `buf` is shorter than the number, which [`MAX_LEN`] bytes never
+/// are. See [`IntFormat`] for padding and separators.
+pub fn fmt_u32(value: u32, buf: &mut [u8]) -> &str {
+    IntFormat::PLAIN.fmt_u32(value, buf)
+}
+
+/// Write `value` at the start of `buf`, returning what was written
+/// 
+/// Traps if `buf` is shorter than the number, which [`MAX_LEN`] bytes never
+/// are. See [`IntFormat`] for padding and separators.
+pub fn fmt_i32(value: i32, buf: &mut [u8]) -> &str {
+    IntFormat::PLAIN.fmt_i32(value, buf)
+}
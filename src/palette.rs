@@ -0,0 +1,120 @@
+//! Animating the palette
+
+use crate::{Color, Palette};
+
+enum Mode<'a> {
+    // Palette indices `first..first + len`
+    Rotate { first: u8, len: u8, forward: bool },
+    Keyframes(&'a [[Color; 4]]),
+    Entry { index: u8, colors: &'a [Color] },
+}
+
+/// Changes palette colors on a schedule, for effects like shimmering water,
+/// glowing lava, or blinking pickups
+/// 
+/// Everything drawn in an animated color changes at once, without redrawing
+/// anything. Call [`PaletteCycler::update`] once every frame.
+/// 
+/// ```ignore
+/// static BLINK: [Color; 2] = [Color::from_u32(0xFFFF00), Color::from_u32(0xFF8000)];
+/// 
+/// let mut water = PaletteCycler::rotate(1, 3, 8);
+/// let mut pickup = PaletteCycler::entry(3, &BLINK, 15);
+/// 
+/// water.update(&mut w.palette);
+/// pickup.update(&mut w.palette);
+/// ```
+pub struct PaletteCycler<'a> {
+    mode: Mode<'a>,
+    frames_per_step: u32,
+    timer: u32,
+    step: usize,
+}
+
+impl<'a> PaletteCycler<'a> {
+    /// Rotate the `len` colors starting at palette index `first` by one place
+    /// every `frames_per_step` frames, so index `first` takes the next one's
+    /// color
+    pub const fn rotate(first: u8, len: u8, frames_per_step: u32) -> Self {
+        assert!(first as u32 + len as u32 <= 4, "palette rotation out of range");
+        Self::with_mode(Mode::Rotate { first, len, forward: true }, frames_per_step)
+    }
+
+    /// Set the whole palette to each of `frames` in turn, one every
+    /// `frames_per_step` frames, looping back to the first
+    pub const fn keyframes(frames: &'a [[Color; 4]], frames_per_step: u32) -> Self {
+        Self::with_mode(Mode::Keyframes(frames), frames_per_step)
+    }
+
+    /// Set one palette index (0-3) to each of `colors` in turn, one every
+    /// `frames_per_step` frames, looping back to the first
+    pub const fn entry(index: u8, colors: &'a [Color], frames_per_step: u32) -> Self {
+        assert!(index < 4, "palette index out of range");
+        Self::with_mode(Mode::Entry { index, colors }, frames_per_step)
+    }
+
+    const fn with_mode(mode: Mode<'a>, frames_per_step: u32) -> Self {
+        Self {
+            mode,
+            frames_per_step: if frames_per_step == 0 { 1 } else { frames_per_step },
+            timer: 0,
+            step: 0,
+        }
+    }
+
+    /// Rotate the other way, so index `first` takes the previous one's color
+    /// 
+    /// Only changes [`PaletteCycler::rotate`]
+    pub const fn reverse(self) -> Self {
+        let mode = match self.mode {
+            Mode::Rotate { first, len, forward } => Mode::Rotate { first, len, forward: !forward },
+            mode => mode,
+        };
+        Self { mode, ..self }
+    }
+
+    /// Advance one frame, changing `palette` when a step is due
+    /// 
+    /// Key frames and entries are written on the first update, a rotation
+    /// first moves after `frames_per_step` frames.
+    pub fn update(&mut self, palette: &mut Palette) {
+        let due = self.timer == 0;
+        self.timer = (self.timer + 1) % self.frames_per_step;
+        match self.mode {
+            Mode::Rotate { first, len, forward } => {
+                if due && self.step > 0 {
+                    let mut colors = palette.get_all();
+                    let range = &mut colors[first as usize..(first + len) as usize];
+                    if forward {
+                        range.rotate_left(1.min(range.len()));
+                    } else {
+                        range.rotate_right(1.min(range.len()));
+                    }
+                    palette.set_all(colors);
+                }
+            }
+            Mode::Keyframes(frames) => {
+                if due && !frames.is_empty() {
+                    palette.set_all(frames[self.step % frames.len()]);
+                }
+            }
+            Mode::Entry { index, colors } => {
+                if due && !colors.is_empty() {
+                    palette.set(index, colors[self.step % colors.len()]);
+                }
+            }
+        }
+        if due {
+            self.step = self.step.wrapping_add(1);
+        }
+    }
+
+    /// Start over from the first step
+    /// 
+    /// The palette isn't changed back, so a rotation carries on from its
+    /// current colors.
+    pub fn reset(&mut self) {
+        self.timer = 0;
+        self.step = 0;
+    }
+}
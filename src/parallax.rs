@@ -0,0 +1,124 @@
+//! Scrolling backgrounds made of layers that move at different speeds
+
+use crate::fixed::Fixed;
+use crate::geometry::Vec2;
+use crate::{read_draw_colors, write_draw_colors, FrameBuffer, Sprite, SpriteSheet};
+
+/// What a [`Layer`] is drawn with
+#[derive(Clone, Copy, Debug)]
+pub enum LayerFill<'a> {
+    /// A sprite, repeated side by side
+    Sprite(Sprite<'a>),
+    /// One tile of a sheet, repeated side by side and `rows` tiles tall
+    Tiles { sheet: SpriteSheet<'a>, tile: u32, rows: u32 },
+}
+
+/// One layer of a [`ParallaxLayers`], repeated across the whole width of the
+/// screen
+#[derive(Clone, Copy, Debug)]
+pub struct Layer<'a> {
+    pub fill: LayerFill<'a>,
+    /// The top of the layer when the camera is at 0
+    pub y: i32,
+    /// How far the layer moves for each pixel the camera moves horizontally,
+    /// so 0 stays still and 1 moves with the foreground
+    pub scroll_x: Fixed,
+    /// Like `scroll_x`, for the camera moving vertically
+    pub scroll_y: Fixed,
+    /// The draw colors it's drawn with, or `None` to use the current ones
+    pub colors: Option<u16>,
+}
+
+impl<'a> Layer<'a> {
+    /// A sprite repeated across the screen, that doesn't scroll vertically
+    pub const fn sprite(sprite: Sprite<'a>, y: i32, scroll_x: Fixed) -> Self {
+        Self::new(LayerFill::Sprite(sprite), y, scroll_x)
+    }
+
+    /// A tile repeated across the screen `rows` tiles tall, that doesn't
+    /// scroll vertically
+    pub const fn tiles(sheet: SpriteSheet<'a>, tile: u32, rows: u32, y: i32, scroll_x: Fixed) -> Self {
+        Self::new(LayerFill::Tiles { sheet, tile, rows }, y, scroll_x)
+    }
+
+    const fn new(fill: LayerFill<'a>, y: i32, scroll_x: Fixed) -> Self {
+        Self {
+            fill,
+            y,
+            scroll_x,
+            scroll_y: Fixed::ZERO,
+            colors: None,
+        }
+    }
+
+    pub const fn scroll_y(self, scroll_y: Fixed) -> Self {
+        Self { scroll_y, ..self }
+    }
+
+    pub const fn colors(self, colors: u16) -> Self {
+        Self { colors: Some(colors), ..self }
+    }
+
+    // The width of the repeating part
+    fn period(&self) -> u32 {
+        match self.fill {
+            LayerFill::Sprite(sprite) => sprite.width,
+            LayerFill::Tiles { sheet, .. } => sheet.tile_width,
+        }
+    }
+
+    /// Draw the layer as seen from `camera`
+    pub fn draw(&self, fb: &mut FrameBuffer, camera: Vec2) {
+        let period = self.period() as i32;
+        if period == 0 {
+            return;
+        }
+        let mut x = -(self.scroll_x * camera.x).to_int().rem_euclid(period);
+        let y = self.y - (self.scroll_y * camera.y).to_int();
+        let old_colors = read_draw_colors();
+        if let Some(colors) = self.colors {
+            write_draw_colors(colors);
+        }
+        while x < 160 {
+            match self.fill {
+                LayerFill::Sprite(sprite) => fb.sprite_at(&sprite, Vec2::new(x, y)),
+                LayerFill::Tiles { sheet, tile, rows } => {
+                    for row in 0..rows as i32 {
+                        sheet.draw(fb, tile, x, y + row * sheet.tile_height as i32);
+                    }
+                }
+            }
+            x += period;
+        }
+        write_draw_colors(old_colors);
+    }
+}
+
+/// Layers drawn back to front, each scrolling at its own speed
+/// 
+/// ```ignore
+/// static BACKGROUND: ParallaxLayers<2> = ParallaxLayers::new([
+///     Layer::sprite(MOUNTAINS, 40, Fixed::from_ratio(1, 4)),
+///     Layer::tiles(TILES, GRASS, 2, 128, Fixed::ONE),
+/// ]);
+/// 
+/// BACKGROUND.draw(&mut w.frame_buffer, state.camera);
+/// ```
+pub struct ParallaxLayers<'a, const N: usize> {
+    pub layers: [Layer<'a>; N],
+}
+
+impl<'a, const N: usize> ParallaxLayers<'a, N> {
+    /// The first layer is the farthest back
+    pub const fn new(layers: [Layer<'a>; N]) -> Self {
+        Self { layers }
+    }
+
+    /// Draw every layer as seen from `camera`, the top left of the view in
+    /// foreground pixels
+    pub fn draw(&self, fb: &mut FrameBuffer, camera: Vec2) {
+        for layer in &self.layers {
+            layer.draw(fb, camera);
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Short-lived particles, for explosions, sparks, and smoke
+
+use crate::fixed::Fixed;
+use crate::geometry::Vec2;
+use crate::rng::Rng;
+use crate::{read_draw_colors, write_draw_colors, FrameBuffer, Sprite};
+
+/// How a particle is drawn
+#[derive(Clone, Copy, Debug)]
+pub enum Shape {
+    /// A single pixel, in draw color 1
+    Pixel,
+    /// A rectangle centered on the particle, see [`FrameBuffer::rect`]
+    Rect { width: u32, height: u32 },
+    /// A sprite centered on the particle
+    Sprite(Sprite<'static>),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Particle {
+    pub x: Fixed,
+    pub y: Fixed,
+    /// Pixels per frame
+    pub vx: Fixed,
+    pub vy: Fixed,
+    /// Frames left before it disappears
+    pub life: u16,
+    pub shape: Shape,
+    /// The draw colors it's drawn with
+    pub colors: u16,
+}
+
+impl Particle {
+    pub fn pos(&self) -> Vec2 {
+        Vec2::new(self.x.round(), self.y.round())
+    }
+}
+
+/// Which way an [`Emitter`] sends particles
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmitterKind {
+    /// `count` particles at once, in every direction
+    Burst { count: u16 },
+    /// `rate` particles each emit, sprayed upwards
+    Fountain { rate: u16 },
+    /// One particle each emit, barely moving, to leave behind something 
+    /// moving
+    Trail,
+}
+
+/// Settings for making particles
+#[derive(Clone, Copy, Debug)]
+pub struct Emitter {
+    pub kind: EmitterKind,
+    pub shape: Shape,
+    pub colors: u16,
+    /// Lifetimes are picked from this range, inclusive
+    pub life: (u16, u16),
+    /// Speeds are picked from this range, inclusive
+    pub speed: (Fixed, Fixed),
+}
+
+impl Emitter {
+    /// Particles flying out in every direction at once
+    pub const fn burst(count: u16) -> Self {
+        Self {
+            kind: EmitterKind::Burst { count },
+            shape: Shape::Pixel,
+            colors: 0x0002,
+            life: (15, 30),
+            speed: (Fixed::HALF, Fixed::from_int(2)),
+        }
+    }
+
+    /// A spray of particles going upwards, meant to be emitted every frame
+    pub const fn fountain(rate: u16) -> Self {
+        Self {
+            kind: EmitterKind::Fountain { rate },
+            shape: Shape::Pixel,
+            colors: 0x0002,
+            life: (20, 40),
+            speed: (Fixed::ONE, Fixed::from_int(2)),
+        }
+    }
+
+    /// Particles left behind something moving, meant to be emitted every 
+    /// frame at its position
+    pub const fn trail() -> Self {
+        Self {
+            kind: EmitterKind::Trail,
+            shape: Shape::Pixel,
+            colors: 0x0002,
+            life: (8, 16),
+            speed: (Fixed::ZERO, Fixed::from_bits(1 << 14)),
+        }
+    }
+
+    pub const fn shape(self, shape: Shape) -> Self {
+        Self { shape, ..self }
+    }
+
+    pub const fn colors(self, colors: u16) -> Self {
+        Self { colors, ..self }
+    }
+
+    pub const fn life(self, min: u16, max: u16) -> Self {
+        Self { life: (min, max), ..self }
+    }
+
+    pub const fn speed(self, min: Fixed, max: Fixed) -> Self {
+        Self { speed: (min, max), ..self }
+    }
+}
+
+// Sines of every 1/16th of a turn, starting pointing right and going
+// clockwise on screen
+const DIRECTIONS: [(Fixed, Fixed); 16] = {
+    const S: [i32; 5] = [0, 25080, 46341, 60547, 65536];
+    let mut out = [(Fixed::ZERO, Fixed::ZERO); 16];
+    let mut i = 0;
+    while i < 16 {
+        let quadrant = i / 4;
+        let step = i % 4;
+        let (x, y) = match quadrant {
+            0 => (S[4 - step], S[step]),
+            1 => (-S[step], S[4 - step]),
+            2 => (-S[4 - step], -S[step]),
+            _ => (S[step], -S[4 - step]),
+        };
+        out[i] = (Fixed::from_bits(x), Fixed::from_bits(y));
+        i += 1;
+    }
+    out
+};
+
+/// Up to `N` particles, which move, fall, and disappear on their own
+pub struct ParticleSystem<const N: usize> {
+    particles: [Option<Particle>; N],
+    /// Added to every particle's vertical speed each frame
+    pub gravity: Fixed,
+}
+
+impl<const N: usize> ParticleSystem<N> {
+    pub const fn new() -> Self {
+        Self {
+            particles: [None; N],
+            gravity: Fixed::ZERO,
+        }
+    }
+
+    pub const fn with_gravity(gravity: Fixed) -> Self {
+        Self {
+            particles: [None; N],
+            gravity,
+        }
+    }
+
+    /// Add a particle, returning `false` if there's no room for it
+    pub fn spawn(&mut self, particle: Particle) -> bool {
+        match self.particles.iter_mut().find(|p| p.is_none()) {
+            Some(slot) => {
+                *slot = Some(particle);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Make particles at `pos` as described by `emitter`
+    /// 
+    /// Particles that don't fit are dropped
+    pub fn emit(&mut self, emitter: &Emitter, pos: Vec2, rng: &mut Rng) {
+        let (count, directions) = match emitter.kind {
+            EmitterKind::Burst { count } => (count, 0..16),
+            // The three directions either side of straight up
+            EmitterKind::Fountain { rate } => (rate, 9..16),
+            EmitterKind::Trail => (1, 0..16),
+        };
+        for _ in 0..count {
+            let (dx, dy) = DIRECTIONS[rng.range(directions.start, directions.end - 1) as usize];
+            let (min, max) = emitter.speed;
+            let speed = min + (max - min) * rng.fixed();
+            let life = rng.range(emitter.life.0 as i32, emitter.life.1 as i32) as u16;
+            let particle = Particle {
+                x: Fixed::from_int(pos.x),
+                y: Fixed::from_int(pos.y),
+                vx: dx * speed,
+                vy: dy * speed,
+                life,
+                shape: emitter.shape,
+                colors: emitter.colors,
+            };
+            if !self.spawn(particle) {
+                break;
+            }
+        }
+    }
+
+    /// Move and age every particle by a frame
+    pub fn update(&mut self) {
+        for slot in &mut self.particles {
+            if let Some(p) = slot {
+                p.life = p.life.saturating_sub(1);
+                if p.life == 0 {
+                    *slot = None;
+                    continue;
+                }
+                p.vy += self.gravity;
+                p.x += p.vx;
+                p.y += p.vy;
+            }
+        }
+    }
+
+    pub fn draw(&self, fb: &mut FrameBuffer) {
+        let prev_colors = read_draw_colors();
+        for p in self.iter() {
+            write_draw_colors(p.colors);
+            let Vec2 { x, y } = p.pos();
+            match p.shape {
+                Shape::Pixel => fb.pixel(x, y),
+                Shape::Rect { width, height } => {
+                    fb.rect(x - (width / 2) as i32, y - (height / 2) as i32, width, height)
+                }
+                Shape::Sprite(sprite) => fb.sprite_at(
+                    &sprite,
+                    Vec2::new(x - (sprite.width / 2) as i32, y - (sprite.height / 2) as i32),
+                ),
+            }
+        }
+        write_draw_colors(prev_colors);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Particle> + '_ {
+        self.particles.iter().flatten()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Particle> + '_ {
+        self.particles.iter_mut().flatten()
+    }
+
+    /// The number of live particles
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.iter().all(|p| p.is_none())
+    }
+
+    pub fn clear(&mut self) {
+        self.particles = [None; N];
+    }
+}
+
+impl<const N: usize> Default for ParticleSystem<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,269 @@
+//! Pathfinding on tile grids
+//! 
+//! Which tiles can be walked on is decided by a callback, so it works with 
+//! any kind of map, and paths are written into a buffer given by the caller.
+
+use crate::geometry::Vec2;
+
+/// Which tiles count as neighbors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbors {
+    /// Up, down, left, and right
+    Four,
+    /// Diagonals as well, without cutting corners past unwalkable tiles
+    Eight,
+}
+
+const STRAIGHT: u16 = 10;
+const DIAGONAL: u16 = 14;
+const NONE: u16 = u16::MAX;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Unseen,
+    Open,
+    Closed,
+}
+
+/// Finds paths on grids of up to `N` tiles
+/// 
+/// All the bookkeeping is stored inline, about 9 bytes per tile, so it's
+/// best kept in a `static` rather than on the stack for large grids.
+pub struct Pathfinder<const N: usize> {
+    neighbors: Neighbors,
+    width: u16,
+    height: u16,
+    // Cost from the start
+    cost: [u16; N],
+    parent: [u16; N],
+    state: [State; N],
+    // A binary heap of open tiles, ordered by estimated total cost
+    heap: [u16; N],
+    heap_len: usize,
+    // Where each tile is in the heap
+    heap_pos: [u16; N],
+}
+
+impl<const N: usize> Pathfinder<N> {
+    pub const fn new(neighbors: Neighbors) -> Self {
+        assert!(N < NONE as usize, "too many tiles");
+        Self {
+            neighbors,
+            width: 0,
+            height: 0,
+            cost: [0; N],
+            parent: [NONE; N],
+            state: [State::Unseen; N],
+            heap: [0; N],
+            heap_len: 0,
+            heap_pos: [0; N],
+        }
+    }
+
+    /// Find the shortest path from `start` to `goal` with A*
+    /// 
+    /// `walkable` is only called with positions inside the `width` by 
+    /// `height` grid. The path is written to `out`, starting with the first 
+    /// step after `start` and ending with `goal`. 
+    /// 
+    /// Returns the number of steps in the path, or `None` if there isn't one.
+    /// If the path is longer than `out`, only the first `out.len()` steps are 
+    /// written.
+    pub fn find(
+        &mut self,
+        width: u32,
+        height: u32,
+        start: Vec2,
+        goal: Vec2,
+        walkable: impl FnMut(i32, i32) -> bool,
+        out: &mut [Vec2],
+    ) -> Option<usize> {
+        let neighbors = self.neighbors;
+        self.search(width, height, start, |pos| pos == goal, |pos| heuristic(neighbors, pos, goal), walkable, out)
+    }
+
+    /// Find the shortest path from `start` to the closest tile `is_goal` 
+    /// returns `true` for, with Dijkstra's algorithm
+    /// 
+    /// Otherwise the same as [`Pathfinder::find`]
+    pub fn find_nearest(
+        &mut self,
+        width: u32,
+        height: u32,
+        start: Vec2,
+        is_goal: impl FnMut(Vec2) -> bool,
+        walkable: impl FnMut(i32, i32) -> bool,
+        out: &mut [Vec2],
+    ) -> Option<usize> {
+        self.search(width, height, start, is_goal, |_| 0, walkable, out)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search(
+        &mut self,
+        width: u32,
+        height: u32,
+        start: Vec2,
+        mut is_goal: impl FnMut(Vec2) -> bool,
+        heuristic: impl Fn(Vec2) -> u16,
+        mut walkable: impl FnMut(i32, i32) -> bool,
+        out: &mut [Vec2],
+    ) -> Option<usize> {
+        crate::assert((width * height) as usize <= N, "grid too big for pathfinder");
+        self.width = width as u16;
+        self.height = height as u16;
+        self.state = [State::Unseen; N];
+        self.heap_len = 0;
+
+        let start_idx = self.index(start)?;
+        self.cost[start_idx] = 0;
+        self.parent[start_idx] = NONE;
+        self.push(start_idx, &heuristic);
+
+        while let Some(idx) = self.pop(&heuristic) {
+            let pos = self.pos(idx);
+            if is_goal(pos) {
+                return Some(self.write_path(idx, out));
+            }
+            self.state[idx] = State::Closed;
+
+            const DIRS: [(i32, i32); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (-1, 1), (1, -1), (-1, -1)];
+            let dirs = match self.neighbors {
+                Neighbors::Four => &DIRS[..4],
+                Neighbors::Eight => &DIRS[..],
+            };
+            for &(dx, dy) in dirs {
+                let next = Vec2::new(pos.x + dx, pos.y + dy);
+                let Some(next_idx) = self.index(next) else { continue };
+                if self.state[next_idx] == State::Closed || !walkable(next.x, next.y) {
+                    continue;
+                }
+                let step = if dx != 0 && dy != 0 {
+                    // No squeezing diagonally between two walls
+                    if !walkable(pos.x + dx, pos.y) || !walkable(pos.x, pos.y + dy) {
+                        continue;
+                    }
+                    DIAGONAL
+                } else {
+                    STRAIGHT
+                };
+                let cost = self.cost[idx].saturating_add(step);
+                match self.state[next_idx] {
+                    State::Unseen => {
+                        self.cost[next_idx] = cost;
+                        self.parent[next_idx] = idx as u16;
+                        self.push(next_idx, &heuristic);
+                    }
+                    State::Open if cost < self.cost[next_idx] => {
+                        self.cost[next_idx] = cost;
+                        self.parent[next_idx] = idx as u16;
+                        self.sift_up(self.heap_pos[next_idx] as usize, &heuristic);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    fn index(&self, pos: Vec2) -> Option<usize> {
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.width as i32 || pos.y >= self.height as i32 {
+            return None;
+        }
+        Some(pos.y as usize * self.width as usize + pos.x as usize)
+    }
+
+    fn pos(&self, idx: usize) -> Vec2 {
+        let width = self.width as usize;
+        Vec2::new((idx % width) as i32, (idx / width) as i32)
+    }
+
+    fn write_path(&self, goal: usize, out: &mut [Vec2]) -> usize {
+        let mut len = 0;
+        let mut idx = goal;
+        while self.parent[idx] != NONE {
+            len += 1;
+            idx = self.parent[idx] as usize;
+        }
+        let mut idx = goal;
+        for step in (0..len).rev() {
+            if let Some(slot) = out.get_mut(step) {
+                *slot = self.pos(idx);
+            }
+            idx = self.parent[idx] as usize;
+        }
+        len
+    }
+
+    fn priority(&self, idx: usize, heuristic: &impl Fn(Vec2) -> u16) -> u32 {
+        self.cost[idx] as u32 + heuristic(self.pos(idx)) as u32
+    }
+
+    fn push(&mut self, idx: usize, heuristic: &impl Fn(Vec2) -> u16) {
+        self.state[idx] = State::Open;
+        self.heap[self.heap_len] = idx as u16;
+        self.heap_pos[idx] = self.heap_len as u16;
+        self.heap_len += 1;
+        self.sift_up(self.heap_len - 1, heuristic);
+    }
+
+    fn pop(&mut self, heuristic: &impl Fn(Vec2) -> u16) -> Option<usize> {
+        if self.heap_len == 0 {
+            return None;
+        }
+        let top = self.heap[0] as usize;
+        self.heap_len -= 1;
+        if self.heap_len > 0 {
+            self.swap(0, self.heap_len);
+            self.sift_down(0, heuristic);
+        }
+        Some(top)
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.heap_pos[self.heap[a] as usize] = a as u16;
+        self.heap_pos[self.heap[b] as usize] = b as u16;
+    }
+
+    fn sift_up(&mut self, mut i: usize, heuristic: &impl Fn(Vec2) -> u16) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.priority(self.heap[i] as usize, heuristic) >= self.priority(self.heap[parent] as usize, heuristic) {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize, heuristic: &impl Fn(Vec2) -> u16) {
+        loop {
+            let mut smallest = i;
+            for child in [i * 2 + 1, i * 2 + 2] {
+                if child < self.heap_len
+                    && self.priority(self.heap[child] as usize, heuristic)
+                        < self.priority(self.heap[smallest] as usize, heuristic)
+                {
+                    smallest = child;
+                }
+            }
+            if smallest == i {
+                break;
+            }
+            self.swap(i, smallest);
+            i = smallest;
+        }
+    }
+}
+
+// Never more than the real cost, so A* still finds the shortest path
+fn heuristic(neighbors: Neighbors, pos: Vec2, goal: Vec2) -> u16 {
+    let dx = pos.x.abs_diff(goal.x);
+    let dy = pos.y.abs_diff(goal.y);
+    let h = match neighbors {
+        Neighbors::Four => (dx + dy) * STRAIGHT as u32,
+        Neighbors::Eight => dx.max(dy) * STRAIGHT as u32 + dx.min(dy) * (DIAGONAL - STRAIGHT) as u32,
+    };
+    h.min(u16::MAX as u32) as u16
+}
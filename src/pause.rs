@@ -0,0 +1,111 @@
+//! Pausing gameplay while a pause menu runs
+
+use crate::audio::AudioScheduler;
+use crate::scenes::{Scene, SceneStack};
+use crate::{SoundSystem, Wasm4};
+
+/// Whether the game is paused, and how long it's been paused for
+/// 
+/// While paused, the gameplay closure given to [`Pause::run`] isn't called,
+/// so anything it updates stays frozen, and [`Pause::game_frame`] stops
+/// counting.
+/// 
+/// ```ignore
+/// state.pause.run(
+///     w,
+///     |w| gameplay(w, &mut state.world),
+///     |w, pause| if pause_menu(w) { pause.resume() },
+/// );
+/// ```
+pub struct Pause {
+    // The frame the game was paused on
+    paused_since: Option<u64>,
+    // Frames spent paused before the current pause
+    paused_frames: u64,
+}
+
+impl Pause {
+    pub const fn new() -> Self {
+        Self {
+            paused_since: None,
+            paused_frames: 0,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_since.is_some()
+    }
+
+    pub fn pause(&mut self) {
+        if self.paused_since.is_none() {
+            self.paused_since = Some(crate::frame());
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if let Some(since) = self.paused_since.take() {
+            self.paused_frames += crate::frame() - since;
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        if self.is_paused() {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    /// Like [`frame`](crate::frame), but not counting frames spent paused
+    pub fn game_frame(&self) -> u64 {
+        let now = crate::frame();
+        let current = self.paused_since.map_or(0, |since| now - since);
+        now - self.paused_frames - current
+    }
+
+    /// Run `gameplay` if not paused, or `menu` if paused
+    /// 
+    /// `menu` can call [`Pause::resume`] to go back to the game
+    pub fn run<R>(
+        &mut self,
+        w: &mut Wasm4,
+        gameplay: impl FnOnce(&mut Wasm4) -> R,
+        menu: impl FnOnce(&mut Wasm4, &mut Self),
+    ) -> Option<R> {
+        if self.is_paused() {
+            menu(w, self);
+            None
+        } else {
+            Some(gameplay(w))
+        }
+    }
+
+    /// Update a scene stack if not paused, or just draw it then run `menu` if 
+    /// paused
+    pub fn run_scenes<S: Scene, const N: usize>(
+        &mut self,
+        w: &mut Wasm4,
+        scenes: &mut SceneStack<S, N>,
+        menu: impl FnOnce(&mut Wasm4, &mut Self),
+    ) {
+        if self.is_paused() {
+            scenes.draw(w);
+            menu(w, self);
+        } else {
+            scenes.update(w);
+        }
+    }
+
+    /// Tick an audio scheduler, unless paused
+    pub fn tick_audio<const N: usize>(&self, scheduler: &mut AudioScheduler<N>, sounds: &SoundSystem) {
+        if !self.is_paused() {
+            scheduler.tick(sounds);
+        }
+    }
+}
+
+impl Default for Pause {
+    fn default() -> Self {
+        Self::new()
+    }
+}
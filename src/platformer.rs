@@ -0,0 +1,180 @@
+//! Moving bodies through a tile map, for platformers
+//! 
+//! Collisions are found with a callback taking tile coordinates, returning
+//! whether that tile is solid, so it works with any kind of map.
+
+use crate::fixed::Fixed;
+use crate::geometry::{Rect, Vec2};
+
+/// Which sides of a [`Body`] hit something while moving
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Contacts {
+    /// Landed on something below
+    pub grounded: bool,
+    /// Bumped into something above
+    pub ceiling: bool,
+    pub wall_left: bool,
+    pub wall_right: bool,
+}
+
+impl Contacts {
+    pub fn wall(&self) -> bool {
+        self.wall_left || self.wall_right
+    }
+}
+
+/// A box with a fixed-point position and velocity, which falls and collides
+/// with solid tiles
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Body {
+    /// The top left corner
+    pub x: Fixed,
+    pub y: Fixed,
+    /// Pixels per frame
+    pub vx: Fixed,
+    pub vy: Fixed,
+    pub width: u32,
+    pub height: u32,
+    /// Added to `vy` every update
+    pub gravity: Fixed,
+    /// The fastest `vy` can get from gravity
+    pub max_fall: Fixed,
+}
+
+impl Body {
+    pub const fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x: Fixed::from_int(x),
+            y: Fixed::from_int(y),
+            vx: Fixed::ZERO,
+            vy: Fixed::ZERO,
+            width,
+            height,
+            gravity: Fixed::from_bits(1 << 14),
+            max_fall: Fixed::from_int(4),
+        }
+    }
+
+    /// The pixel position of the top left corner
+    pub fn pos(&self) -> Vec2 {
+        Vec2::new(self.x.to_int(), self.y.to_int())
+    }
+
+    /// The pixels the body covers
+    pub fn rect(&self) -> Rect {
+        Rect::from_pos_size(self.pos(), self.width, self.height)
+    }
+
+    /// Apply gravity, then move by the velocity
+    /// 
+    /// `solid` takes tile coordinates, with tiles `tile_size` pixels square.
+    /// Velocity going into anything hit is stopped.
+    pub fn update(&mut self, tile_size: u32, solid: impl FnMut(i32, i32) -> bool) -> Contacts {
+        self.vy = (self.vy + self.gravity).min(self.max_fall.max(self.vy));
+        self.move_by(self.vx, self.vy, tile_size, solid)
+    }
+
+    /// Move by `(dx, dy)`, stopping at solid tiles
+    /// 
+    /// Moves horizontally first, then vertically. Unlike [`Body::update`], 
+    /// neither gravity nor the velocity are used, though velocity going into
+    /// anything hit is still stopped.
+    pub fn move_by(
+        &mut self,
+        dx: Fixed,
+        dy: Fixed,
+        tile_size: u32,
+        mut solid: impl FnMut(i32, i32) -> bool,
+    ) -> Contacts {
+        crate::assert(tile_size > 0, "tile size must not be 0");
+        let mut contacts = Contacts::default();
+        if self.move_axis(true, dx, tile_size, &mut solid) {
+            if dx > Fixed::ZERO {
+                contacts.wall_right = true;
+            } else {
+                contacts.wall_left = true;
+            }
+            self.vx = Fixed::ZERO;
+        }
+        if self.move_axis(false, dy, tile_size, &mut solid) {
+            if dy > Fixed::ZERO {
+                contacts.grounded = true;
+            } else {
+                contacts.ceiling = true;
+            }
+            self.vy = Fixed::ZERO;
+        }
+        contacts
+    }
+
+    /// Is there a solid tile directly below?
+    pub fn on_ground(&self, tile_size: u32, mut solid: impl FnMut(i32, i32) -> bool) -> bool {
+        let ts = tile_size as i32;
+        let below = (last_pixel(self.y, self.height) + 1).div_euclid(ts);
+        self.tile_span(true, ts).any(|tx| solid(tx, below))
+    }
+
+    // The range of tiles the body covers across the other axis
+    fn tile_span(&self, along_x: bool, ts: i32) -> core::ops::RangeInclusive<i32> {
+        let (start, len) = if along_x {
+            (self.x, self.width)
+        } else {
+            (self.y, self.height)
+        };
+        start.to_int().div_euclid(ts)..=last_pixel(start, len).div_euclid(ts)
+    }
+
+    // Returns whether something was hit
+    fn move_axis(
+        &mut self,
+        horizontal: bool,
+        mut amount: Fixed,
+        tile_size: u32,
+        solid: &mut impl FnMut(i32, i32) -> bool,
+    ) -> bool {
+        let ts = tile_size as i32;
+        let size = if horizontal { self.width } else { self.height } as i32;
+        // Steps of at most a tile, so nothing can be skipped over
+        let max_step = Fixed::from_int(ts);
+        while amount != Fixed::ZERO {
+            let step = amount.clamp(-max_step, max_step);
+            amount -= step;
+            let pos = if horizontal { self.x } else { self.y };
+            let moved = pos + step;
+            let (edge, snap) = if step > Fixed::ZERO {
+                let tile = last_pixel(moved, size as u32).div_euclid(ts);
+                (tile, tile * ts - size)
+            } else {
+                let tile = moved.to_int().div_euclid(ts);
+                (tile, (tile + 1) * ts)
+            };
+            let hit = self.tile_span(!horizontal, ts).any(|across| {
+                if horizontal {
+                    solid(edge, across)
+                } else {
+                    solid(across, edge)
+                }
+            });
+            let new = if hit {
+                Fixed::from_int(snap)
+            } else {
+                moved
+            };
+            if horizontal {
+                self.x = new;
+            } else {
+                self.y = new;
+            }
+            if hit {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+// The last pixel touched by something starting at `start` and `len` long,
+// including any it only partly covers
+fn last_pixel(start: Fixed, len: u32) -> i32 {
+    (start + Fixed::from_int(len.max(1) as i32) - Fixed::EPSILON).to_int()
+}
@@ -0,0 +1,318 @@
+//! First person views of a grid map, drawn one column at a time like
+//! Wolfenstein 3D
+//! 
+//! Maps are given as a callback from tile coordinates to a tile, where 0 is
+//! empty and anything else is a wall. Positions are in tiles, so `(2.5, 3.5)`
+//! is the middle of tile `(2, 3)`.
+
+use crate::fixed::Fixed;
+use crate::geometry::Rect;
+use crate::{read_draw_colors, write_draw_colors, FrameBuffer, Sprite};
+
+const ONE: i64 = 1 << Fixed::FRAC_BITS;
+// Stands in for an endless distance, small enough to add to without
+// overflowing
+const FAR: i64 = 1 << 40;
+
+/// Where a view is seen from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Camera {
+    pub x: Fixed,
+    pub y: Fixed,
+    /// The direction it faces in turns, where 0 faces along `x` and 1/4
+    /// faces along `y`
+    pub angle: Fixed,
+    /// Half the width of the view one tile in front of the camera
+    pub fov: Fixed,
+}
+
+impl Camera {
+    /// About 66 degrees across
+    pub const DEFAULT_FOV: Fixed = Fixed::from_bits(43254);
+
+    pub const fn new(x: Fixed, y: Fixed, angle: Fixed) -> Self {
+        Self {
+            x,
+            y,
+            angle,
+            fov: Self::DEFAULT_FOV,
+        }
+    }
+
+    /// One tile in the direction the camera is facing
+    pub fn forward(&self) -> (Fixed, Fixed) {
+        (self.angle.cos_turns(), self.angle.sin_turns())
+    }
+
+    /// One tile to the camera's right
+    pub fn right(&self) -> (Fixed, Fixed) {
+        let (dx, dy) = self.forward();
+        (-dy, dx)
+    }
+}
+
+/// Which kind of face a ray hit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    /// A face along the `y` axis, crossed moving along `x`
+    X,
+    /// A face along the `x` axis, crossed moving along `y`
+    Y,
+}
+
+/// Where a ray hit a wall
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hit {
+    pub tile: u8,
+    pub map_x: i32,
+    pub map_y: i32,
+    pub side: Side,
+    /// In multiples of the ray's direction, so in tiles for a direction one
+    /// tile long
+    pub distance: Fixed,
+    /// How far along the face it hit, from 0 to 1, going left to right as
+    /// seen by the ray
+    pub offset: Fixed,
+}
+
+/// How a column of wall is drawn
+#[derive(Clone, Copy, Debug)]
+pub enum Wall<'a> {
+    /// A palette index (0-3)
+    Solid(u8),
+    /// Two palette indices in a checkerboard, for a shade between them
+    Dithered(u8, u8),
+    /// A sprite stretched over the face, with its pixels going through the
+    /// draw colors like [`FrameBuffer::sprite`]
+    Textured(Sprite<'a>),
+}
+
+/// Follow a ray through the map from `(x, y)` until it hits a wall
+/// 
+/// Gives up after crossing `max_steps` tile edges. Useful on its own for line
+/// of sight and hitscan weapons.
+pub fn cast_ray(
+    x: Fixed,
+    y: Fixed,
+    dir_x: Fixed,
+    dir_y: Fixed,
+    max_steps: u32,
+    mut cell: impl FnMut(i32, i32) -> u8,
+) -> Option<Hit> {
+    let (px, py) = (x.to_bits() as i64, y.to_bits() as i64);
+    let (dx, dy) = (dir_x.to_bits() as i64, dir_y.to_bits() as i64);
+    // How far along the ray it is between each x or y edge
+    let delta = |d: i64| if d == 0 { FAR } else { (ONE * ONE / d.abs()).min(FAR) };
+    let (delta_x, delta_y) = (delta(dx), delta(dy));
+    let (mut map_x, mut map_y) = (x.to_int(), y.to_int());
+    let (frac_x, frac_y) = (px & (ONE - 1), py & (ONE - 1));
+    // How far along the ray the next x and y edges are
+    let (step_x, mut side_x) = if dx < 0 {
+        (-1, (frac_x * delta_x) >> Fixed::FRAC_BITS)
+    } else {
+        (1, ((ONE - frac_x) * delta_x) >> Fixed::FRAC_BITS)
+    };
+    let (step_y, mut side_y) = if dy < 0 {
+        (-1, (frac_y * delta_y) >> Fixed::FRAC_BITS)
+    } else {
+        (1, ((ONE - frac_y) * delta_y) >> Fixed::FRAC_BITS)
+    };
+    for _ in 0..max_steps {
+        let side = if side_x < side_y {
+            side_x += delta_x;
+            map_x += step_x;
+            Side::X
+        } else {
+            side_y += delta_y;
+            map_y += step_y;
+            Side::Y
+        };
+        let tile = cell(map_x, map_y);
+        if tile == 0 {
+            continue;
+        }
+        let distance = match side {
+            Side::X => side_x - delta_x,
+            Side::Y => side_y - delta_y,
+        }
+        .clamp(1, i32::MAX as i64);
+        let along = match side {
+            Side::X => py + ((distance * dy) >> Fixed::FRAC_BITS),
+            Side::Y => px + ((distance * dx) >> Fixed::FRAC_BITS),
+        };
+        let mut offset = along & (ONE - 1);
+        if (side == Side::X && dx > 0) || (side == Side::Y && dy < 0) {
+            offset = ONE - 1 - offset;
+        }
+        return Some(Hit {
+            tile,
+            map_x,
+            map_y,
+            side,
+            distance: Fixed::from_bits(distance as i32),
+            offset: Fixed::from_bits(offset as i32),
+        });
+    }
+    None
+}
+
+/// Where a point in the map shows up on screen, from [`Raycaster::project`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Projected {
+    /// The screen column of the point
+    pub x: i32,
+    /// How tall something one tile tall is at that distance, in pixels
+    pub size: u32,
+    /// The distance in front of the camera, to compare with
+    /// [`Raycaster::depth`]
+    pub depth: Fixed,
+}
+
+/// Draws first person views into part of the screen
+/// 
+/// ```ignore
+/// let mut view = Raycaster::new(Rect::SCREEN).ceiling(3).floor(2);
+/// 
+/// view.render(fb, &camera, |x, y| map.get(x, y).unwrap_or(1), |hit| match hit.side {
+///     Side::X => Wall::Solid(0),
+///     Side::Y => Wall::Dithered(0, 1),
+/// });
+/// ```
+pub struct Raycaster {
+    viewport: Rect,
+    ceiling: Option<u8>,
+    floor: Option<u8>,
+    max_steps: u32,
+    // The distance to the wall in each column of the last render
+    depth: [Fixed; 160],
+}
+
+impl Raycaster {
+    /// A view filling `viewport`, which can be at most the whole screen, with
+    /// no ceiling or floor
+    pub const fn new(viewport: Rect) -> Self {
+        assert!(viewport.width <= 160, "viewport is wider than the screen");
+        Self {
+            viewport,
+            ceiling: None,
+            floor: None,
+            max_steps: 64,
+            depth: [Fixed::MAX; 160],
+        }
+    }
+
+    /// Fill the top half of the view behind the walls with a palette index
+    /// (0-3)
+    pub const fn ceiling(self, color: u8) -> Self {
+        Self { ceiling: Some(color), ..self }
+    }
+
+    /// Fill the bottom half of the view behind the walls with a palette index
+    /// (0-3)
+    pub const fn floor(self, color: u8) -> Self {
+        Self { floor: Some(color), ..self }
+    }
+
+    /// The most tile edges a ray crosses before it gives up, 64 by default
+    pub const fn max_steps(self, max_steps: u32) -> Self {
+        Self { max_steps, ..self }
+    }
+
+    pub fn viewport(&self) -> Rect {
+        self.viewport
+    }
+
+    /// Draw the view from `camera`, with `wall` picking how each wall hit is
+    /// drawn
+    /// 
+    /// Solid colors are drawn with [`FrameBuffer::vline`], so like it they
+    /// ignore any [`Mask`](crate::Mask).
+    pub fn render<'a>(
+        &mut self,
+        fb: &mut FrameBuffer,
+        camera: &Camera,
+        mut cell: impl FnMut(i32, i32) -> u8,
+        mut wall: impl FnMut(&Hit) -> Wall<'a>,
+    ) {
+        let Rect { x: left, y: top, width, height } = self.viewport;
+        let (w, h) = (width as i32, height as i32);
+        let bottom = top + h;
+        let horizon = top + h / 2;
+        let (dir_x, dir_y) = camera.forward();
+        let (plane_x, plane_y) = (-dir_y * camera.fov, dir_x * camera.fov);
+        let colors = read_draw_colors();
+        for column in 0..w {
+            let x = left + column;
+            // From -1 at the left edge to 1 at the right, through the middle
+            // of the column
+            let across = Fixed::from_ratio(2 * column + 1 - w, w);
+            let ray_x = dir_x + plane_x * across;
+            let ray_y = dir_y + plane_y * across;
+            let Some(hit) = cast_ray(camera.x, camera.y, ray_x, ray_y, self.max_steps, &mut cell) else {
+                self.depth[column as usize] = Fixed::MAX;
+                fill_column(fb, x, top, horizon, self.ceiling);
+                fill_column(fb, x, horizon, bottom, self.floor);
+                continue;
+            };
+            self.depth[column as usize] = hit.distance;
+            let line = (((h as i64) << Fixed::FRAC_BITS) / hit.distance.to_bits() as i64).min(h as i64 * 64) as i32;
+            let wall_top = horizon - line / 2;
+            let wall_bottom = wall_top + line;
+            fill_column(fb, x, top, wall_top.min(bottom), self.ceiling);
+            fill_column(fb, x, wall_bottom.max(top), bottom, self.floor);
+            let (start, end) = (wall_top.max(top), wall_bottom.min(bottom));
+            match wall(&hit) {
+                Wall::Solid(color) => fill_column(fb, x, start, end, Some(color)),
+                Wall::Dithered(a, b) => fb.put_column(x, start, end, |y| Some(if (x + y) & 1 == 0 { a } else { b })),
+                Wall::Textured(texture) => {
+                    let tex_x = ((hit.offset.to_bits() as i64 * texture.width as i64) >> Fixed::FRAC_BITS) as u32;
+                    fb.put_column(x, start, end, |y| {
+                        let tex_y = ((y - wall_top) as i64 * texture.height as i64 / line as i64) as u32;
+                        let value = texture.pixel(tex_x, tex_y.min(texture.height - 1));
+                        let color = (colors >> (value * 4)) & 0b1111;
+                        (color != 0).then(|| (color as u8 - 1) & 0b11)
+                    });
+                }
+            }
+        }
+        write_draw_colors(colors);
+    }
+
+    /// How far away the wall was in a column of the view in the last render,
+    /// or [`Fixed::MAX`] if there wasn't one
+    /// 
+    /// Columns count from the left of the viewport. Anything farther away
+    /// than this is hidden behind the wall.
+    pub fn depth(&self, column: u32) -> Fixed {
+        self.depth.get(column as usize).copied().unwrap_or(Fixed::MAX)
+    }
+
+    /// Where the point `(x, y)` in the map appears in the view from `camera`,
+    /// for drawing objects in the world
+    /// 
+    /// Returns `None` if it's behind the camera.
+    pub fn project(&self, camera: &Camera, x: Fixed, y: Fixed) -> Option<Projected> {
+        let (dir_x, dir_y) = camera.forward();
+        let (rel_x, rel_y) = (x - camera.x, y - camera.y);
+        let depth = rel_x * dir_x + rel_y * dir_y;
+        if depth <= Fixed::from_ratio(1, 64) {
+            return None;
+        }
+        let lateral = (rel_y * dir_x - rel_x * dir_y) / camera.fov;
+        let half = self.viewport.width as i32 / 2;
+        Some(Projected {
+            x: self.viewport.x + half + (lateral * half / depth).round(),
+            size: (Fixed::from_int(self.viewport.height as i32) / depth).round() as u32,
+            depth,
+        })
+    }
+}
+
+// Fill part of a column with a palette index, going down from `start` and
+// stopping before `end`. Draw color 1 is left set to it
+fn fill_column(fb: &mut FrameBuffer, x: i32, start: i32, end: i32, color: Option<u8>) {
+    if let Some(color) = color.filter(|_| end > start) {
+        write_draw_colors(color as u16 + 1);
+        fb.vline(x, start, (end - start) as u32);
+    }
+}
@@ -0,0 +1,94 @@
+//! Sprites compressed with run-length encoding
+//! 
+//! The pixel data is packed with [`disk::rle_encode`](crate::disk::rle_encode),
+//! which shrinks large areas of one color, like backgrounds, to a fraction of
+//! their size. Make them at compile time with `include_rle_sprite!`, or with
+//! `rle_sprite` in `assets!`.
+
+use crate::{assert, FrameBuffer, Sprite, SpriteFlags};
+
+// How much is decompressed at a time when drawing directly
+const STRIP_BYTES: usize = 256;
+
+/// A sprite with its pixel data compressed
+/// 
+/// Draw it straight to the screen with [`RleSprite::draw`], or decompress it
+/// once with [`RleSprite::decode`] to draw it like any other sprite.
+/// 
+/// ```ignore
+/// const SKY: RleSprite = include_rle_sprite!("art/sky.bin", 160, 96, TWO_BPP);
+/// 
+/// SKY.draw(&mut w.frame_buffer, 0, 0);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct RleSprite<'a> {
+    pub data: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+    pub flags: SpriteFlags,
+}
+
+impl<'a> RleSprite<'a> {
+    pub const fn new(data: &'a [u8], width: u32, height: u32, flags: SpriteFlags) -> Self {
+        Self { data, width, height, flags }
+    }
+
+    const fn row_bits(&self) -> u32 {
+        self.width * ((self.flags.0 & SpriteFlags::TWO_BPP.0) + 1)
+    }
+
+    /// The number of bytes the pixel data takes up once decompressed
+    pub const fn unpacked_len(&self) -> usize {
+        (self.row_bits() * self.height).div_ceil(8) as usize
+    }
+
+    /// Decompress the pixel data into `buf`, which must be at least
+    /// [`RleSprite::unpacked_len`] bytes long
+    pub fn decode<'b>(&self, buf: &'b mut [u8]) -> Sprite<'b> {
+        let len = self.unpacked_len();
+        assert(buf.len() >= len, "buffer too small for RLE sprite");
+        let buf = &mut buf[..len];
+        let unpacked = crate::disk::rle_decode(self.data, buf);
+        assert(unpacked == Some(len), "corrupted RLE sprite");
+        Sprite::new(buf, self.width, self.height, self.flags)
+    }
+
+    /// Draw the sprite with its top left corner at `x`, `y`, decompressing a
+    /// few rows at a time
+    /// 
+    /// Traps if the sprite is rotated, or so wide that the fewest rows ending
+    /// on a byte boundary take more than 256 bytes. Use
+    /// [`RleSprite::decode`] for those.
+    pub fn draw(&self, fb: &mut FrameBuffer, x: i32, y: i32) {
+        assert(
+            self.flags.0 & SpriteFlags::ROTATE.0 == 0,
+            "can't draw a rotated RLE sprite directly, use `decode`",
+        );
+        let row_bits = self.row_bits();
+        if row_bits == 0 || self.height == 0 {
+            return;
+        }
+        // Each strip is drawn as its own sprite, so it has to start on a
+        // byte boundary
+        let group_rows = 8 >> row_bits.trailing_zeros().min(3);
+        let group_bytes = (row_bits * group_rows / 8) as usize;
+        assert(
+            group_bytes <= STRIP_BYTES,
+            "RLE sprite too wide to draw directly, use `decode`",
+        );
+        let strip_rows = (STRIP_BYTES / group_bytes) as u32 * group_rows;
+        let flip_y = self.flags.0 & SpriteFlags::FLIP_Y.0 != 0;
+
+        let mut buf = [0; STRIP_BYTES];
+        let mut unpacker = crate::disk::Unpacker::new(self.data);
+        let mut row = 0;
+        while row < self.height {
+            let rows = strip_rows.min(self.height - row);
+            let bytes = (rows * row_bits).div_ceil(8) as usize;
+            assert(unpacker.fill(&mut buf[..bytes]) == bytes, "corrupted RLE sprite");
+            let offset = if flip_y { self.height - row - rows } else { row };
+            fb.sprite(&buf[..bytes], x, y + offset as i32, self.width, rows, self.flags);
+            row += rows;
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! A small, fast, deterministic random number generator
+//! 
+//! Being deterministic, the same seed gives the same numbers on every client,
+//! so it's safe to use with netplay.
+
+/// A xorshift32 random number generator
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rng(u32);
+
+impl Rng {
+    /// Create a generator from a seed
+    /// 
+    /// Each seed gives a different sequence
+    pub const fn new(seed: u32) -> Self {
+        // xorshift gets stuck at 0, so mix the seed into a nonzero state
+        let state = seed.wrapping_mul(0x9E3779B9) ^ 0x6C8E9CF5;
+        Self(if state == 0 { 1 } else { state })
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+
+    /// A random number in `0..n`
+    /// 
+    /// Returns 0 if `n` is 0
+    pub fn below(&mut self, n: u32) -> u32 {
+        ((self.next_u32() as u64 * n as u64) >> 32) as u32
+    }
+
+    /// A random number in the range `min..=max`
+    pub fn range(&mut self, min: i32, max: i32) -> i32 {
+        if max <= min {
+            return min;
+        }
+        let span = (max as i64 - min as i64 + 1) as u64;
+        min.wrapping_add(((self.next_u32() as u64 * span) >> 32) as i32)
+    }
+
+    /// Returns true with a chance of `numerator` in `denominator`
+    pub fn chance(&mut self, numerator: u32, denominator: u32) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u32() & 1 != 0
+    }
+
+    /// A random fixed-point number in `0..1`
+    pub fn fixed(&mut self) -> crate::fixed::Fixed {
+        crate::fixed::Fixed::from_bits((self.next_u32() >> (32 - crate::fixed::Fixed::FRAC_BITS)) as i32)
+    }
+
+    /// A random item of a slice, or `None` if it's empty
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        items.get(self.below(items.len() as u32) as usize)
+    }
+
+    /// Randomly reorder a slice
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i as u32 + 1) as usize);
+        }
+    }
+}
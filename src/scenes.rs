@@ -0,0 +1,127 @@
+//! Game flow as a stack of scenes, like title screen -> gameplay -> pause menu
+//! 
+//! Without allocation, scenes can't be boxed, so a game's scenes are usually
+//! variants of one enum implementing [`Scene`].
+
+use crate::{assert, Wasm4};
+
+/// What to do with the scene stack after a scene updates
+pub enum Transition<S> {
+    /// Stay on this scene
+    None,
+    /// Put a new scene on top of this one
+    Push(S),
+    /// Leave this scene, going back to the one below it
+    Pop,
+    /// Leave this scene for a new one
+    Replace(S),
+}
+
+/// A screen or mode of the game
+pub trait Scene: Sized {
+    /// Run a frame of this scene's logic
+    fn update(&mut self, w: &mut Wasm4) -> Transition<Self>;
+
+    /// Draw this scene
+    fn draw(&mut self, w: &mut Wasm4);
+
+    /// Called when this scene is added to the stack
+    fn on_enter(&mut self, _w: &mut Wasm4) {}
+
+    /// Called when this scene is removed from the stack
+    fn on_exit(&mut self, _w: &mut Wasm4) {}
+
+    /// Should the scene below this one be drawn first?
+    /// 
+    /// Useful for pause menus drawn over the paused game.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+/// A stack of up to `N` scenes, where only the top one updates
+pub struct SceneStack<S, const N: usize> {
+    scenes: [Option<S>; N],
+    len: usize,
+}
+
+impl<S: Scene, const N: usize> SceneStack<S, N> {
+    /// Create a stack with one scene in it, entering it
+    pub fn new(mut initial: S, w: &mut Wasm4) -> Self {
+        assert(N > 0, "a scene stack needs space for a scene");
+        initial.on_enter(w);
+        let mut scenes = core::array::from_fn(|_| None);
+        scenes[0] = Some(initial);
+        Self { scenes, len: 1 }
+    }
+
+    /// Update the top scene, apply its transition, then draw
+    pub fn update(&mut self, w: &mut Wasm4) {
+        if let Some(top) = self.top_mut() {
+            match top.update(w) {
+                Transition::None => (),
+                Transition::Push(scene) => self.push(scene, w),
+                Transition::Pop => {
+                    self.pop(w);
+                }
+                Transition::Replace(scene) => self.replace(scene, w),
+            }
+        }
+        self.draw(w);
+    }
+
+    /// Draw the top scene, and the scenes under it if it's an overlay
+    pub fn draw(&mut self, w: &mut Wasm4) {
+        // Start from the highest scene that isn't an overlay
+        let bottom = self.scenes[..self.len]
+            .iter()
+            .rposition(|scene| !scene.as_ref().is_some_and(S::is_overlay))
+            .unwrap_or(0);
+        for scene in self.scenes[bottom..self.len].iter_mut().flatten() {
+            scene.draw(w);
+        }
+    }
+
+    /// Put a new scene on top, entering it
+    pub fn push(&mut self, mut scene: S, w: &mut Wasm4) {
+        assert(self.len < N, "scene stack is full");
+        scene.on_enter(w);
+        self.scenes[self.len] = Some(scene);
+        self.len += 1;
+    }
+
+    /// Remove the top scene, exiting it
+    pub fn pop(&mut self, w: &mut Wasm4) -> Option<S> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let mut scene = self.scenes[self.len].take()?;
+        scene.on_exit(w);
+        Some(scene)
+    }
+
+    /// Replace the top scene with a new one, exiting the old one and entering 
+    /// the new one
+    pub fn replace(&mut self, scene: S, w: &mut Wasm4) {
+        self.pop(w);
+        self.push(scene, w);
+    }
+
+    pub fn top(&self) -> Option<&S> {
+        self.scenes[..self.len].last()?.as_ref()
+    }
+
+    pub fn top_mut(&mut self) -> Option<&mut S> {
+        self.scenes[..self.len].last_mut()?.as_mut()
+    }
+
+    /// The number of scenes on the stack
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
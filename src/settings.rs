@@ -0,0 +1,263 @@
+//! Options that players change and expect to be remembered, with a ready-made
+//! options screen
+//! 
+//! ```ignore
+//! const SETTINGS_START: usize = Disk::CAPACITY - Settings::<Action, 2>::DISK_SIZE;
+//! static PALETTES: &[(&str, [Color; 4])] = &[("Classic", CLASSIC), ("Night", NIGHT)];
+//! static ACTIONS: &[(&str, Action)] = &[("Jump", Action::Jump), ("Shoot", Action::Shoot)];
+//! 
+//! let controls = ActionMap::new([Button::X.into(), Button::Z.into()]);
+//! let mut settings = Settings::new(controls, PALETTES.len() as u8);
+//! settings.load(&w.disk, SETTINGS_START);
+//! let mut options = OptionsMenu::new(PALETTES, ACTIONS);
+//! 
+//! // On the options screen
+//! match options.update(&mut settings, state.gamepad.just_pressed_buttons()) {
+//!     MenuEvent::Selected(_) => settings.save(&w.disk, SETTINGS_START),
+//!     MenuEvent::Back => state.screen = Screen::Title,
+//!     _ => (),
+//! }
+//! options.draw(&mut w.frame_buffer, &settings, 8, 8);
+//! w.palette.set_all(PALETTES[settings.palette as usize].1);
+//! ```
+
+use crate::disk::read_image;
+use crate::input::{Action, ActionMap};
+use crate::menu::MenuEvent;
+use crate::{Button, Buttons, Color, Disk, FrameBuffer, Sound};
+
+// Marks the settings as saved, so a blank disk loads the defaults
+const SAVED: u8 = 0x5E;
+
+/// The usual options of a game, with `N` actions the controls can be bound to
+pub struct Settings<A, const N: usize> {
+    /// From 0 to 100
+    pub music_volume: u8,
+    /// From 0 to 100
+    pub sfx_volume: u8,
+    /// Which of the game's palettes is used
+    pub palette: u8,
+    pub controls: ActionMap<A, N>,
+    palette_count: u8,
+}
+
+impl<A: Action, const N: usize> Settings<A, N> {
+    /// The number of bytes used by [`Settings::write_bytes`]
+    pub const BYTES: usize = 3 + ActionMap::<A, N>::BYTES;
+
+    /// The number of bytes used by [`Settings::save`]
+    pub const DISK_SIZE: usize = 1 + Self::BYTES;
+
+    /// Full volume and the first of the game's `palette_count` palettes, with
+    /// the given controls
+    pub const fn new(controls: ActionMap<A, N>, palette_count: u8) -> Self {
+        core::assert!(palette_count > 0, "there must be at least one palette");
+        Self {
+            music_volume: 100,
+            sfx_volume: 100,
+            palette: 0,
+            controls,
+            palette_count,
+        }
+    }
+
+    /// A sound with its volume turned down by [`Settings::music_volume`]
+    pub fn music(&self, sound: Sound) -> Sound {
+        scale_volume(sound, self.music_volume)
+    }
+
+    /// A sound with its volume turned down by [`Settings::sfx_volume`]
+    pub fn sfx(&self, sound: Sound) -> Sound {
+        scale_volume(sound, self.sfx_volume)
+    }
+
+    /// Write the settings to `out`, which must be at least
+    /// [`Settings::BYTES`] long
+    pub fn write_bytes(&self, out: &mut [u8]) {
+        crate::assert(out.len() >= Self::BYTES, "buffer too small for settings");
+        out[0] = self.music_volume;
+        out[1] = self.sfx_volume;
+        out[2] = self.palette;
+        self.controls.write_bytes(&mut out[3..]);
+    }
+
+    /// Load settings written by [`Settings::write_bytes`]
+    /// 
+    /// A palette the game doesn't have, like from a damaged save, goes back to
+    /// the first one.
+    pub fn read_bytes(&mut self, bytes: &[u8]) {
+        crate::assert(bytes.len() >= Self::BYTES, "not enough settings data");
+        self.music_volume = bytes[0].min(100);
+        self.sfx_volume = bytes[1].min(100);
+        self.palette = if bytes[2] < self.palette_count { bytes[2] } else { 0 };
+        self.controls.read_bytes(&bytes[3..]);
+    }
+
+    /// Store the settings on the disk starting `start` bytes in, leaving the
+    /// rest of the disk alone
+    pub fn save(&self, disk: &Disk, start: usize) {
+        let end = start + Self::DISK_SIZE;
+        crate::assert(end <= Disk::CAPACITY, "settings go past the end of the disk");
+        let (mut image, len) = read_image(disk);
+        image[start] = SAVED;
+        self.write_bytes(&mut image[start + 1..end]);
+        disk.write_all(&image[..len.max(end)]);
+    }
+
+    /// Load settings stored by [`Settings::save`], returning `false` and
+    /// leaving them unchanged if there aren't any
+    pub fn load(&mut self, disk: &Disk, start: usize) -> bool {
+        let end = start + Self::DISK_SIZE;
+        crate::assert(end <= Disk::CAPACITY, "settings go past the end of the disk");
+        let (image, _) = read_image(disk);
+        if image[start] != SAVED {
+            return false;
+        }
+        self.read_bytes(&image[start + 1..end]);
+        true
+    }
+}
+
+fn scale_volume(mut sound: Sound, volume: u8) -> Sound {
+    let scale = |v: u8| (v as u32 * volume.min(100) as u32 / 100) as u8;
+    sound.peak_vol = scale(sound.peak_vol);
+    sound.sustain_vol = scale(sound.sustain_vol);
+    sound
+}
+
+/// A changeable line of an [`OptionsMenu`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting {
+    MusicVolume,
+    SfxVolume,
+    Palette,
+    /// The controls of the action at this index of the menu's actions
+    Binding(usize),
+}
+
+/// An options screen for [`Settings`], moved through like a
+/// [`Menu`](crate::menu::Menu)
+/// 
+/// Left and right change volumes, by 10 at a time, and the palette. Selecting
+/// a control with X waits for the next button pressed to bind to it. The
+/// palette line is left out if there's only one palette.
+pub struct OptionsMenu<'a, A> {
+    palettes: &'a [(&'a str, [Color; 4])],
+    actions: &'a [(&'a str, A)],
+    cursor: usize,
+    rebinding: bool,
+}
+
+impl<'a, A: Action> OptionsMenu<'a, A> {
+    pub const fn new(palettes: &'a [(&'a str, [Color; 4])], actions: &'a [(&'a str, A)]) -> Self {
+        Self {
+            palettes,
+            actions,
+            cursor: 0,
+            rebinding: false,
+        }
+    }
+
+    // Every line, with `None` for "Back" at the end
+    fn line(&self, i: usize) -> Option<Setting> {
+        let mut lines = [Setting::MusicVolume, Setting::SfxVolume, Setting::Palette]
+            .into_iter()
+            .filter(|&s| s != Setting::Palette || self.palettes.len() > 1)
+            .chain((0..self.actions.len()).map(Setting::Binding));
+        lines.nth(i)
+    }
+
+    fn len(&self) -> usize {
+        2 + (self.palettes.len() > 1) as usize + self.actions.len() + 1
+    }
+
+    /// Whether a control is waiting for a button to bind to it
+    pub fn is_rebinding(&self) -> bool {
+        self.rebinding
+    }
+
+    /// React to the buttons pressed this frame, which should only have
+    /// buttons on the frame they're pressed
+    /// 
+    /// Gives `Selected` with the setting that was changed, and `Back` when
+    /// backed out of with Z or the "Back" line.
+    pub fn update<const N: usize>(&mut self, settings: &mut Settings<A, N>, pressed: Buttons) -> MenuEvent<Setting> {
+        if self.rebinding {
+            let Some(Setting::Binding(i)) = self.line(self.cursor) else {
+                self.rebinding = false;
+                return MenuEvent::None;
+            };
+            if !settings.controls.capture_binding(0, self.actions[i].1, pressed) {
+                return MenuEvent::None;
+            }
+            self.rebinding = false;
+            return MenuEvent::Selected(Setting::Binding(i));
+        }
+        let line = self.line(self.cursor);
+        if pressed.contains(Button::Z) || (pressed.contains(Button::X) && line.is_none()) {
+            return MenuEvent::Back;
+        }
+        let prev = self.cursor;
+        if pressed.contains(Button::Up) {
+            self.cursor = self.cursor.checked_sub(1).unwrap_or(self.len() - 1);
+        }
+        if pressed.contains(Button::Down) {
+            self.cursor = (self.cursor + 1) % self.len();
+        }
+        if self.cursor != prev {
+            return MenuEvent::Moved;
+        }
+        let step = pressed.contains(Button::Right) as i32 - pressed.contains(Button::Left) as i32;
+        match line {
+            Some(Setting::Binding(_)) if pressed.contains(Button::X) => {
+                self.rebinding = true;
+                MenuEvent::None
+            }
+            Some(setting @ (Setting::MusicVolume | Setting::SfxVolume)) if step != 0 => {
+                let volume = match setting {
+                    Setting::MusicVolume => &mut settings.music_volume,
+                    _ => &mut settings.sfx_volume,
+                };
+                *volume = (*volume as i32 + step * 10).clamp(0, 100) as u8;
+                MenuEvent::Selected(setting)
+            }
+            Some(Setting::Palette) if step != 0 => {
+                let count = self.palettes.len() as i32;
+                settings.palette = (settings.palette as i32 + step).rem_euclid(count) as u8;
+                MenuEvent::Selected(Setting::Palette)
+            }
+            _ => MenuEvent::None,
+        }
+    }
+
+    /// Draw the options with their top left corner at `(x, y)`, in the
+    /// current draw colors
+    pub fn draw<const N: usize>(&self, fb: &mut FrameBuffer, settings: &Settings<A, N>, x: i32, y: i32) {
+        let mut buf = crate::StrBuf::<20>::new();
+        for i in 0..self.len() {
+            let line_y = y + i as i32 * 10;
+            if i == self.cursor {
+                fb.text(">", x, line_y);
+            }
+            let text = match self.line(i) {
+                Some(Setting::MusicVolume) => crate::format_buf!(buf, "Music  {}", settings.music_volume),
+                Some(Setting::SfxVolume) => crate::format_buf!(buf, "Sound  {}", settings.sfx_volume),
+                Some(Setting::Palette) => {
+                    let name = self.palettes.get(settings.palette as usize).map_or("?", |p| p.0);
+                    crate::format_buf!(buf, "Colors {}", name)
+                }
+                Some(Setting::Binding(a)) if self.rebinding && i == self.cursor => {
+                    crate::format_buf!(buf, "{} ...", self.actions[a].0)
+                }
+                Some(Setting::Binding(a)) => {
+                    let (name, action) = self.actions[a];
+                    let button = settings.controls.bindings(0, action).iter().next();
+                    let glyph = button.map_or("-", |b| b.glyph().as_str());
+                    crate::format_buf!(buf, "{} {}", name, glyph)
+                }
+                None => "Back",
+            };
+            fb.text(text, x + 16, line_y);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Ready-made sound effects
+//! 
+//! ```ignore
+//! w.sounds.play(Sfx::Jump.sound());
+//! // A little different every time
+//! w.sounds.play(Sfx::Coin.sound_random(&mut rng));
+//! ```
+
+use crate::rng::Rng;
+use crate::{Channel, DutyCycle, Sound};
+
+/// A classic sound effect
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sfx {
+    Jump,
+    Coin,
+    Laser,
+    Explosion,
+    Hurt,
+    PowerUp,
+}
+
+impl Sfx {
+    /// The sound for this effect
+    pub const fn sound(self) -> Sound {
+        match self {
+            Sfx::Jump => jump(),
+            Sfx::Coin => coin(),
+            Sfx::Laser => laser(),
+            Sfx::Explosion => explosion(),
+            Sfx::Hurt => hurt(),
+            Sfx::PowerUp => power_up(),
+        }
+    }
+
+    /// The sound for this effect, with slightly randomized pitch and length
+    pub fn sound_random(self, rng: &mut Rng) -> Sound {
+        vary(self.sound(), rng)
+    }
+}
+
+/// A quick upwards sweep
+pub const fn jump() -> Sound {
+    Sound::builder()
+        .freq_slide(220, 640)
+        .sustain_frames(6)
+        .release_frames(4)
+        .volume(60)
+        .channel(Channel::Pulse1(DutyCycle::Quarter))
+        .build()
+}
+
+/// A short, bright chime
+pub const fn coin() -> Sound {
+    Sound::builder()
+        .freq_slide(1000, 1600)
+        .decay_frames(2)
+        .sustain_frames(4)
+        .release_frames(10)
+        .peak_volume(80)
+        .sustain_volume(50)
+        .channel(Channel::Pulse1(DutyCycle::Eighth))
+        .build()
+}
+
+/// A fast downwards zap
+pub const fn laser() -> Sound {
+    Sound::builder()
+        .freq_slide(1500, 200)
+        .sustain_frames(10)
+        .release_frames(2)
+        .volume(50)
+        .channel(Channel::Pulse2(DutyCycle::Half))
+        .build()
+}
+
+/// A long burst of falling noise
+pub const fn explosion() -> Sound {
+    Sound::builder()
+        .freq_slide(600, 80)
+        .decay_frames(10)
+        .sustain_frames(10)
+        .release_frames(20)
+        .peak_volume(100)
+        .sustain_volume(60)
+        .channel(Channel::Noise)
+        .build()
+}
+
+/// A harsh low drop
+pub const fn hurt() -> Sound {
+    Sound::builder()
+        .freq_slide(400, 140)
+        .sustain_frames(6)
+        .release_frames(6)
+        .volume(70)
+        .channel(Channel::Pulse2(DutyCycle::Quarter))
+        .build()
+}
+
+/// A long rising sweep
+pub const fn power_up() -> Sound {
+    Sound::builder()
+        .freq_slide(300, 1200)
+        .attack_frames(2)
+        .sustain_frames(30)
+        .release_frames(6)
+        .volume(80)
+        .channel(Channel::Triangle)
+        .build()
+}
+
+/// Randomize the pitch of a sound by up to about 15%, and its length by up to
+/// about 25%
+pub fn vary(mut sound: Sound, rng: &mut Rng) -> Sound {
+    let pitch = rng.range(85, 115) as u32;
+    sound.start_freq = (sound.start_freq as u32 * pitch / 100).min(u16::MAX as u32) as u16;
+    sound.end_freq = (sound.end_freq as u32 * pitch / 100).min(u16::MAX as u32) as u16;
+    let length = rng.range(75, 125) as u32;
+    sound.sustain = (sound.sustain as u32 * length / 100).clamp(1, u8::MAX as u32) as u8;
+    sound
+}
@@ -0,0 +1,89 @@
+//! Screen shake, from an amount of "trauma" that wears off over time
+//! 
+//! Add the shake's offset to the camera position passed to things like
+//! [`ParallaxLayers::draw`](crate::parallax::ParallaxLayers::draw) and
+//! [`TileMap::draw`](crate::tilemap::TileMap::draw), so everything drawn
+//! relative to the camera shakes together.
+
+use crate::fixed::Fixed;
+use crate::geometry::Vec2;
+use crate::rng::Rng;
+
+/// Shakes harder the more trauma it has, calming down as it wears off
+/// 
+/// The shake grows with the square of the trauma, so small hits barely move
+/// the screen and big ones throw it around. Being driven by an [`Rng`], the
+/// same seed shakes the same way on every client.
+/// 
+/// ```ignore
+/// let mut shake = ScreenShake::new(4, 30, 1234);
+/// 
+/// // When something explodes
+/// shake.add_trauma(Fixed::from_ratio(1, 2));
+/// 
+/// // Every frame
+/// shake.tick();
+/// let camera = state.camera + shake.offset();
+/// level.draw(&mut w.frame_buffer, &TILES, 8, -camera);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScreenShake {
+    trauma: Fixed,
+    decay: Fixed,
+    max_offset: i32,
+    offset: Vec2,
+    rng: Rng,
+}
+
+impl ScreenShake {
+    /// Shake up to `max_offset` pixels in each direction, taking
+    /// `recovery_frames` frames to calm down from full trauma
+    pub const fn new(max_offset: i32, recovery_frames: u32, seed: u32) -> Self {
+        let recovery_frames = if recovery_frames == 0 { 1 } else { recovery_frames };
+        Self {
+            trauma: Fixed::ZERO,
+            decay: Fixed::from_ratio(1, recovery_frames as i32),
+            max_offset,
+            offset: Vec2::new(0, 0),
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Add trauma, up to a total of 1
+    pub fn add_trauma(&mut self, amount: Fixed) {
+        self.trauma = self.trauma.saturating_add(amount).clamp(Fixed::ZERO, Fixed::ONE);
+    }
+
+    /// How much trauma is left, from 0 to 1
+    pub fn trauma(&self) -> Fixed {
+        self.trauma
+    }
+
+    pub fn is_shaking(&self) -> bool {
+        self.trauma > Fixed::ZERO
+    }
+
+    /// Stop shaking right away
+    pub fn stop(&mut self) {
+        self.trauma = Fixed::ZERO;
+        self.offset = Vec2::new(0, 0);
+    }
+
+    /// Advance a frame, picking this frame's offset and wearing off some
+    /// trauma
+    pub fn tick(&mut self) {
+        let shake = self.trauma * self.trauma;
+        let mut axis = || {
+            // A random number from -1 to 1
+            let r = self.rng.fixed() * 2 - Fixed::ONE;
+            (r * shake * self.max_offset).round()
+        };
+        self.offset = Vec2::new(axis(), axis());
+        self.trauma = self.trauma.saturating_sub(self.decay).max(Fixed::ZERO);
+    }
+
+    /// How far to move the screen this frame, in pixels
+    pub fn offset(&self) -> Vec2 {
+        self.offset
+    }
+}
@@ -0,0 +1,85 @@
+//! Statics that can be changed safely
+//! 
+//! Most state belongs in the [`Game`](crate::Game), but some has to live
+//! outside it, like a cache shared between modules. Declare it with
+//! [`static_state!`](crate::static_state) and reach it through
+//! [`StaticState::with`].
+
+use core::cell::{Cell, UnsafeCell};
+
+/// A value in a `static` that's borrowed for the length of a closure
+/// 
+/// Only one closure can have it at a time. Trying to borrow it again from
+/// inside one traps, like a `RefCell` would panic.
+/// 
+/// ```ignore
+/// sw4::static_state! {
+///     static HIGH_SCORE: u32 = 0;
+/// }
+/// 
+/// HIGH_SCORE.with(|best| *best = (*best).max(score));
+/// ```
+pub struct StaticState<T> {
+    value: UnsafeCell<T>,
+    borrowed: Cell<bool>,
+}
+
+// wasm4 carts only ever have one thread
+unsafe impl<T: Send> Sync for StaticState<T> {}
+
+impl<T> StaticState<T> {
+    pub const fn new(value: T) -> Self {
+        Self {
+            value: UnsafeCell::new(value),
+            borrowed: Cell::new(false),
+        }
+    }
+
+    /// Run `f` with the value, trapping if it's already borrowed
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        match self.try_with(f) {
+            Some(r) => r,
+            None => crate::panic("static state is already borrowed"),
+        }
+    }
+
+    /// Run `f` with the value, or return `None` if it's already borrowed
+    pub fn try_with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        if self.borrowed.replace(true) {
+            return None;
+        }
+        // Released even if `f` unwinds, though wasm4 carts abort instead
+        struct Release<'a>(&'a Cell<bool>);
+        impl Drop for Release<'_> {
+            fn drop(&mut self) {
+                self.0.set(false)
+            }
+        }
+        let _release = Release(&self.borrowed);
+        // The flag makes this the only reference until `f` returns
+        Some(f(unsafe { &mut *self.value.get() }))
+    }
+
+    /// Whether a closure passed to [`with`](Self::with) is running
+    pub fn is_borrowed(&self) -> bool {
+        self.borrowed.get()
+    }
+}
+
+/// Declare statics wrapped in [`StaticState`](crate::state::StaticState)
+/// 
+/// ```ignore
+/// sw4::static_state! {
+///     static SEEN: [bool; 64] = [false; 64];
+///     pub(crate) static CACHE: Option<u32> = None;
+/// }
+/// ```
+#[macro_export]
+macro_rules! static_state {
+    ($($(#[$meta:meta])* $vis:vis static $name:ident: $ty:ty = $value:expr;)*) => {
+        $(
+            $(#[$meta])*
+            $vis static $name: $crate::state::StaticState<$ty> = $crate::state::StaticState::new($value);
+        )*
+    };
+}
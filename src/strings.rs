@@ -0,0 +1,101 @@
+//! Translated text, picked by the current language
+//! 
+//! [`strings!`](crate::strings!) builds the tables at compile time, and the 
+//! language can be changed at runtime with [`set_lang`].
+//! 
+//! ```ignore
+//! sw4::strings! {
+//!     pub enum Lang { En, Fr }
+//!     pub enum Str {
+//!         Start => ["Start", "Commencer"],
+//!         Quit => ["Quit", "Quitter"],
+//!     }
+//! }
+//! 
+//! sw4::strings::set_lang(Lang::Fr);
+//! w.frame_buffer.text(Str::Start.text(), 10, 10);
+//! ```
+
+/// A set of languages, made by [`strings!`](crate::strings!)
+pub trait Language: Copy + 'static {
+    /// Every language, in the order their strings are given
+    const ALL: &'static [Self];
+
+    fn index(self) -> usize;
+
+    fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+}
+
+/// Keys of a string table, made by [`strings!`](crate::strings!)
+pub trait StringTable: Copy {
+    type Lang: Language;
+
+    /// The text for this key in `lang`
+    fn get(self, lang: Self::Lang) -> &'static str;
+
+    /// The text for this key in the current language
+    fn text(self) -> &'static str {
+        self.get(lang())
+    }
+}
+
+#[allow(deprecated)]
+static CURRENT: crate::SyncUnsafeCell<u8> = crate::SyncUnsafeCell::new(0);
+
+/// Change the language used by [`StringTable::text`]
+pub fn set_lang<L: Language>(lang: L) {
+    unsafe { *CURRENT.get() = lang.index() as u8 }
+}
+
+/// The current language, which is the first one until [`set_lang`] is called
+pub fn lang<L: Language>() -> L {
+    let index = unsafe { *CURRENT.get() } as usize;
+    L::from_index(index).unwrap_or(L::ALL[0])
+}
+
+/// Define a set of languages and a table of strings translated into each
+/// 
+/// Every key must have a string for every language, in the same order as the
+/// languages, or it won't compile. See the [module docs](crate::strings).
+#[macro_export]
+macro_rules! strings {
+    (
+        $(#[$lang_meta:meta])*
+        $lang_vis:vis enum $lang:ident { $($lang_variant:ident),+ $(,)? }
+        $(#[$key_meta:meta])*
+        $key_vis:vis enum $key:ident { $($key_variant:ident => [$($text:expr),+ $(,)?]),+ $(,)? }
+    ) => {
+        $(#[$lang_meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[repr(u8)]
+        $lang_vis enum $lang {
+            $($lang_variant),+
+        }
+
+        impl $crate::strings::Language for $lang {
+            const ALL: &'static [Self] = &[$($lang::$lang_variant),+];
+
+            fn index(self) -> usize {
+                self as usize
+            }
+        }
+
+        $(#[$key_meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        $key_vis enum $key {
+            $($key_variant),+
+        }
+
+        impl $crate::strings::StringTable for $key {
+            type Lang = $lang;
+
+            fn get(self, lang: $lang) -> &'static str {
+                const LANGS: usize = [$(stringify!($lang_variant)),+].len();
+                const TABLE: &[[&str; LANGS]] = &[$([$($text),+]),+];
+                TABLE[self as usize][lang as usize]
+            }
+        }
+    };
+}
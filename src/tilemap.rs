@@ -0,0 +1,247 @@
+//! Grids of tiles, for levels
+//! 
+//! Level files can be turned into [`MapData`] at compile time with 
+//! [`include_map!`](crate::include_map).
+
+use crate::geometry::Vec2;
+use crate::{FrameBuffer, Sprite, SpriteSheet};
+
+/// Where something should be placed when a map is loaded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Spawn {
+    pub name: &'static str,
+    /// In tiles
+    pub x: i32,
+    pub y: i32,
+}
+
+/// A map built into the cart, possibly compressed
+#[derive(Clone, Copy, Debug)]
+pub struct MapData {
+    pub width: u32,
+    pub height: u32,
+    /// One byte per tile, row by row, compressed with 
+    /// [`disk::rle_encode`](crate::disk::rle_encode) if `compressed` is set
+    pub tiles: &'static [u8],
+    pub compressed: bool,
+    pub spawns: &'static [Spawn],
+}
+
+impl MapData {
+    /// The number of bytes the tiles take up when not compressed
+    pub const fn len(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Use the tiles as they are, which only works if they aren't compressed
+    pub fn map(&self) -> TileMap<'static> {
+        crate::assert(!self.compressed, "map data is compressed, use `unpack`");
+        TileMap::new(self.width, self.height, self.tiles)
+    }
+
+    /// Copy or decompress the tiles into `buf`, which must be at least 
+    /// [`MapData::len`] bytes long
+    pub fn unpack<'a>(&self, buf: &'a mut [u8]) -> TileMap<'a> {
+        let len = self.len();
+        crate::assert(buf.len() >= len, "buffer too small for map");
+        let buf = &mut buf[..len];
+        if self.compressed {
+            let unpacked = crate::disk::rle_decode(self.tiles, buf);
+            crate::assert(unpacked == Some(len), "corrupted map data");
+        } else {
+            buf.copy_from_slice(self.tiles);
+        }
+        TileMap::new(self.width, self.height, buf)
+    }
+
+    /// The first spawn point with a name
+    pub fn spawn(&self, name: &str) -> Option<Spawn> {
+        self.spawns.iter().find(|s| s.name == name).copied()
+    }
+}
+
+/// A grid of tiles, one byte each, stored row by row
+#[derive(Clone, Copy, Debug)]
+pub struct TileMap<'a> {
+    width: u32,
+    height: u32,
+    tiles: &'a [u8],
+}
+
+impl<'a> TileMap<'a> {
+    /// Tiles with this value aren't drawn
+    pub const EMPTY: u8 = 0xFF;
+
+    pub const fn new(width: u32, height: u32, tiles: &'a [u8]) -> Self {
+        assert!(tiles.len() >= width as usize * height as usize, "not enough tiles");
+        Self { width, height, tiles }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn tiles(&self) -> &'a [u8] {
+        self.tiles
+    }
+
+    /// The tile at `(x, y)`, or `None` if it's off the map
+    pub fn get(&self, x: i32, y: i32) -> Option<u8> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        Some(self.tiles[y as usize * self.width as usize + x as usize])
+    }
+
+    /// Is the tile at `(x, y)` solid, according to `solid`?
+    /// 
+    /// Everything off the map counts as solid. Made for the collision
+    /// callbacks taken by [`platformer`](crate::platformer) and 
+    /// [`path`](crate::path):
+    /// 
+    /// ```ignore
+    /// body.update(8, |x, y| map.is_solid(x, y, |tile| tile != 0));
+    /// ```
+    pub fn is_solid(&self, x: i32, y: i32, solid: impl Fn(u8) -> bool) -> bool {
+        self.get(x, y).is_none_or(solid)
+    }
+
+    /// Draw the tiles on screen, with the map's top left corner at `offset`
+    /// 
+    /// `tileset` is a sprite with the tiles laid out left to right and top to
+    /// bottom, each `tile_size` pixels square. Only the tiles on screen are 
+    /// drawn, and [`TileMap::EMPTY`] tiles are skipped.
+    pub fn draw(&self, fb: &mut FrameBuffer, tileset: &Sprite, tile_size: u32, offset: Vec2) {
+        crate::assert(tile_size > 0, "tile size must not be 0");
+        let ts = tile_size as i32;
+        let columns = (tileset.width / tile_size).max(1);
+        // Only the tiles overlapping the screen
+        let first_x = (-offset.x).div_euclid(ts).max(0);
+        let first_y = (-offset.y).div_euclid(ts).max(0);
+        let last_x = (159 - offset.x).div_euclid(ts).min(self.width as i32 - 1);
+        let last_y = (159 - offset.y).div_euclid(ts).min(self.height as i32 - 1);
+        for y in first_y..=last_y {
+            for x in first_x..=last_x {
+                let tile = self.tiles[y as usize * self.width as usize + x as usize];
+                if tile == Self::EMPTY {
+                    continue;
+                }
+                let src_x = (tile as u32 % columns) * tile_size;
+                let src_y = (tile as u32 / columns) * tile_size;
+                fb.sub_sprite(
+                    tileset.data,
+                    offset.x + x * ts,
+                    offset.y + y * ts,
+                    tile_size,
+                    tile_size,
+                    src_x,
+                    src_y,
+                    tileset.width,
+                    tileset.flags,
+                );
+            }
+        }
+    }
+
+    /// Draw the tiles in an isometric layout, with the top corner of tile
+    /// `(0, 0)` at `origin`
+    /// 
+    /// Tiles are drawn back to front, so taller tiles overlap the ones behind
+    /// them. Each tile in `tileset` has its bottom lined up with the bottom
+    /// of its diamond, so it may be taller than [`Isometric::tile_height`].
+    /// [`TileMap::EMPTY`] tiles are skipped.
+    pub fn draw_iso(&self, fb: &mut FrameBuffer, tileset: &SpriteSheet, iso: Isometric, origin: Vec2) {
+        self.draw_iso_with(fb, tileset, iso, origin, |_, _, _| {});
+    }
+
+    /// Like [`TileMap::draw_iso`], calling `after_tile` with each tile's 
+    /// position after it's drawn, even if it's empty
+    /// 
+    /// Anything standing on a tile can be drawn there to be sorted along
+    /// with the tiles.
+    pub fn draw_iso_with(
+        &self,
+        fb: &mut FrameBuffer,
+        tileset: &SpriteSheet,
+        iso: Isometric,
+        origin: Vec2,
+        mut after_tile: impl FnMut(&mut FrameBuffer, i32, i32),
+    ) {
+        let (width, height) = (self.width as i32, self.height as i32);
+        let (tile_width, tile_height) = (tileset.tile_width as i32, tileset.tile_height as i32);
+        // Each diagonal going down the screen, with the ones nearer the
+        // bottom drawn later
+        for depth in 0..(width + height - 1).max(0) {
+            let first_x = (depth - height + 1).max(0);
+            let last_x = depth.min(width - 1);
+            for x in first_x..=last_x {
+                let y = depth - x;
+                let tile = self.tiles[y as usize * self.width as usize + x as usize];
+                if tile != Self::EMPTY {
+                    let top = iso.tile_to_screen(Vec2::new(x, y), origin);
+                    let sx = top.x - iso.tile_width as i32 / 2;
+                    let sy = top.y + iso.tile_height as i32 - tile_height;
+                    let on_screen = sx < 160 && sy < 160 && sx + tile_width > 0 && sy + tile_height > 0;
+                    if on_screen {
+                        tileset.draw(fb, tile as u32, sx, sy);
+                    }
+                }
+                after_tile(fb, x, y);
+            }
+        }
+    }
+}
+
+/// The size of the diamond each tile covers in an isometric layout
+/// 
+/// The layout has `x` going down and to the right, and `y` going down and to
+/// the left. Even sizes keep every tile on whole pixels, and the usual shape
+/// is twice as wide as it is tall.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Isometric {
+    pub tile_width: u32,
+    pub tile_height: u32,
+}
+
+impl Isometric {
+    pub const fn new(tile_width: u32, tile_height: u32) -> Self {
+        assert!(tile_width > 0 && tile_height > 0, "tile size must not be 0");
+        Self { tile_width, tile_height }
+    }
+
+    /// The screen position of the top corner of a tile's diamond, with tile
+    /// `(0, 0)` at `origin`
+    pub const fn tile_to_screen(&self, tile: Vec2, origin: Vec2) -> Vec2 {
+        Vec2::new(
+            origin.x + (tile.x - tile.y) * self.tile_width as i32 / 2,
+            origin.y + (tile.x + tile.y) * self.tile_height as i32 / 2,
+        )
+    }
+
+    /// The tile whose diamond covers a screen position, with tile `(0, 0)`
+    /// at `origin`
+    /// 
+    /// The tile may be off the map, see [`TileMap::get`].
+    pub const fn screen_to_tile(&self, point: Vec2, origin: Vec2) -> Vec2 {
+        let (w, h) = (self.tile_width as i32, self.tile_height as i32);
+        let (px, py) = (point.x - origin.x, point.y - origin.y);
+        Vec2::new(
+            (px * h + py * w).div_euclid(w * h),
+            (py * w - px * h).div_euclid(w * h),
+        )
+    }
+
+    /// The screen position of the center of a tile's diamond
+    pub const fn tile_center(&self, tile: Vec2, origin: Vec2) -> Vec2 {
+        let top = self.tile_to_screen(tile, origin);
+        Vec2::new(top.x, top.y + self.tile_height as i32 / 2)
+    }
+}
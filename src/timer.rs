@@ -0,0 +1,351 @@
+//! Timers and blinking counted in frames
+//! 
+//! Call `tick` once per frame, and it reports what fired on that frame.
+
+use crate::fixed::Fixed;
+
+/// A countdown in frames, which can fire once or repeatedly
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timer {
+    remaining: u32,
+    duration: u32,
+    repeating: bool,
+    running: bool,
+}
+
+impl Timer {
+    /// A timer that fires once, after `frames` ticks
+    pub const fn once(frames: u32) -> Self {
+        Self {
+            remaining: frames,
+            duration: frames,
+            repeating: false,
+            running: true,
+        }
+    }
+
+    /// A timer that fires every `frames` ticks
+    pub const fn repeating(frames: u32) -> Self {
+        Self {
+            remaining: frames,
+            duration: frames,
+            repeating: true,
+            running: true,
+        }
+    }
+
+    /// A one-shot timer that has already finished, until it's restarted
+    pub const fn stopped(frames: u32) -> Self {
+        Self {
+            remaining: 0,
+            duration: frames,
+            repeating: false,
+            running: false,
+        }
+    }
+
+    /// Advance a frame, returning whether the timer fired
+    pub fn tick(&mut self) -> bool {
+        if !self.running {
+            return false;
+        }
+        self.remaining = self.remaining.saturating_sub(1);
+        if self.remaining > 0 {
+            return false;
+        }
+        if self.repeating {
+            self.remaining = self.duration.max(1);
+        } else {
+            self.running = false;
+        }
+        true
+    }
+
+    /// Start counting down from the full duration again
+    pub fn restart(&mut self) {
+        self.remaining = self.duration;
+        self.running = true;
+    }
+
+    /// Stop the timer without it firing
+    pub fn stop(&mut self) {
+        self.running = false;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Has a one-shot timer fired, or a timer been stopped?
+    pub fn is_finished(&self) -> bool {
+        !self.running
+    }
+
+    /// Frames left until the timer next fires
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    pub fn duration(&self) -> u32 {
+        self.duration
+    }
+
+    /// How far through its countdown the timer is, from 0 to 1
+    pub fn progress(&self) -> Fixed {
+        if !self.running || self.duration == 0 {
+            return Fixed::ONE;
+        }
+        let elapsed = self.duration - self.remaining.min(self.duration);
+        Fixed::from_ratio(elapsed as i32, self.duration as i32)
+    }
+}
+
+/// Whether something blinking with a period of `period` frames is showing on
+/// `frame`
+/// 
+/// It's shown for the first half of each period, so
+/// `blink(sw4::frame(), 60)` is on for 30 frames then off for 30, like a
+/// "press start" prompt. A period of 0 is always shown.
+pub fn blink(frame: u64, period: u32) -> bool {
+    let period = period as u64;
+    period == 0 || frame % period < period.div_ceil(2)
+}
+
+/// A flash that blinks for a while after being triggered, like a player
+/// flickering after being hit
+/// 
+/// Call [`Flash::tick`] once per frame.
+/// 
+/// ```ignore
+/// let mut hurt = Flash::new(60, 8);
+/// 
+/// // When hit
+/// hurt.trigger();
+/// 
+/// // Every frame
+/// hurt.tick();
+/// if hurt.visible() {
+///     w.frame_buffer.sprite_at(&PLAYER, pos);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Flash {
+    remaining: u32,
+    duration: u32,
+    period: u32,
+}
+
+impl Flash {
+    /// A flash lasting `frames` frames once triggered, blinking with a period
+    /// of `period` frames
+    pub const fn new(frames: u32, period: u32) -> Self {
+        Self {
+            remaining: 0,
+            duration: frames,
+            period,
+        }
+    }
+
+    /// Start the flash from the beginning
+    pub fn trigger(&mut self) {
+        self.remaining = self.duration;
+    }
+
+    /// End the flash early
+    pub fn stop(&mut self) {
+        self.remaining = 0;
+    }
+
+    /// Advance a frame
+    pub fn tick(&mut self) {
+        self.remaining = self.remaining.saturating_sub(1);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0
+    }
+
+    // Flashing on this frame, which the first frame always is
+    fn on(&self) -> bool {
+        self.is_active() && blink((self.duration - self.remaining) as u64, self.period)
+    }
+
+    /// Whether to draw the flashing thing this frame, which blinks off while
+    /// the flash is active
+    pub fn visible(&self) -> bool {
+        !self.on()
+    }
+
+    /// Whether to draw the flashing thing inverted or in a highlight color
+    /// this frame, for flashing instead of disappearing
+    pub fn inverted(&self) -> bool {
+        self.on()
+    }
+
+    /// How much of the flash is left, going from 1 when triggered down to 0,
+    /// for fading it out
+    pub fn intensity(&self) -> Fixed {
+        if self.duration == 0 {
+            return Fixed::ZERO;
+        }
+        Fixed::from_ratio(self.remaining as i32, self.duration as i32)
+    }
+}
+
+/// Identifies an event scheduled on a [`Scheduler`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimerId {
+    index: u16,
+    generation: u16,
+}
+
+#[derive(Clone, Copy)]
+struct Slot<E> {
+    event: E,
+    timer: Timer,
+    fired: bool,
+}
+
+/// Up to `N` events, each fired after a delay or repeatedly
+/// 
+/// Events can be any `Copy` type, such as an enum of things that can happen,
+/// or a `fn` to call as a callback.
+/// 
+/// ```ignore
+/// let mut timers = Scheduler::<Event, 8>::new();
+/// timers.after(60, Event::SpawnEnemy);
+/// timers.every(30, Event::Blink);
+/// 
+/// // Every frame
+/// for event in timers.tick() {
+///     match event { ... }
+/// }
+/// ```
+pub struct Scheduler<E, const N: usize> {
+    slots: [Option<Slot<E>>; N],
+    generations: [u16; N],
+}
+
+impl<E: Copy, const N: usize> Scheduler<E, N> {
+    pub const fn new() -> Self {
+        Self {
+            slots: [None; N],
+            generations: [0; N],
+        }
+    }
+
+    /// Fire `event` once, `frames` ticks from now
+    /// 
+    /// Returns `None` if all `N` slots are in use
+    pub fn after(&mut self, frames: u32, event: E) -> Option<TimerId> {
+        self.add(Timer::once(frames), event)
+    }
+
+    /// Fire `event` every `frames` ticks, until cancelled
+    /// 
+    /// Returns `None` if all `N` slots are in use
+    pub fn every(&mut self, frames: u32, event: E) -> Option<TimerId> {
+        self.add(Timer::repeating(frames), event)
+    }
+
+    fn add(&mut self, timer: Timer, event: E) -> Option<TimerId> {
+        let index = self.slots.iter().position(|slot| slot.is_none())?;
+        self.slots[index] = Some(Slot {
+            event,
+            timer,
+            fired: false,
+        });
+        Some(TimerId {
+            index: index as u16,
+            generation: self.generations[index],
+        })
+    }
+
+    fn slot(&self, id: TimerId) -> Option<&Slot<E>> {
+        let index = id.index as usize;
+        if self.generations.get(index) != Some(&id.generation) {
+            return None;
+        }
+        self.slots[index].as_ref()
+    }
+
+    fn free(&mut self, index: usize) {
+        self.slots[index] = None;
+        self.generations[index] = self.generations[index].wrapping_add(1);
+    }
+
+    /// Stop an event from firing, returning whether it was still scheduled
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        if self.slot(id).is_none() {
+            return false;
+        }
+        self.free(id.index as usize);
+        true
+    }
+
+    /// Is the event still waiting to fire, or repeating?
+    pub fn is_scheduled(&self, id: TimerId) -> bool {
+        self.slot(id).is_some_and(|slot| slot.timer.is_running())
+    }
+
+    /// Frames left until the event next fires
+    pub fn remaining(&self, id: TimerId) -> Option<u32> {
+        self.slot(id)
+            .filter(|slot| slot.timer.is_running())
+            .map(|slot| slot.timer.remaining())
+    }
+
+    /// Advance a frame, giving the events that fired on it
+    pub fn tick(&mut self) -> impl Iterator<Item = E> + '_ {
+        for index in 0..N {
+            // One-shot events are kept until the next tick so they can be 
+            // reported, then freed
+            if self.slots[index].is_some_and(|slot| slot.fired && slot.timer.is_finished()) {
+                self.free(index);
+            }
+            if let Some(slot) = &mut self.slots[index] {
+                slot.fired = slot.timer.tick();
+            }
+        }
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|slot| slot.fired)
+            .map(|slot| slot.event)
+    }
+
+    /// Advance a frame, calling `f` with each event that fired on it
+    pub fn tick_with(&mut self, mut f: impl FnMut(E)) {
+        for event in self.tick() {
+            f(event);
+        }
+    }
+
+    /// The number of scheduled events
+    pub fn len(&self) -> usize {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|slot| slot.timer.is_running())
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cancel every event
+    pub fn clear(&mut self) {
+        for index in 0..N {
+            if self.slots[index].is_some() {
+                self.free(index);
+            }
+        }
+    }
+}
+
+impl<E: Copy, const N: usize> Default for Scheduler<E, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,246 @@
+//! Immediate-mode UI widgets, for settings screens and level selects
+//! 
+//! Widgets are laid out top to bottom, and are both drawn and checked for 
+//! input when they're called, so there's no widget tree to keep in sync.
+//! Focus moves with up and down on the gamepad or by pointing with the mouse,
+//! and widgets are used with X or a left click.
+//! 
+//! ```ignore
+//! let mut ui = state.ui.begin(w, 10, 10);
+//! ui.label("Settings");
+//! ui.toggle("Music", &mut state.music);
+//! ui.slider("Volume", &mut state.volume, 0, 100);
+//! if ui.button("Back") {
+//!     ...
+//! }
+//! ```
+
+use crate::input::{GamepadTracker, MouseTracker, Repeater};
+use crate::{read_draw_colors, write_draw_colors, Button, FrameBuffer, MouseButton, Wasm4};
+
+/// The height of each row of widgets
+pub const ROW_HEIGHT: i32 = 10;
+/// The width of a slider's track
+pub const SLIDER_WIDTH: u32 = 48;
+
+/// What a [`Ui`] remembers between frames
+pub struct UiState {
+    focus: usize,
+    // How many focusable widgets there were last frame
+    widgets: usize,
+    gamepad: GamepadTracker,
+    repeater: Repeater,
+    mouse: MouseTracker,
+}
+
+impl UiState {
+    pub const fn new() -> Self {
+        Self {
+            focus: 0,
+            widgets: 0,
+            gamepad: GamepadTracker::new(),
+            repeater: Repeater::new(20, 5),
+            mouse: MouseTracker::new(),
+        }
+    }
+
+    /// Start this frame's UI, with the first widget at `(x, y)`
+    /// 
+    /// Input is taken from the first gamepad and the mouse
+    pub fn begin<'a>(&'a mut self, w: &'a mut Wasm4, x: i32, y: i32) -> Ui<'a> {
+        self.gamepad.update(&w.gamepads[0]);
+        self.repeater.update(&w.gamepads[0]);
+        self.mouse.update(&w.mouse);
+        if self.widgets > 0 {
+            if self.repeater.fired(Button::Up) {
+                self.focus = (self.focus + self.widgets - 1) % self.widgets;
+            }
+            if self.repeater.fired(Button::Down) {
+                self.focus = (self.focus + 1) % self.widgets;
+            }
+            self.focus = self.focus.min(self.widgets - 1);
+        }
+        Ui {
+            state: self,
+            fb: &mut w.frame_buffer,
+            x,
+            y,
+            index: 0,
+        }
+    }
+
+    /// The index of the focused widget, counting only widgets that can be 
+    /// focused
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    pub fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+    }
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lays out and draws widgets for one frame
+/// 
+/// Text is drawn with draw color 1, and the focused widget is highlighted 
+/// with draw color 2.
+pub struct Ui<'a> {
+    state: &'a mut UiState,
+    fb: &'a mut FrameBuffer,
+    x: i32,
+    y: i32,
+    index: usize,
+}
+
+// What happened to a focusable widget this frame
+struct Interaction {
+    focused: bool,
+    activated: bool,
+    hovered: bool,
+}
+
+impl Ui<'_> {
+    /// Text that can't be focused
+    pub fn label(&mut self, s: &str) {
+        self.text(s, self.x, self.y);
+        self.y += ROW_HEIGHT;
+    }
+
+    /// Add empty space before the next widget
+    pub fn space(&mut self, pixels: i32) {
+        self.y += pixels;
+    }
+
+    /// A button, returning whether it was pressed
+    pub fn button(&mut self, s: &str) -> bool {
+        let interaction = self.widget(text_width(s));
+        self.text(s, self.x, self.y);
+        self.y += ROW_HEIGHT;
+        interaction.activated
+    }
+
+    /// A checkbox, returning whether `value` was changed
+    pub fn toggle(&mut self, s: &str, value: &mut bool) -> bool {
+        let interaction = self.widget(text_width(s) + 32);
+        self.text(if *value { "[x]" } else { "[ ]" }, self.x, self.y);
+        self.text(s, self.x + 32, self.y);
+        self.y += ROW_HEIGHT;
+        if interaction.activated {
+            *value = !*value;
+        }
+        interaction.activated
+    }
+
+    /// A slider between `min` and `max` inclusive, returning whether `value` 
+    /// was changed
+    /// 
+    /// Left and right move it by one step, and the mouse can click or drag 
+    /// along its track.
+    pub fn slider(&mut self, s: &str, value: &mut i32, min: i32, max: i32) -> bool {
+        let track_x = self.x + text_width(s) as i32 + 4;
+        let interaction = self.widget(text_width(s) + 4 + SLIDER_WIDTH);
+        let old = *value;
+        if interaction.focused {
+            if self.state.repeater.fired(Button::Left) {
+                *value -= 1;
+            }
+            if self.state.repeater.fired(Button::Right) {
+                *value += 1;
+            }
+            let mouse = &self.state.mouse;
+            if interaction.hovered && mouse.held(MouseButton::Left) && max > min {
+                let offset = (mouse.x() as i32 - track_x).clamp(0, SLIDER_WIDTH as i32 - 1);
+                let range = (max - min) as i64;
+                let steps = offset as i64 * range + (SLIDER_WIDTH as i64 - 1) / 2;
+                *value = min + (steps / (SLIDER_WIDTH as i64 - 1)) as i32;
+            }
+        }
+        *value = (*value).clamp(min, max.max(min));
+
+        self.text(s, self.x, self.y);
+        let colors = read_draw_colors();
+        write_draw_colors(colors & 0xF);
+        self.fb.hline(track_x, self.y + 3, SLIDER_WIDTH);
+        let knob = if max > min {
+            ((*value - min) as i64 * (SLIDER_WIDTH as i64 - 2) / (max - min) as i64) as i32
+        } else {
+            0
+        };
+        self.fb.rect(track_x + knob, self.y, 2, 8);
+        write_draw_colors(colors);
+        self.y += ROW_HEIGHT;
+        *value != old
+    }
+
+    /// One row per item, returning whether `selected` was changed
+    /// 
+    /// The selected item is marked with a `>`
+    pub fn list(&mut self, items: &[&str], selected: &mut usize) -> bool {
+        let old = *selected;
+        for (i, item) in items.iter().enumerate() {
+            let interaction = self.widget(text_width(item) + 16);
+            if interaction.activated {
+                *selected = i;
+            }
+            if *selected == i {
+                self.text(">", self.x, self.y);
+            }
+            self.text(item, self.x + 16, self.y);
+            self.y += ROW_HEIGHT;
+        }
+        *selected != old
+    }
+
+    /// Lay out the next focusable widget
+    fn widget(&mut self, width: u32) -> Interaction {
+        let index = self.index;
+        self.index += 1;
+        let mouse = &self.state.mouse;
+        let (mx, my) = (mouse.x() as i32, mouse.y() as i32);
+        let hovered = (self.x - 1..self.x + width as i32 + 1).contains(&mx)
+            && (self.y - 1..self.y + ROW_HEIGHT - 1).contains(&my);
+        // Only steal focus when the mouse moves, so a resting cursor doesn't
+        // fight the gamepad
+        if hovered && (mouse.moved() || mouse.clicked(MouseButton::Left)) {
+            self.state.focus = index;
+        }
+        let focused = self.state.focus == index;
+        let activated = focused
+            && (self.state.gamepad.just_pressed(Button::X)
+                || (hovered && mouse.clicked(MouseButton::Left)));
+        if focused {
+            let colors = read_draw_colors();
+            write_draw_colors((colors >> 4) & 0xF);
+            self.fb.rect(self.x - 1, self.y - 1, width + 2, ROW_HEIGHT as u32);
+            write_draw_colors(colors);
+        }
+        Interaction {
+            focused,
+            activated,
+            hovered,
+        }
+    }
+
+    fn text(&mut self, s: &str, x: i32, y: i32) {
+        let colors = read_draw_colors();
+        write_draw_colors(colors & 0xF);
+        self.fb.text(s, x, y);
+        write_draw_colors(colors);
+    }
+}
+
+impl Drop for Ui<'_> {
+    fn drop(&mut self) {
+        self.state.widgets = self.index;
+    }
+}
+
+fn text_width(s: &str) -> u32 {
+    crate::text_size(s).0
+}
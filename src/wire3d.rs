@@ -0,0 +1,242 @@
+//! Wireframe 3D, with fixed-point math
+//! 
+//! Space is laid out like the screen, with `x` going right, `y` going down,
+//! and `z` going into the screen. Meshes are a list of points and the edges
+//! between them, which are drawn as lines with [`FrameBuffer::line`].
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+use crate::fixed::Fixed;
+use crate::geometry::Vec2;
+use crate::FrameBuffer;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Vec3 {
+    pub x: Fixed,
+    pub y: Fixed,
+    pub z: Fixed,
+}
+
+impl Vec3 {
+    pub const ZERO: Self = Self::new(Fixed::ZERO, Fixed::ZERO, Fixed::ZERO);
+
+    pub const fn new(x: Fixed, y: Fixed, z: Fixed) -> Self {
+        Self { x, y, z }
+    }
+
+    /// A point with whole number coordinates
+    pub const fn from_ints(x: i32, y: i32, z: i32) -> Self {
+        Self::new(Fixed::from_int(x), Fixed::from_int(y), Fixed::from_int(z))
+    }
+
+    pub fn dot(self, other: Self) -> Fixed {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Mul<Fixed> for Vec3 {
+    type Output = Self;
+
+    fn mul(self, rhs: Fixed) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+/// A 4x4 transformation matrix, stored row by row
+/// 
+/// Points are treated as columns, so `a * b` applies `b` first and then `a`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Mat4(pub [[Fixed; 4]; 4]);
+
+impl Mat4 {
+    pub const IDENTITY: Self = Self([
+        [Fixed::ONE, Fixed::ZERO, Fixed::ZERO, Fixed::ZERO],
+        [Fixed::ZERO, Fixed::ONE, Fixed::ZERO, Fixed::ZERO],
+        [Fixed::ZERO, Fixed::ZERO, Fixed::ONE, Fixed::ZERO],
+        [Fixed::ZERO, Fixed::ZERO, Fixed::ZERO, Fixed::ONE],
+    ]);
+
+    pub const fn translation(offset: Vec3) -> Self {
+        let mut m = Self::IDENTITY;
+        m.0[0][3] = offset.x;
+        m.0[1][3] = offset.y;
+        m.0[2][3] = offset.z;
+        m
+    }
+
+    pub const fn scale(x: Fixed, y: Fixed, z: Fixed) -> Self {
+        let mut m = Self::IDENTITY;
+        m.0[0][0] = x;
+        m.0[1][1] = y;
+        m.0[2][2] = z;
+        m
+    }
+
+    /// Rotate around the `x` axis, by an angle in turns
+    pub fn rotation_x(turns: Fixed) -> Self {
+        let (s, c) = (turns.sin_turns(), turns.cos_turns());
+        let mut m = Self::IDENTITY;
+        m.0[1][1] = c;
+        m.0[1][2] = -s;
+        m.0[2][1] = s;
+        m.0[2][2] = c;
+        m
+    }
+
+    /// Rotate around the `y` axis, by an angle in turns
+    pub fn rotation_y(turns: Fixed) -> Self {
+        let (s, c) = (turns.sin_turns(), turns.cos_turns());
+        let mut m = Self::IDENTITY;
+        m.0[0][0] = c;
+        m.0[0][2] = s;
+        m.0[2][0] = -s;
+        m.0[2][2] = c;
+        m
+    }
+
+    /// Rotate around the `z` axis, by an angle in turns
+    pub fn rotation_z(turns: Fixed) -> Self {
+        let (s, c) = (turns.sin_turns(), turns.cos_turns());
+        let mut m = Self::IDENTITY;
+        m.0[0][0] = c;
+        m.0[0][1] = -s;
+        m.0[1][0] = s;
+        m.0[1][1] = c;
+        m
+    }
+
+    /// Transform a point, including the translation
+    /// 
+    /// The bottom row is ignored, so this is only right for affine
+    /// transformations, which is everything made by this type.
+    pub fn transform(&self, p: Vec3) -> Vec3 {
+        let row = |r: &[Fixed; 4]| r[0] * p.x + r[1] * p.y + r[2] * p.z + r[3];
+        Vec3::new(row(&self.0[0]), row(&self.0[1]), row(&self.0[2]))
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut out = [[Fixed::ZERO; 4]; 4];
+        for (i, row) in out.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..4).fold(Fixed::ZERO, |sum, k| sum + self.0[i][k] * rhs.0[k][j]);
+            }
+        }
+        Self(out)
+    }
+}
+
+/// Points and the edges between them
+/// 
+/// Each edge is a pair of indices into `vertices`.
+#[derive(Clone, Copy, Debug)]
+pub struct Mesh<'a> {
+    pub vertices: &'a [Vec3],
+    pub edges: &'a [(u16, u16)],
+}
+
+impl<'a> Mesh<'a> {
+    pub const fn new(vertices: &'a [Vec3], edges: &'a [(u16, u16)]) -> Self {
+        Self { vertices, edges }
+    }
+}
+
+/// How points in front of the viewer map to the screen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Projection {
+    /// Where points straight ahead end up
+    pub center: Vec2,
+    /// How many pixels across one unit is, one unit away
+    pub focal: Fixed,
+    /// Anything closer than this is cut off
+    pub near: Fixed,
+}
+
+impl Projection {
+    /// Centered on the screen, about 77 degrees across
+    pub const SCREEN: Self = Self {
+        center: Vec2::new(80, 80),
+        focal: Fixed::from_int(100),
+        near: Fixed::from_ratio(1, 16),
+    };
+
+    /// Where a point ends up on the screen, or `None` if it's closer than
+    /// `near`
+    pub fn project(&self, p: Vec3) -> Option<Vec2> {
+        if p.z < self.near {
+            return None;
+        }
+        // In 64 bits, since points close to the viewer end up far off screen
+        let scale = |v: Fixed| {
+            let scaled = v.to_bits() as i64 * self.focal.to_bits() as i64 / p.z.to_bits() as i64;
+            ((scaled + (1 << (Fixed::FRAC_BITS - 1))) >> Fixed::FRAC_BITS).clamp(-0x8000, 0x7FFF) as i32
+        };
+        Some(Vec2::new(self.center.x + scale(p.x), self.center.y + scale(p.y)))
+    }
+}
+
+/// Draw every edge of `mesh` after moving it with `transform`, in draw color 1
+/// 
+/// `transform` takes the mesh into view space, where the viewer is at the
+/// origin looking along `z`. Edges crossing `near` are cut off there.
+pub fn draw_mesh(fb: &mut FrameBuffer, mesh: &Mesh, transform: &Mat4, projection: &Projection) {
+    for &(a, b) in mesh.edges {
+        let a = transform.transform(mesh.vertices[a as usize]);
+        let b = transform.transform(mesh.vertices[b as usize]);
+        draw_line(fb, a, b, projection);
+    }
+}
+
+/// Draw a line between two points in view space, in draw color 1
+pub fn draw_line(fb: &mut FrameBuffer, mut a: Vec3, mut b: Vec3, projection: &Projection) {
+    let near = projection.near;
+    if a.z < near && b.z < near {
+        return;
+    }
+    // Move whichever end is too close up to the near plane
+    if a.z < near {
+        a = a + (b - a) * ((near - a.z) / (b.z - a.z));
+        a.z = near;
+    } else if b.z < near {
+        b = b + (a - b) * ((near - b.z) / (a.z - b.z));
+        b.z = near;
+    }
+    if let (Some(a), Some(b)) = (projection.project(a), projection.project(b)) {
+        fb.line_v(a, b);
+    }
+}